@@ -12,6 +12,9 @@ use plonky::{blake_hash_usize_to_curve, fft_precompute, msm_precompute, AffinePo
 
 pub const SECURITY_BITS: usize = 128;
 
+/// The domain separator of Plookup proof transcripts.
+pub const DOMAIN_SEPARATOR: &str = "plookup/v1";
+
 /// Computes a proof that `t` is a sub(multi)set of `f`, following the Plookup protocol (https://ia.cr/2020/315).
 pub fn prove<C: HaloCurve>(f: &[C::ScalarField], t: &[C::ScalarField]) -> Result<PlookupProof<C>> {
     let (n, f, t) = pad_inputs(f, t);
@@ -22,7 +25,7 @@ pub fn prove<C: HaloCurve>(f: &[C::ScalarField], t: &[C::ScalarField]) -> Result
     sort_by(&mut s, &t);
 
     // Initiate the Fiat-Shamir challenger.
-    let mut challenger = Challenger::new(SECURITY_BITS);
+    let mut challenger: Challenger<_> = Challenger::new(DOMAIN_SEPARATOR, SECURITY_BITS);
 
     // FFT precomputation on the cyclic subgroup of order `n+1`.
     let fft_precomputation = fft_precompute(n + 1);
@@ -51,13 +54,17 @@ pub fn prove<C: HaloCurve>(f: &[C::ScalarField], t: &[C::ScalarField]) -> Result
 
     // Observe the commitments to get verifier challenges.
     // `beta` and `gamma` are used to construct the Plookup grand product.
-    challenger.observe_affine_points(&[
-        c_f.to_affine(),
-        c_t.to_affine(),
-        c_h1.to_affine(),
-        c_h2.to_affine(),
-    ]);
-    let (beta_bf, gamma_bf) = challenger.get_2_challenges();
+    challenger.append_affine_points(
+        "commitments",
+        &[
+            c_f.to_affine(),
+            c_t.to_affine(),
+            c_h1.to_affine(),
+            c_h2.to_affine(),
+        ],
+    );
+    let beta_bf = challenger.challenge("beta");
+    let gamma_bf = challenger.challenge("gamma");
     let beta = C::BaseField::try_convert(&beta_bf).unwrap();
     let gamma = C::BaseField::try_convert(&gamma_bf).unwrap();
 
@@ -69,8 +76,8 @@ pub fn prove<C: HaloCurve>(f: &[C::ScalarField], t: &[C::ScalarField]) -> Result
 
     // Observe the commitment to get a verifier challenge.
     // `alpha` is used to batch all vanishing polynomials.
-    challenger.observe_affine_point(c_z.to_affine());
-    let alpha_bf = challenger.get_challenge();
+    challenger.append_affine_point("c_z", c_z.to_affine());
+    let alpha_bf = challenger.challenge("alpha");
     let alpha = C::BaseField::try_convert(&alpha_bf).unwrap();
 
     // Compute the coefficients of the "vanishing polynomial".
@@ -89,8 +96,8 @@ pub fn prove<C: HaloCurve>(f: &[C::ScalarField], t: &[C::ScalarField]) -> Result
 
     // Observe the commitment to get a verifier challenge.
     // `zeta` is the point at which we'll open all polynomials.
-    challenger.observe_affine_point(c_quotient.to_affine());
-    let zeta_bf = challenger.get_challenge();
+    challenger.append_affine_point("c_quotient", c_quotient.to_affine());
+    let zeta_bf = challenger.challenge("zeta");
     let zeta: C::ScalarField = C::BaseField::try_convert(&zeta_bf).unwrap();
 
     // Open all polynomials.
@@ -116,8 +123,10 @@ pub fn prove<C: HaloCurve>(f: &[C::ScalarField], t: &[C::ScalarField]) -> Result
                 .expect("For now, we assume that all opened values fit in both fields")
         })
         .collect();
-    challenger.observe_elements(&openings_bf);
-    let (v_bf, u_bf, u_scaling_bf) = challenger.get_3_challenges();
+    challenger.append_message("openings", &openings_bf);
+    let v_bf = challenger.challenge("v");
+    let u_bf = challenger.challenge("u");
+    let u_scaling_bf = challenger.challenge("u_scaling");
     let v = v_bf.try_convert::<C::ScalarField>()?;
     let u = u_bf.try_convert::<C::ScalarField>()?;
     let u_scaling = u_scaling_bf.try_convert::<C::ScalarField>()?;
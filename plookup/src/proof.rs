@@ -1,5 +1,5 @@
 use crate::openings::PlookupOpenings;
-use crate::plookup::SECURITY_BITS;
+use crate::plookup::{DOMAIN_SEPARATOR, SECURITY_BITS};
 use anyhow::{anyhow, Result};
 use plonky::halo::OpeningProof;
 use plonky::plonk_challenger::Challenger;
@@ -65,17 +65,18 @@ pub struct PlookupProofChallenge<C: Curve> {
 
 impl<C: HaloCurve> PlookupProof<C> {
     pub fn get_challenges(&self) -> Result<PlookupProofChallenge<C>> {
-        let mut challenger = Challenger::new(SECURITY_BITS);
+        let mut challenger: Challenger<_> = Challenger::new(DOMAIN_SEPARATOR, SECURITY_BITS);
         let error_msg = "Conversion from base to scalar field failed.";
-        challenger.observe_affine_points(&[self.c_f, self.c_t, self.c_h1, self.c_h2]);
-        let (beta_bf, gamma_bf) = challenger.get_2_challenges();
+        challenger.append_affine_points("commitments", &[self.c_f, self.c_t, self.c_h1, self.c_h2]);
+        let beta_bf = challenger.challenge("beta");
+        let gamma_bf = challenger.challenge("gamma");
         let beta = C::try_convert_b2s(beta_bf).map_err(|_| anyhow!(error_msg))?;
         let gamma = C::try_convert_b2s(gamma_bf).map_err(|_| anyhow!(error_msg))?;
-        challenger.observe_affine_point(self.c_z);
-        let alpha_bf = challenger.get_challenge();
+        challenger.append_affine_point("c_z", self.c_z);
+        let alpha_bf = challenger.challenge("alpha");
         let alpha = C::try_convert_b2s(alpha_bf).map_err(|_| anyhow!(error_msg))?;
-        challenger.observe_affine_point(self.c_quotient);
-        let zeta_bf = challenger.get_challenge();
+        challenger.append_affine_point("c_quotient", self.c_quotient);
+        let zeta_bf = challenger.challenge("zeta");
         let zeta = C::try_convert_b2s(zeta_bf).map_err(|_| anyhow!(error_msg))?;
         let openings_bf: Vec<_> = self
             .openings
@@ -86,8 +87,10 @@ impl<C: HaloCurve> PlookupProof<C> {
                     .expect("For now, we assume that all opened values fit in both fields")
             })
             .collect();
-        challenger.observe_elements(&openings_bf);
-        let (v_bf, u_bf, u_scaling_bf) = challenger.get_3_challenges();
+        challenger.append_message("openings", &openings_bf);
+        let v_bf = challenger.challenge("v");
+        let u_bf = challenger.challenge("u");
+        let u_scaling_bf = challenger.challenge("u_scaling");
         let v = C::try_convert_b2s(v_bf).map_err(|_| anyhow!(error_msg))?;
         let u = C::try_convert_b2s(u_bf).map_err(|_| anyhow!(error_msg))?;
         let u_scaling = C::try_convert_b2s(u_scaling_bf).map_err(|_| anyhow!(error_msg))?;
@@ -95,9 +98,11 @@ impl<C: HaloCurve> PlookupProof<C> {
         // Compute IPA challenges.
         let mut halo_us = Vec::new();
         for i in 0..self.halo_proof.halo_l.len() {
-            challenger
-                .observe_affine_points(&[self.halo_proof.halo_l[i], self.halo_proof.halo_r[i]]);
-            let r_bf = challenger.get_challenge();
+            challenger.append_affine_points(
+                "halo_lr",
+                &[self.halo_proof.halo_l[i], self.halo_proof.halo_r[i]],
+            );
+            let r_bf = challenger.challenge("halo_challenge");
             let r_sf = r_bf.try_convert::<C::ScalarField>()?;
             let r_bits = &r_sf.to_canonical_bool_vec()[..SECURITY_BITS];
             let u_j_squared = halo_n::<C>(r_bits);
@@ -108,8 +113,8 @@ impl<C: HaloCurve> PlookupProof<C> {
         }
 
         // Compute challenge for Schnorr protocol.
-        challenger.observe_affine_point(self.halo_proof.schnorr_proof.r);
-        let schnorr_challenge_bf = challenger.get_challenge();
+        challenger.append_affine_point("schnorr_r", self.halo_proof.schnorr_proof.r);
+        let schnorr_challenge_bf = challenger.challenge("schnorr");
         let schnorr_challenge =
             C::try_convert_b2s(schnorr_challenge_bf).map_err(|_| anyhow!(error_msg))?;
 
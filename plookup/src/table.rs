@@ -52,11 +52,11 @@ impl<F: Field, const N: usize> Table<F, N> {
 
     /// Get a verifier challenge from a `Table`. Used to reduce a `Table` to a vector.
     fn get_challenge(&self) -> F {
-        let mut challenger = Challenger::new(SECURITY_BITS);
+        let mut challenger: Challenger<_> = Challenger::new("plookup/table/v1", SECURITY_BITS);
         for a in &self.0 {
-            challenger.observe_elements(a);
+            challenger.append_message("row", a);
         }
-        challenger.get_challenge()
+        challenger.challenge("reduction")
     }
 
     /// Reduces a `Table` to a vector using a random challenge.
@@ -54,6 +54,36 @@ pub(crate) fn sub<const N: usize>(a: [u64; N], b: [u64; N]) -> [u64; N] {
     difference
 }
 
+/// Computes `a + b` modulo `2^(64 N)`, along with a flag indicating whether the addition overflowed.
+pub(crate) const fn overflowing_add<const N: usize>(a: [u64; N], b: [u64; N]) -> ([u64; N], bool) {
+    let mut carry = false;
+    let mut sum = [0; N];
+    let mut i = 0;
+    while i < N {
+        let result1 = a[i].overflowing_add(b[i]);
+        let result2 = result1.0.overflowing_add(carry as u64);
+        sum[i] = result2.0;
+        carry = result1.1 | result2.1;
+        i += 1;
+    }
+    (sum, carry)
+}
+
+/// Computes `a - b` modulo `2^(64 N)`.
+pub(crate) const fn wrapping_sub<const N: usize>(a: [u64; N], b: [u64; N]) -> [u64; N] {
+    let mut borrow = false;
+    let mut difference = [0; N];
+    let mut i = 0;
+    while i < N {
+        let result1 = a[i].overflowing_sub(b[i]);
+        let result2 = result1.0.overflowing_sub(borrow as u64);
+        difference[i] = result2.0;
+        borrow = result1.1 | result2.1;
+        i += 1;
+    }
+    difference
+}
+
 #[inline(always)]
 pub(crate) fn is_even<const N: usize>(x: [u64; N]) -> bool {
     x[0] & 1 == 0
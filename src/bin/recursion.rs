@@ -51,9 +51,10 @@ fn main() -> Result<()> {
 
     // Populate inputs.
     let mut recursion_inputs = PartialWitness::new();
-    if let Err(e) = recursion_circuit
-        .proof
-        .populate_witness(&mut recursion_inputs, inner_proof)
+    if let Err(e) =
+        recursion_circuit
+            .proof
+            .populate_witness(&mut recursion_inputs, inner_proof, &[])
     {
         panic!("Failed to populate inputs: {:?}", e);
     }
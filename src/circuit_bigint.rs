@@ -1,14 +1,16 @@
 use crate::util::ceil_div_usize;
-use crate::{biguint_to_field, field_to_biguint, util::pad_to_multiple_usize, Base4SumGate, BoundedTarget, CircuitBuilder, Field, HaloCurve, OrderingTarget, PartialWitness, Target, WitnessGenerator};
+use crate::{biguint_to_field, field_order, field_to_biguint, BoolTarget, BoundedTarget, CircuitBuilder, Field, HaloCurve, OrderingTarget, PartialWitness, Target, WitnessGenerator};
 use num::{BigUint, Integer, One, Zero};
 
-/// We use 86-bit limbs so that
+/// By default we use 86-bit limbs so that
 /// - Any ~256 bit field element can be encoded as three limbs
 /// - Each limb product will take at most 172 bits
 /// - If our native field size is at least ~256 bits, we can accumulate many limb products witout
 ///   overflowing the native field
-pub(crate) const LIMB_DIBITS: usize = 43;
-pub(crate) const LIMB_BITS: usize = LIMB_DIBITS * 2;
+///
+/// Values which are naturally smaller, e.g. 32 or 64 bits, can use narrower limbs instead. Any even
+/// width works, as long as sums of limb products fit in the native field.
+pub(crate) const DEFAULT_LIMB_BITS: usize = 86;
 
 /// Targets representing an unsigned big integer with limbs defined over a field `F`.
 #[derive(Clone)]
@@ -16,25 +18,45 @@ pub struct BigIntTarget<F: Field> {
     pub limbs: Vec<Target<F>>,
     /// An inclusive upper bound on this number.
     pub max: BigUint,
+    /// The number of bits in each limb. This must be even, since we range check limbs in base 4.
+    pub limb_bits: usize,
 }
 
 impl<F: Field> BigIntTarget<F> {
     pub fn new_bounded(limbs: Vec<Target<F>>, max: BigUint) -> Self {
-        Self { limbs, max }
+        Self::new_bounded_with_limb_bits(limbs, max, DEFAULT_LIMB_BITS)
+    }
+
+    pub fn new_bounded_with_limb_bits(limbs: Vec<Target<F>>, max: BigUint, limb_bits: usize) -> Self {
+        assert_eq!(limb_bits % 2, 0, "Limb widths must be even");
+        Self {
+            limbs,
+            max,
+            limb_bits,
+        }
     }
 
     pub fn new_unbounded(limbs: Vec<Target<F>>) -> Self {
-        let bits = LIMB_BITS * limbs.len();
+        Self::new_unbounded_with_limb_bits(limbs, DEFAULT_LIMB_BITS)
+    }
+
+    pub fn new_unbounded_with_limb_bits(limbs: Vec<Target<F>>, limb_bits: usize) -> Self {
+        let bits = limb_bits * limbs.len();
         let one = BigUint::one();
         let max = (&one << bits) - one;
-        Self { limbs, max }
+        Self::new_bounded_with_limb_bits(limbs, max, limb_bits)
     }
 
     pub fn zero() -> Self {
-        Self {
-            limbs: Vec::new(),
-            max: BigUint::zero(),
-        }
+        Self::zero_with_limb_bits(DEFAULT_LIMB_BITS)
+    }
+
+    pub fn zero_with_limb_bits(limb_bits: usize) -> Self {
+        Self::new_bounded_with_limb_bits(Vec::new(), BigUint::zero(), limb_bits)
+    }
+
+    pub fn limb_dibits(&self) -> usize {
+        self.limb_bits / 2
     }
 
     pub fn num_limbs(&self) -> usize {
@@ -47,8 +69,8 @@ impl<F: Field> BigIntTarget<F> {
 
     pub fn get_bounded_limb(&self, index: usize) -> BoundedTarget<F> {
         // We shift self.max to get the max value of this limb AND any more significant limbs.
-        let max_high_limbs = &self.max >> (LIMB_BITS * index);
-        let max_any_limb = (BigUint::one() << LIMB_BITS) - BigUint::one();
+        let max_high_limbs = &self.max >> (self.limb_bits * index);
+        let max_any_limb = (BigUint::one() << self.limb_bits) - BigUint::one();
         let max_this_limb = max_high_limbs.min(max_any_limb);
         BoundedTarget {
             target: self.get_limb(index),
@@ -73,63 +95,88 @@ impl<F: Field> BigIntTarget<F> {
     fn split_smallest_limb(&self) -> (Target<F>, Self) {
         let first = self.get_limb(0);
         let rest_limbs = self.limbs[1..].to_vec();
-        let rest_max = &self.max >> LIMB_BITS;
-        let rest = BigIntTarget {
-            limbs: rest_limbs,
-            max: rest_max,
-        };
+        let rest_max = &self.max >> self.limb_bits;
+        let rest = BigIntTarget::new_bounded_with_limb_bits(rest_limbs, rest_max, self.limb_bits);
         (first, rest)
     }
 }
 
 impl<F: Field> From<BoundedTarget<F>> for BigIntTarget<F> {
     fn from(bounded_target: BoundedTarget<F>) -> Self {
-        Self {
-            limbs: vec![bounded_target.target],
-            max: bounded_target.max,
-        }
+        Self::new_bounded(vec![bounded_target.target], bounded_target.max)
     }
 }
 
-pub(crate) fn biguint_to_limbs<F: Field>(biguint: &BigUint) -> Vec<F> {
-    let num_limbs = ceil_div_usize(biguint.bits() as usize, LIMB_BITS);
-    let base = BigUint::one() << LIMB_BITS;
+pub(crate) fn biguint_to_limbs<F: Field>(biguint: &BigUint, limb_bits: usize) -> Vec<F> {
+    let num_limbs = ceil_div_usize(biguint.bits() as usize, limb_bits);
+    let base = BigUint::one() << limb_bits;
     (0..num_limbs)
-        .map(|i| biguint_to_field((biguint >> (i * LIMB_BITS)) % &base))
+        .map(|i| biguint_to_field((biguint >> (i * limb_bits)) % &base))
         .collect()
 }
 
+/// Returns the limb width shared by the given bigints. Bigints without any limbs, such as a
+/// constant zero, are compatible with any width.
+fn common_limb_bits<F: Field>(terms: &[&BigIntTarget<F>]) -> usize {
+    let mut widths = terms
+        .iter()
+        .filter(|t| t.num_limbs() > 0)
+        .map(|t| t.limb_bits);
+    match widths.next() {
+        Some(limb_bits) => {
+            assert!(
+                widths.all(|w| w == limb_bits),
+                "Bigints with different limb widths cannot be combined"
+            );
+            limb_bits
+        }
+        None => terms.first().map_or(DEFAULT_LIMB_BITS, |t| t.limb_bits),
+    }
+}
+
 impl<C: HaloCurve> CircuitBuilder<C> {
     pub fn add_virtual_bigint_target(
         &mut self,
         max: &BigUint,
         validate: bool,
     ) -> BigIntTarget<C::ScalarField> {
-        let num_limbs = ceil_div_usize(max.bits() as usize, LIMB_BITS);
+        self.add_virtual_bigint_target_with_limb_bits(max, validate, DEFAULT_LIMB_BITS)
+    }
+
+    pub fn add_virtual_bigint_target_with_limb_bits(
+        &mut self,
+        max: &BigUint,
+        validate: bool,
+        limb_bits: usize,
+    ) -> BigIntTarget<C::ScalarField> {
+        let num_limbs = ceil_div_usize(max.bits() as usize, limb_bits);
         let limbs = self.add_virtual_targets(num_limbs);
+        let result = BigIntTarget::new_bounded_with_limb_bits(limbs, max.clone(), limb_bits);
 
         if validate {
             // Check that we have a valid bigint encoding, with each limb being in the proper range.
-            for &limb in &limbs {
-                self.assert_dibit_length(limb, LIMB_DIBITS);
+            for &limb in &result.limbs {
+                self.assert_dibit_length(limb, result.limb_dibits());
             }
         }
 
-        BigIntTarget {
-            limbs,
-            max: max.clone(),
-        }
+        result
     }
 
     pub fn constant_bigint(&mut self, value: &BigUint) -> BigIntTarget<C::ScalarField> {
-        let limbs = biguint_to_limbs(value)
+        self.constant_bigint_with_limb_bits(value, DEFAULT_LIMB_BITS)
+    }
+
+    pub fn constant_bigint_with_limb_bits(
+        &mut self,
+        value: &BigUint,
+        limb_bits: usize,
+    ) -> BigIntTarget<C::ScalarField> {
+        let limbs = biguint_to_limbs(value, limb_bits)
             .into_iter()
             .map(|limb| self.constant_wire(limb))
             .collect();
-        BigIntTarget {
-            limbs,
-            max: value.clone(),
-        }
+        BigIntTarget::new_bounded_with_limb_bits(limbs, value.clone(), limb_bits)
     }
 
     pub fn bigint_cmp(
@@ -141,6 +188,8 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         // does not match, and compare those limbs. A roughly similar approach was described in
         // https://github.com/mir-protocol/r1cs-workshop/blob/master/workshop.pdf
 
+        let limb_bits = common_limb_bits(&[x, y]);
+
         // Zero-pad the inputs if needed.
         let num_limbs = x.limbs.len().max(y.limbs.len());
         let x = self.bigint_pad_limbs(x, num_limbs);
@@ -158,42 +207,16 @@ impl<C: HaloCurve> CircuitBuilder<C> {
             y_diff = self.select(equal, y_diff, y_i);
         }
 
-        self.limb_cmp(x_diff, y_diff)
-    }
-
-    fn limb_cmp(
-        &mut self,
-        x: Target<C::ScalarField>,
-        y: Target<C::ScalarField>,
-    ) -> OrderingTarget<C::ScalarField> {
-        let ordering = self.add_virtual_ordering_target(true);
-        let OrderingTarget { gt, eq, lt } = ordering;
-        self.add_ordering_generator(ordering, x, y);
-
-        // Check that eq == (x == y).
-        let is_equal = self.is_equal(x, y);
-        self.copy(eq, is_equal);
-
-        // Now we want to check that if lt == 1, x <= y, and if gt == 1, x >= y.
-        // (Strict equality is not required here since we already checked the eq case.)
-        // We will do this by computing
-        //     r = lt * (y - x) + gt * (x - y)
-        //       = lt * (y - x) - gt * (y - x)
-        // and range checking r to ensure that no underflow occurs.
-        let delta = self.sub(y, x);
-        let gt_delta = self.mul(gt, delta);
-        let r = self.mul_sub(lt, delta, gt_delta);
-
-        // We have some flexibility in what upper bound to use for the range check. The max
-        // number of dibits must be at least LIMB_DIBITS, since r can legitimately be that large
-        // without underflow. We will pad to a multiple of Base4Gate::NUM_LIMBS, since
-        // assert_dibit_length is more efficient in that case. We will still detect any underflow,
-        // since that would result in r consuming at least roughly F_dibits - LIMB_DIBITS, which
-        // for any reasonably large field, will exceed pad(LIMB_DIBITS).
-        let max_dibits = pad_to_multiple_usize(LIMB_DIBITS, Base4SumGate::<C>::NUM_LIMBS);
-        self.assert_dibit_length(r, max_dibits);
-
-        ordering
+        let max_limb = (BigUint::one() << limb_bits) - BigUint::one();
+        let x_diff = BoundedTarget {
+            target: x_diff,
+            max: max_limb.clone(),
+        };
+        let y_diff = BoundedTarget {
+            target: y_diff,
+            max: max_limb,
+        };
+        self.cmp_bounded(&x_diff, &y_diff)
     }
 
     pub fn bigint_add(
@@ -213,6 +236,7 @@ impl<C: HaloCurve> CircuitBuilder<C> {
             .map(BigIntTarget::num_limbs)
             .max()
             .expect("No operands");
+        let limb_bits = common_limb_bits(&terms.iter().collect::<Vec<_>>());
 
         let mut carry: BoundedTarget<C::ScalarField> = self.zero_bounded_target();
         let mut result_limbs = Vec::new();
@@ -224,7 +248,7 @@ impl<C: HaloCurve> CircuitBuilder<C> {
                 }
             }
 
-            let sum_of_limbs = self.sum_limbs(&bounded_limbs);
+            let sum_of_limbs = self.sum_limbs(&bounded_limbs, limb_bits);
 
             // Unless the number of terms is astronomical, we should have two limbs at most.
             assert!(sum_of_limbs.num_limbs() <= 2);
@@ -247,25 +271,24 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         }
 
         let max = terms.iter().map(|t| &t.max).sum();
-        BigIntTarget {
-            limbs: result_limbs,
-            max,
-        }
+        BigIntTarget::new_bounded_with_limb_bits(result_limbs, max, limb_bits)
     }
 
     fn sum_limbs(
         &mut self,
         limbs: &[BoundedTarget<C::ScalarField>],
+        limb_bits: usize,
     ) -> BigIntTarget<C::ScalarField> {
         let nonzero_limbs: Vec<BoundedTarget<C::ScalarField>> =
             limbs.iter().cloned().filter(|l| !l.max.is_zero()).collect();
 
         if nonzero_limbs.is_empty() {
-            return BigIntTarget::zero();
+            return BigIntTarget::zero_with_limb_bits(limb_bits);
         }
 
         if nonzero_limbs.len() == 1 {
-            return nonzero_limbs[0].clone().into();
+            let BoundedTarget { target, max } = nonzero_limbs[0].clone();
+            return BigIntTarget::new_bounded_with_limb_bits(vec![target], max, limb_bits);
         }
 
         // Convert to a Vec of unbounded limbs.
@@ -281,14 +304,22 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         }
 
         let sum = self.add_many(&nonzero_limbs);
-        self.target_to_bigint(&BoundedTarget { target: sum, max })
+        self.target_to_bigint(&BoundedTarget { target: sum, max }, limb_bits)
     }
 
-    /// Split the given bounded target into a `BigIntTarget`.
-    fn target_to_bigint(
+    /// Split the given bounded target into a `BigIntTarget` with the given limb width.
+    pub(crate) fn target_to_bigint(
         &mut self,
         input: &BoundedTarget<C::ScalarField>,
+        limb_bits: usize,
     ) -> BigIntTarget<C::ScalarField> {
+        // If the bound reached the native field's order, the target may have silently wrapped
+        // around, so its value would not be the integer we are tracking.
+        assert!(
+            input.max < field_order::<C::ScalarField>(),
+            "Bigint limb arithmetic would overflow the native field; try narrower limbs"
+        );
+
         struct SplitGenerator<F: Field> {
             input: BoundedTarget<F>,
             output: BigIntTarget<F>,
@@ -312,7 +343,7 @@ impl<C: HaloCurve> CircuitBuilder<C> {
             }
         }
 
-        let output = self.add_virtual_bigint_target(&input.max, true);
+        let output = self.add_virtual_bigint_target_with_limb_bits(&input.max, true, limb_bits);
         self.add_generator(SplitGenerator {
             input: input.clone(),
             output: output.clone(),
@@ -326,12 +357,12 @@ impl<C: HaloCurve> CircuitBuilder<C> {
     }
 
     /// Join a `BigIntTarget` into a `BoundedTarget`.
-    fn bigint_to_target(
+    pub(crate) fn bigint_to_target(
         &mut self,
         bigint: &BigIntTarget<C::ScalarField>,
     ) -> BoundedTarget<C::ScalarField> {
         let mut sum = self.zero_wire();
-        let limb_multiplier = self.constant_wire(C::ScalarField::TWO.exp_usize(LIMB_BITS));
+        let limb_multiplier = self.constant_wire(C::ScalarField::TWO.exp_usize(bigint.limb_bits));
         for &limb in bigint.limbs.iter().rev() {
             sum = self.mul_add(sum, limb_multiplier, limb);
         }
@@ -346,11 +377,15 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         x: &BigIntTarget<C::ScalarField>,
         y: &BigIntTarget<C::ScalarField>,
     ) -> BigIntTarget<C::ScalarField> {
+        let limb_bits = common_limb_bits(&[x, y]);
         let x_n = x.num_limbs();
         let y_n = y.num_limbs();
+        if x_n == 0 || y_n == 0 {
+            return BigIntTarget::zero_with_limb_bits(limb_bits);
+        }
 
         let mut result_digits = Vec::new();
-        let mut carry: BigIntTarget<C::ScalarField> = BigIntTarget::zero();
+        let mut carry = BigIntTarget::zero_with_limb_bits(limb_bits);
 
         // We want to enumerate the cartesian product of x's and y's limbs, ordered from least
         // significant to most significant. We will do so by enumerating the possible "shifts",
@@ -370,7 +405,8 @@ impl<C: HaloCurve> CircuitBuilder<C> {
                 }
             }
 
-            let sum_of_limb_products_bigint = self.target_to_bigint(&sum_of_limb_products);
+            let sum_of_limb_products_bigint =
+                self.target_to_bigint(&sum_of_limb_products, limb_bits);
             let (first, rest) = sum_of_limb_products_bigint.split_smallest_limb();
             result_digits.push(first);
             carry = rest;
@@ -382,10 +418,7 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         }
 
         let max = &x.max * &y.max;
-        BigIntTarget {
-            limbs: result_digits,
-            max,
-        }
+        BigIntTarget::new_bounded_with_limb_bits(result_digits, max, limb_bits)
     }
 
     pub fn bigint_div(
@@ -411,6 +444,17 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         &mut self,
         x: &BigIntTarget<C::ScalarField>,
         y: &BigIntTarget<C::ScalarField>,
+    ) -> (BigIntTarget<C::ScalarField>, BigIntTarget<C::ScalarField>) {
+        self.bigint_div_rem_bounded(x, y, &x.max)
+    }
+
+    /// Like `bigint_div_rem`, but with a caller-supplied (inclusive) upper bound on the quotient.
+    /// A tight bound, e.g. when `y` is known to be large, saves limbs in the quotient.
+    pub(crate) fn bigint_div_rem_bounded(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        y: &BigIntTarget<C::ScalarField>,
+        max_div: &BigUint,
     ) -> (BigIntTarget<C::ScalarField>, BigIntTarget<C::ScalarField>) {
         struct DivRemGenerator<F: Field> {
             x: BigIntTarget<F>,
@@ -440,9 +484,10 @@ impl<C: HaloCurve> CircuitBuilder<C> {
             }
         }
 
+        let limb_bits = common_limb_bits(&[x, y]);
         let max_rem = &y.max - BigUint::one();
-        let div = self.add_virtual_bigint_target(&x.max, true);
-        let rem = self.add_virtual_bigint_target(&max_rem, true);
+        let div = self.add_virtual_bigint_target_with_limb_bits(max_div, true, limb_bits);
+        let rem = self.add_virtual_bigint_target_with_limb_bits(&max_rem, true, limb_bits);
 
         self.add_generator(DivRemGenerator {
             x: x.clone(),
@@ -452,17 +497,315 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         });
 
         // Check that x = div * y + rem.
-        let div_y = self.bigint_mul(&div, &y);
+        let div_y = self.bigint_mul(&div, y);
         let div_y_plus_rem = self.bigint_add(&div_y, &rem);
-        self.copy_bigint(&x, &div_y_plus_rem);
+        self.copy_bigint(x, &div_y_plus_rem);
 
         // Check that rem < y.
-        let cmp_rem_y = self.bigint_cmp(&rem, &y).lt;
+        let cmp_rem_y = self.bigint_cmp(&rem, y).lt;
         self.assert_one(cmp_rem_y);
 
         (div, rem)
     }
 
+    /// Computes `x - y`. Witness generation will fail if `x < y`, and the constraints rule out any
+    /// underflowing result.
+    pub fn bigint_sub(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        y: &BigIntTarget<C::ScalarField>,
+    ) -> BigIntTarget<C::ScalarField> {
+        struct SubGenerator<F: Field> {
+            x: BigIntTarget<F>,
+            y: BigIntTarget<F>,
+            diff: BigIntTarget<F>,
+        }
+
+        impl<F: Field> WitnessGenerator<F> for SubGenerator<F> {
+            fn dependencies(&self) -> Vec<Target<F>> {
+                [self.x.limbs.as_slice(), self.y.limbs.as_slice()].concat()
+            }
+
+            fn generate(
+                &self,
+                _constants: &[Vec<F>],
+                witness: &PartialWitness<F>,
+            ) -> PartialWitness<F> {
+                let x = witness.get_bigint_target(&self.x);
+                let y = witness.get_bigint_target(&self.y);
+                assert!(x >= y, "Bigint subtraction underflowed");
+
+                let mut result = PartialWitness::new();
+                result.set_bigint_target(&self.diff, &(x - y));
+                result
+            }
+        }
+
+        let limb_bits = common_limb_bits(&[x, y]);
+        let diff = self.add_virtual_bigint_target_with_limb_bits(&x.max, true, limb_bits);
+        self.add_generator(SubGenerator {
+            x: x.clone(),
+            y: y.clone(),
+            diff: diff.clone(),
+        });
+
+        // Check that diff + y = x. Since diff is range checked, it cannot encode a negative value.
+        let diff_plus_y = self.bigint_add(&diff, y);
+        self.copy_bigint(&diff_plus_y, x);
+
+        diff
+    }
+
+    /// Computes `x << n` for a constant `n`.
+    pub fn bigint_shl(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        n: usize,
+    ) -> BigIntTarget<C::ScalarField> {
+        let mut bits = vec![self.zero_wire(); n];
+        bits.extend(self.bigint_to_bits(x, x.max.bits() as usize));
+        let mut result = self.bigint_from_bits(&bits, x.limb_bits);
+        result.max = &x.max << n;
+        result
+    }
+
+    /// Computes `x >> n` for a constant `n`.
+    pub fn bigint_shr(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        n: usize,
+    ) -> BigIntTarget<C::ScalarField> {
+        let bits = self.bigint_to_bits(x, x.max.bits() as usize);
+        let mut result = self.bigint_from_bits(&bits[n.min(bits.len())..], x.limb_bits);
+        result.max = &x.max >> n;
+        result
+    }
+
+    /// Computes `x << shift`, where `shift` must fit in `shift_bits` bits.
+    pub fn bigint_shl_variable(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        shift: Target<C::ScalarField>,
+        shift_bits: usize,
+    ) -> BigIntTarget<C::ScalarField> {
+        let max_shift = (1 << shift_bits) - 1;
+        let zero = self.zero_wire();
+        let mut bits = self.bigint_to_bits(x, x.max.bits() as usize);
+        bits.resize(bits.len() + max_shift, zero);
+
+        // A barrel shifter: for each bit of the shift amount, conditionally shift by 2^k.
+        let shift_bits = self.to_bits_le(shift, shift_bits);
+        for (k, &shift_bit) in shift_bits.iter().enumerate() {
+            let amount = 1 << k;
+            bits = (0..bits.len())
+                .map(|i| {
+                    let shifted = if i >= amount { bits[i - amount] } else { zero };
+                    self.select(shift_bit, shifted, bits[i])
+                })
+                .collect();
+        }
+
+        let mut result = self.bigint_from_bits(&bits, x.limb_bits);
+        result.max = &x.max << max_shift;
+        result
+    }
+
+    /// Computes `x >> shift`, where `shift` must fit in `shift_bits` bits.
+    pub fn bigint_shr_variable(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        shift: Target<C::ScalarField>,
+        shift_bits: usize,
+    ) -> BigIntTarget<C::ScalarField> {
+        let zero = self.zero_wire();
+        let mut bits = self.bigint_to_bits(x, x.max.bits() as usize);
+
+        let shift_bits = self.to_bits_le(shift, shift_bits);
+        for (k, &shift_bit) in shift_bits.iter().enumerate() {
+            let amount = 1 << k;
+            bits = (0..bits.len())
+                .map(|i| {
+                    let shifted = if i + amount < bits.len() { bits[i + amount] } else { zero };
+                    self.select(shift_bit, shifted, bits[i])
+                })
+                .collect();
+        }
+
+        let mut result = self.bigint_from_bits(&bits, x.limb_bits);
+        result.max = x.max.clone();
+        result
+    }
+
+    /// Computes the bitwise AND of `x` and `y`.
+    pub fn bigint_and(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        y: &BigIntTarget<C::ScalarField>,
+    ) -> BigIntTarget<C::ScalarField> {
+        let mut result = self.bigint_bitwise(x, y, |builder, a, b| builder.mul(a, b));
+        result.max = (&x.max).min(&y.max).clone();
+        result
+    }
+
+    /// Computes the bitwise OR of `x` and `y`.
+    pub fn bigint_or(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        y: &BigIntTarget<C::ScalarField>,
+    ) -> BigIntTarget<C::ScalarField> {
+        // a | b = a + b - a b
+        self.bigint_bitwise(x, y, |builder, a, b| {
+            let sum = builder.add(a, b);
+            let product = builder.mul(a, b);
+            builder.sub(sum, product)
+        })
+    }
+
+    /// Computes the bitwise XOR of `x` and `y`.
+    pub fn bigint_xor(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        y: &BigIntTarget<C::ScalarField>,
+    ) -> BigIntTarget<C::ScalarField> {
+        // a ^ b = a + b - 2 a b
+        self.bigint_bitwise(x, y, |builder, a, b| {
+            let sum = builder.add(a, b);
+            let product = builder.mul(a, b);
+            let double_product = builder.add(product, product);
+            builder.sub(sum, double_product)
+        })
+    }
+
+    /// Applies a binary operation to each pair of bits of `x` and `y`. The result's bound is the
+    /// largest value with as many bits as the larger input.
+    fn bigint_bitwise<Op>(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        y: &BigIntTarget<C::ScalarField>,
+        op: Op,
+    ) -> BigIntTarget<C::ScalarField>
+    where
+        Op: Fn(&mut Self, Target<C::ScalarField>, Target<C::ScalarField>) -> Target<C::ScalarField>,
+    {
+        let limb_bits = common_limb_bits(&[x, y]);
+        let zero = self.zero_wire();
+        let mut x_bits = self.bigint_to_bits(x, x.max.bits() as usize);
+        let mut y_bits = self.bigint_to_bits(y, y.max.bits() as usize);
+        let num_bits = x_bits.len().max(y_bits.len());
+        x_bits.resize(num_bits, zero);
+        y_bits.resize(num_bits, zero);
+
+        let bits: Vec<_> = x_bits
+            .into_iter()
+            .zip(y_bits)
+            .map(|(a, b)| op(self, a, b))
+            .collect();
+        self.bigint_from_bits(&bits, limb_bits)
+    }
+
+    /// Computes `base^exponent % modulus`.
+    pub fn bigint_mod_pow(
+        &mut self,
+        base: &BigIntTarget<C::ScalarField>,
+        exponent: &BigIntTarget<C::ScalarField>,
+        modulus: &BigIntTarget<C::ScalarField>,
+    ) -> BigIntTarget<C::ScalarField> {
+        let exponent_bits = self.bigint_to_bits(exponent, exponent.max.bits() as usize);
+        let base = self.bigint_rem(base, modulus);
+        let one = self.constant_bigint_with_limb_bits(&BigUint::one(), modulus.limb_bits);
+        let mut result = self.bigint_rem(&one, modulus);
+
+        // Left-to-right square-and-multiply.
+        for &bit in exponent_bits.iter().rev() {
+            let square = self.bigint_mul(&result, &result);
+            result = self.bigint_rem(&square, modulus);
+            let product = self.bigint_mul(&result, &base);
+            let product = self.bigint_rem(&product, modulus);
+            // bigint_to_bits constrains each bit to be binary.
+            let bit = BoolTarget::new_unsafe(bit);
+            result = self.bigint_select(bit, &product, &result);
+        }
+
+        result
+    }
+
+    /// Computes `base^exponent % modulus` for a constant `exponent`. This is much cheaper than
+    /// `bigint_mod_pow`, since we only multiply for the exponent's set bits.
+    pub fn bigint_mod_pow_constant(
+        &mut self,
+        base: &BigIntTarget<C::ScalarField>,
+        exponent: &BigUint,
+        modulus: &BigIntTarget<C::ScalarField>,
+    ) -> BigIntTarget<C::ScalarField> {
+        let base = self.bigint_rem(base, modulus);
+        let one = self.constant_bigint_with_limb_bits(&BigUint::one(), modulus.limb_bits);
+        let mut result = self.bigint_rem(&one, modulus);
+
+        for i in (0..exponent.bits()).rev() {
+            let square = self.bigint_mul(&result, &result);
+            result = self.bigint_rem(&square, modulus);
+            if ((exponent >> i) & BigUint::one()).is_one() {
+                let product = self.bigint_mul(&result, &base);
+                result = self.bigint_rem(&product, modulus);
+            }
+        }
+
+        result
+    }
+
+    /// Returns `if b { x } else { y }`.
+    pub fn bigint_select(
+        &mut self,
+        b: BoolTarget<C::ScalarField>,
+        x: &BigIntTarget<C::ScalarField>,
+        y: &BigIntTarget<C::ScalarField>,
+    ) -> BigIntTarget<C::ScalarField> {
+        let limb_bits = common_limb_bits(&[x, y]);
+        let num_limbs = x.num_limbs().max(y.num_limbs());
+        let x = self.bigint_pad_limbs(x, num_limbs);
+        let y = self.bigint_pad_limbs(y, num_limbs);
+        let limbs = (0..num_limbs)
+            .map(|i| self.select(b.target(), x.get_limb(i), y.get_limb(i)))
+            .collect();
+        let max = (&x.max).max(&y.max).clone();
+        BigIntTarget::new_bounded_with_limb_bits(limbs, max, limb_bits)
+    }
+
+    /// Splits `x` into `num_bits` little-endian bits, and asserts that no higher bits are set.
+    pub(crate) fn bigint_to_bits(
+        &mut self,
+        x: &BigIntTarget<C::ScalarField>,
+        num_bits: usize,
+    ) -> Vec<Target<C::ScalarField>> {
+        let mut bits = Vec::with_capacity(x.num_limbs() * x.limb_bits);
+        for &limb in &x.limbs {
+            bits.extend(self.to_bits_le(limb, x.limb_bits));
+        }
+
+        // Any bits beyond num_bits must be zero.
+        let zero = self.zero_wire();
+        for &bit in bits.iter().skip(num_bits) {
+            self.assert_zero(bit);
+        }
+        bits.resize(num_bits, zero);
+        bits
+    }
+
+    /// Joins little-endian bits, which are assumed to be binary, into a `BigIntTarget` with the
+    /// given limb width.
+    pub(crate) fn bigint_from_bits(
+        &mut self,
+        bits: &[Target<C::ScalarField>],
+        limb_bits: usize,
+    ) -> BigIntTarget<C::ScalarField> {
+        let limbs = bits
+            .chunks(limb_bits)
+            .map(|limb_bits| self.from_bits_le(limb_bits))
+            .collect();
+        let max = (BigUint::one() << bits.len()) - BigUint::one();
+        BigIntTarget::new_bounded_with_limb_bits(limbs, max, limb_bits)
+    }
+
     /// Assert that the two given bigints encode the same integer.
     pub fn copy_bigint(
         &mut self,
@@ -471,7 +814,8 @@ impl<C: HaloCurve> CircuitBuilder<C> {
     ) {
         // The number of limbs may differ, in which case we assert equality for any limb indices
         // which are valid for both bigints, then assert that any "extra" limbs (present in one
-        // bigint but not the other) are zero.
+        // bigint but not the other) are zero. Limbs are only comparable if the widths match.
+        common_limb_bits(&[lhs, rhs]);
 
         let min_limbs = lhs.num_limbs().min(rhs.num_limbs());
         for i in 0..min_limbs {
@@ -486,7 +830,7 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         }
     }
 
-    fn bigint_pad_limbs(
+    pub(crate) fn bigint_pad_limbs(
         &mut self,
         x: &BigIntTarget<C::ScalarField>,
         num_limbs: usize,
@@ -500,8 +844,8 @@ impl<C: HaloCurve> CircuitBuilder<C> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{CircuitBuilder, PartialWitness, Tweedledum};
-    use num::{BigUint, FromPrimitive, Integer};
+    use crate::{CircuitBuilder, Curve, Field, PartialWitness, Tweedledum};
+    use num::{BigUint, FromPrimitive, Integer, One};
 
     #[test]
     fn test_bigint_add() {
@@ -555,4 +899,110 @@ mod tests {
         assert_eq!(actual_div_value, expected_div_value);
         assert_eq!(actual_rem_value, expected_rem_value);
     }
+
+    #[test]
+    fn test_bigint_sub() {
+        let x_value = BigUint::from_u128(456456456456456456456456456456456456).unwrap() << 100;
+        let y_value = BigUint::from_u128(123123123123123123123123123123123123).unwrap();
+        let expected_z_value = &x_value - &y_value;
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let x = builder.constant_bigint(&x_value);
+        let y = builder.constant_bigint(&y_value);
+        let z = builder.bigint_sub(&x, &y);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        let actual_z_value = witness.get_bigint_target(&z);
+        assert_eq!(actual_z_value, expected_z_value);
+    }
+
+    #[test]
+    fn test_bigint_shifts() {
+        type F = <Tweedledum as Curve>::ScalarField;
+        let x_value = BigUint::from_u128(123123123123123123123123123123123123).unwrap();
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let x = builder.constant_bigint(&x_value);
+        let shl = builder.bigint_shl(&x, 100);
+        let shr = builder.bigint_shr(&x, 30);
+        let shift = builder.constant_wire(F::from_canonical_usize(5));
+        let shl_variable = builder.bigint_shl_variable(&x, shift, 3);
+        let shr_variable = builder.bigint_shr_variable(&x, shift, 3);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        assert_eq!(witness.get_bigint_target(&shl), &x_value << 100);
+        assert_eq!(witness.get_bigint_target(&shr), &x_value >> 30);
+        assert_eq!(witness.get_bigint_target(&shl_variable), &x_value << 5);
+        assert_eq!(witness.get_bigint_target(&shr_variable), &x_value >> 5);
+    }
+
+    #[test]
+    fn test_bigint_bitwise() {
+        let x_value = BigUint::from_u128(456456456456456456456456456456456456).unwrap();
+        let y_value = BigUint::from_u128(123123123123123123123123123123123123).unwrap() << 50;
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let x = builder.constant_bigint(&x_value);
+        let y = builder.constant_bigint(&y_value);
+        let and = builder.bigint_and(&x, &y);
+        let or = builder.bigint_or(&x, &y);
+        let xor = builder.bigint_xor(&x, &y);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        assert_eq!(witness.get_bigint_target(&and), &x_value & &y_value);
+        assert_eq!(witness.get_bigint_target(&or), &x_value | &y_value);
+        assert_eq!(witness.get_bigint_target(&xor), &x_value ^ &y_value);
+    }
+
+    #[test]
+    fn test_bigint_mod_pow() {
+        let base_value = BigUint::from_u128(456456456456456456456456456456456456).unwrap();
+        let exponent_value = BigUint::from_u32(1000003).unwrap();
+        let modulus_value = BigUint::from_u128(123123123123123123123123123123123123).unwrap();
+        let expected_value = base_value.modpow(&exponent_value, &modulus_value);
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let base = builder.constant_bigint(&base_value);
+        let exponent = builder.constant_bigint(&exponent_value);
+        let modulus = builder.constant_bigint(&modulus_value);
+        let result = builder.bigint_mod_pow(&base, &exponent, &modulus);
+        let result_constant = builder.bigint_mod_pow_constant(&base, &exponent_value, &modulus);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        assert_eq!(witness.get_bigint_target(&result), expected_value);
+        assert_eq!(witness.get_bigint_target(&result_constant), expected_value);
+    }
+
+    #[test]
+    fn test_bigint_narrow_limbs() {
+        let limb_bits = 32;
+        let x_value = BigUint::from_u128(456456456456456456456456456456456456).unwrap();
+        let y_value = BigUint::from_u64(123123123123123123).unwrap();
+        let (expected_div_value, expected_rem_value) = x_value.div_rem(&y_value);
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let max = (BigUint::one() << 128) - BigUint::one();
+        let x = builder.add_virtual_bigint_target_with_limb_bits(&max, true, limb_bits);
+        let y = builder.constant_bigint_with_limb_bits(&y_value, limb_bits);
+        assert_eq!(x.num_limbs(), 4);
+        assert_eq!(y.num_limbs(), 2);
+        let sum = builder.bigint_add(&x, &y);
+        let product = builder.bigint_mul(&x, &y);
+        let (div, rem) = builder.bigint_div_rem(&x, &y);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_bigint_target(&x, &x_value);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(witness.get_bigint_target(&sum), &x_value + &y_value);
+        assert_eq!(witness.get_bigint_target(&product), &x_value * &y_value);
+        assert_eq!(witness.get_bigint_target(&div), expected_div_value);
+        assert_eq!(witness.get_bigint_target(&rem), expected_rem_value);
+        assert!(product.limbs.len() <= 6);
+        assert_eq!(product.limb_bits, limb_bits);
+    }
 }
@@ -0,0 +1,132 @@
+use crate::{field_order, CircuitBuilder, Field, HaloCurve, Target};
+use num::BigUint;
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// Asserts that `x`, interpreted as an integer, is less than `2^num_bits`.
+    pub fn range_check(&mut self, x: Target<C::ScalarField>, num_bits: usize) {
+        if num_bits % 2 == 1 {
+            // With an odd number of bits, we split off the least significant bit, then range
+            // check the remaining (x - b) / 2 in base 4.
+            let low_bit = self.split_binary(x, 1)[0];
+            self.assert_binary(low_bit);
+            let x_minus_low_bit = self.sub(x, low_bit);
+            let half =
+                self.constant_wire(C::ScalarField::TWO.multiplicative_inverse_assuming_nonzero());
+            let high = self.mul(x_minus_low_bit, half);
+            self.assert_dibit_length(high, num_bits / 2);
+        } else {
+            self.assert_dibit_length(x, num_bits / 2);
+        }
+    }
+
+    /// Splits `x` into `num_bits` little-endian bits, constraining each bit to be binary and their
+    /// weighted sum to equal `x`.
+    ///
+    /// If `num_bits` is close to the size of the field, some values will have two valid
+    /// decompositions. Use `to_bits_canonical` if that matters.
+    pub fn to_bits_le(
+        &mut self,
+        x: Target<C::ScalarField>,
+        num_bits: usize,
+    ) -> Vec<Target<C::ScalarField>> {
+        let bits = self.split_binary(x, num_bits);
+        for &bit in &bits {
+            self.assert_binary(bit);
+        }
+        let sum = self.from_bits_le(&bits);
+        self.copy(sum, x);
+        bits
+    }
+
+    /// Joins little-endian bits into a single target. The bits are assumed to be binary.
+    pub fn from_bits_le(&mut self, bits: &[Target<C::ScalarField>]) -> Target<C::ScalarField> {
+        let two = self.two_wire();
+        let mut sum = self.zero_wire();
+        for &bit in bits.iter().rev() {
+            sum = self.mul_add(sum, two, bit);
+        }
+        sum
+    }
+
+    /// Splits `x` into its unique little-endian binary representation, with `C::ScalarField::BITS`
+    /// bits. In addition to the checks done by `to_bits_le`, this asserts that the bits encode an
+    /// integer less than the field's order.
+    pub fn to_bits_canonical(&mut self, x: Target<C::ScalarField>) -> Vec<Target<C::ScalarField>> {
+        let bits = self.to_bits_le(x, C::ScalarField::BITS);
+        let max = field_order::<C::ScalarField>() - BigUint::from(1u32);
+        self.assert_bits_le_at_most(&bits, &max);
+        bits
+    }
+
+    /// Asserts that the integer encoded by the given little-endian bits is at most `max`. The
+    /// bits are assumed to be binary.
+    pub(crate) fn assert_bits_le_at_most(
+        &mut self,
+        bits: &[Target<C::ScalarField>],
+        max: &BigUint,
+    ) {
+        assert!(max.bits() as usize <= bits.len());
+
+        // We scan from the most significant bit, tracking whether the bits seen so far match
+        // those of max. While they match, any bit which is unset in max must also be unset.
+        let mut prefix_equal = self.one_wire();
+        for (i, &bit) in bits.iter().enumerate().rev() {
+            if max.bit(i as u64) {
+                prefix_equal = self.mul(prefix_equal, bit);
+            } else {
+                let exceeds = self.mul(prefix_equal, bit);
+                self.assert_zero(exceeds);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{field_to_biguint, CircuitBuilder, Curve, Field, PartialWitness, Tweedledum};
+
+    type C = Tweedledum;
+    type F = <C as Curve>::ScalarField;
+
+    #[test]
+    fn test_to_bits_le_and_back() {
+        let x_value = F::from_canonical_u64(0b1011_0110_1110);
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.constant_wire(x_value);
+        let bits = builder.to_bits_le(x, 12);
+        let joined = builder.from_bits_le(&bits);
+        builder.range_check(x, 12);
+        builder.range_check(x, 13);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        let bit_values: Vec<u64> = bits
+            .iter()
+            .map(|&bit| witness.get_target(bit).to_canonical_u64_vec()[0])
+            .collect();
+        assert_eq!(bit_values, vec![0, 1, 1, 1, 0, 1, 1, 0, 1, 1, 0, 1]);
+        assert_eq!(witness.get_target(joined), x_value);
+    }
+
+    #[test]
+    fn test_to_bits_canonical() {
+        let x_value = F::NEG_ONE;
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.constant_wire(x_value);
+        let bits = builder.to_bits_canonical(x);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        let x_biguint = field_to_biguint(x_value);
+        for (i, &bit) in bits.iter().enumerate() {
+            let expected = if x_biguint.bit(i as u64) {
+                F::ONE
+            } else {
+                F::ZERO
+            };
+            assert_eq!(witness.get_target(bit), expected);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{CircuitBuilder, Field, HaloCurve, Target};
+
+/// A target which is constrained to hold either 0 or 1.
+///
+/// Only `CircuitBuilder` methods which enforce (or otherwise guarantee) this can create one.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BoolTarget<F: Field> {
+    target: Target<F>,
+}
+
+impl<F: Field> BoolTarget<F> {
+    /// Wraps a target which is already known to be binary, without adding any constraints.
+    pub(crate) fn new_unsafe(target: Target<F>) -> Self {
+        Self { target }
+    }
+
+    pub fn target(self) -> Target<F> {
+        self.target
+    }
+}
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// Adds a virtual target which is constrained to be binary.
+    pub fn add_virtual_bool_target(&mut self) -> BoolTarget<C::ScalarField> {
+        let target = self.add_virtual_target();
+        self.assert_bool(target)
+    }
+
+    pub fn constant_bool(&mut self, b: bool) -> BoolTarget<C::ScalarField> {
+        let target = if b { self.one_wire() } else { self.zero_wire() };
+        BoolTarget::new_unsafe(target)
+    }
+
+    /// Asserts that `x` is binary, and returns it as a `BoolTarget`.
+    pub fn assert_bool(&mut self, x: Target<C::ScalarField>) -> BoolTarget<C::ScalarField> {
+        self.assert_binary(x);
+        BoolTarget::new_unsafe(x)
+    }
+
+    pub fn bool_not(&mut self, b: BoolTarget<C::ScalarField>) -> BoolTarget<C::ScalarField> {
+        BoolTarget::new_unsafe(self.not(b.target))
+    }
+
+    pub fn bool_and(
+        &mut self,
+        x: BoolTarget<C::ScalarField>,
+        y: BoolTarget<C::ScalarField>,
+    ) -> BoolTarget<C::ScalarField> {
+        BoolTarget::new_unsafe(self.mul(x.target, y.target))
+    }
+
+    /// Returns `x & !y`.
+    pub fn bool_and_not(
+        &mut self,
+        x: BoolTarget<C::ScalarField>,
+        y: BoolTarget<C::ScalarField>,
+    ) -> BoolTarget<C::ScalarField> {
+        // x & !y = x - x y
+        let result = self.arithmetic(
+            C::ScalarField::NEG_ONE,
+            x.target,
+            y.target,
+            C::ScalarField::ONE,
+            x.target,
+        );
+        BoolTarget::new_unsafe(result)
+    }
+
+    pub fn bool_or(
+        &mut self,
+        x: BoolTarget<C::ScalarField>,
+        y: BoolTarget<C::ScalarField>,
+    ) -> BoolTarget<C::ScalarField> {
+        // x | y = x + y - x y
+        let sum = self.add(x.target, y.target);
+        let result = self.arithmetic(
+            C::ScalarField::NEG_ONE,
+            x.target,
+            y.target,
+            C::ScalarField::ONE,
+            sum,
+        );
+        BoolTarget::new_unsafe(result)
+    }
+
+    pub fn bool_xor(
+        &mut self,
+        x: BoolTarget<C::ScalarField>,
+        y: BoolTarget<C::ScalarField>,
+    ) -> BoolTarget<C::ScalarField> {
+        // x ^ y = x + y - 2 x y
+        let sum = self.add(x.target, y.target);
+        let result = self.arithmetic(
+            -C::ScalarField::TWO,
+            x.target,
+            y.target,
+            C::ScalarField::ONE,
+            sum,
+        );
+        BoolTarget::new_unsafe(result)
+    }
+
+    /// Returns `if b { x } else { y }`.
+    pub fn bool_select(
+        &mut self,
+        b: BoolTarget<C::ScalarField>,
+        x: BoolTarget<C::ScalarField>,
+        y: BoolTarget<C::ScalarField>,
+    ) -> BoolTarget<C::ScalarField> {
+        BoolTarget::new_unsafe(self.select(b.target, x.target, y.target))
+    }
+
+    /// Returns `if x == 0 { true } else { false }`.
+    pub fn is_zero_bool(&mut self, x: Target<C::ScalarField>) -> BoolTarget<C::ScalarField> {
+        // is_zero's constraints already force its output to be binary.
+        BoolTarget::new_unsafe(self.is_zero(x))
+    }
+
+    /// Returns `if x == y { true } else { false }`.
+    pub fn is_equal_bool(
+        &mut self,
+        x: Target<C::ScalarField>,
+        y: Target<C::ScalarField>,
+    ) -> BoolTarget<C::ScalarField> {
+        BoolTarget::new_unsafe(self.is_equal(x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CircuitBuilder, PartialWitness, Tweedledum};
+
+    #[test]
+    fn test_bool_ops() {
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let x = builder.add_virtual_bool_target();
+        let y = builder.add_virtual_bool_target();
+        let not = builder.bool_not(x);
+        let and = builder.bool_and(x, y);
+        let and_not = builder.bool_and_not(x, y);
+        let or = builder.bool_or(x, y);
+        let xor = builder.bool_xor(x, y);
+        let select = builder.bool_select(x, y, not);
+        let circuit = builder.build();
+
+        for &(x_value, y_value) in &[(false, false), (false, true), (true, false), (true, true)] {
+            let mut partial_witness = PartialWitness::new();
+            partial_witness.set_bool_target(x, x_value);
+            partial_witness.set_bool_target(y, y_value);
+            let witness = circuit.generate_partial_witness(partial_witness);
+            assert_eq!(witness.get_bool_target(not), !x_value);
+            assert_eq!(witness.get_bool_target(and), x_value & y_value);
+            assert_eq!(witness.get_bool_target(and_not), x_value & !y_value);
+            assert_eq!(witness.get_bool_target(or), x_value | y_value);
+            assert_eq!(witness.get_bool_target(xor), x_value ^ y_value);
+            let expected_select = if x_value { y_value } else { !x_value };
+            assert_eq!(witness.get_bool_target(select), expected_select);
+        }
+    }
+}
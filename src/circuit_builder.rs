@@ -5,11 +5,12 @@ use std::collections::{BTreeMap, HashMap};
 use crate::gates::*;
 use crate::plonk_util::{commit_polynomials, polynomials_to_values_padded, sigma_polynomials, values_to_polynomials};
 use crate::util::{ceil_div_usize, log2_strict, transpose};
-use crate::{blake_hash_usize_to_curve, fft_precompute, generate_rescue_constants, msm_precompute, AffinePoint, AffinePointTarget, BoundedTarget, Circuit, Curve, Field, HaloCurve, PartialWitness, PublicInput, Target, TargetPartitions, VirtualTarget, Wire, WitnessGenerator, NUM_CONSTANTS, NUM_WIRES};
+use crate::{blake_hash_usize_to_curve, fft_precompute, msm_precompute, AffinePoint, AffinePointTarget, BoundedTarget, Circuit, Curve, Field, HaloCurve, PartialWitness, PoseidonParams, PublicInput, RescueParams, Target, TargetPartitions, TranscriptHashType, VirtualTarget, Wire, WitnessGenerator, NUM_CONSTANTS, NUM_WIRES};
 use num::{BigUint, Zero};
 
 pub struct CircuitBuilder<C: HaloCurve> {
     pub(crate) security_bits: usize,
+    transcript_hash: TranscriptHashType,
     public_input_index: usize,
     virtual_target_index: usize,
     gate_counts: BTreeMap<&'static str, usize>,
@@ -17,12 +18,15 @@ pub struct CircuitBuilder<C: HaloCurve> {
     copy_constraints: Vec<(Target<C::ScalarField>, Target<C::ScalarField>)>,
     generators: Vec<Box<dyn WitnessGenerator<C::ScalarField>>>,
     constant_wires: HashMap<C::ScalarField, Target<C::ScalarField>>,
+    pub(crate) rescue_params: RescueParams<C::ScalarField>,
+    pub(crate) poseidon_params: PoseidonParams<C::ScalarField>,
 }
 
 impl<C: HaloCurve> CircuitBuilder<C> {
     pub fn new(security_bits: usize) -> Self {
         CircuitBuilder {
             security_bits,
+            transcript_hash: TranscriptHashType::default(),
             public_input_index: 0,
             virtual_target_index: 0,
             gate_counts: BTreeMap::new(),
@@ -30,9 +34,18 @@ impl<C: HaloCurve> CircuitBuilder<C> {
             copy_constraints: Vec::new(),
             generators: Vec::new(),
             constant_wires: HashMap::new(),
+            rescue_params: RescueParams::new(RESCUE_SPONGE_WIDTH, security_bits),
+            poseidon_params: PoseidonParams::new(POSEIDON_SPONGE_WIDTH, security_bits),
         }
     }
 
+    /// Sets the hash used to generate Fiat-Shamir challenges for proofs of this circuit. The
+    /// default, Rescue, allows proofs to be verified recursively; `TranscriptHashType::Blake3` is
+    /// much faster for proofs which are only verified natively.
+    pub fn set_transcript_hash(&mut self, transcript_hash: TranscriptHashType) {
+        self.transcript_hash = transcript_hash;
+    }
+
     pub fn add_public_input(&mut self) -> Target<C::ScalarField> {
         let index = self.public_input_index;
         self.public_input_index += 1;
@@ -192,6 +205,17 @@ impl<C: HaloCurve> CircuitBuilder<C> {
                     }),
                 )
             }
+
+            // Any remaining limb wires are unused, so we fill them with zeros.
+            for i in chunk.len()..Base4SumGate::<C>::NUM_LIMBS {
+                self.generate_constant(
+                    Target::Wire(Wire {
+                        gate,
+                        input: Base4SumGate::<C>::wire_limb(i),
+                    }),
+                    C::ScalarField::ZERO,
+                );
+            }
         }
     }
 
@@ -205,42 +229,16 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         // This is similar to the technique described in
         // https://github.com/mir-protocol/r1cs-workshop/blob/master/workshop.pdf
 
-        let is_zero: Target<C::ScalarField> = self.add_virtual_target();
-
         // m will hold if x != 0 { -1 / x } else { 1 }.
-        let m = self.add_virtual_target();
-
-        struct IsZeroGenerator<F: Field> {
-            x: Target<F>,
-            m: Target<F>,
-            is_zero: Target<F>,
-        }
-
-        impl<F: Field> WitnessGenerator<F> for IsZeroGenerator<F> {
-            fn dependencies(&self) -> Vec<Target<F>> {
-                vec![self.x]
+        let outputs = self.add_hint(&[x], 2, |values| {
+            let x = values[0];
+            if x.is_zero() {
+                vec![C::ScalarField::ONE, C::ScalarField::ONE]
+            } else {
+                vec![C::ScalarField::ZERO, -x.multiplicative_inverse().unwrap()]
             }
-
-            fn generate(
-                &self,
-                _constants: &[Vec<F>],
-                witness: &PartialWitness<F>,
-            ) -> PartialWitness<F> {
-                let x = witness.get_target(self.x);
-                let (m, is_zero) = if x.is_zero() {
-                    (F::ONE, F::ONE)
-                } else {
-                    (-x.multiplicative_inverse().unwrap(), F::ZERO)
-                };
-
-                let mut result = PartialWitness::new();
-                result.set_target(self.m, m);
-                result.set_target(self.is_zero, is_zero);
-                result
-            }
-        }
-
-        self.add_generator(IsZeroGenerator { x, m, is_zero });
+        });
+        let (is_zero, m) = (outputs[0], outputs[1]);
 
         // Enforce that is_zero = x * m + 1.
         let one = self.one_wire();
@@ -603,32 +601,9 @@ impl<C: HaloCurve> CircuitBuilder<C> {
     }
 
     pub fn inv(&mut self, x: Target<C::ScalarField>) -> Target<C::ScalarField> {
-        struct InverseGenerator<F: Field> {
-            x: Target<F>,
-            x_inv: Target<F>,
-        }
-
-        impl<F: Field> WitnessGenerator<F> for InverseGenerator<F> {
-            fn dependencies(&self) -> Vec<Target<F>> {
-                vec![self.x]
-            }
-
-            fn generate(
-                &self,
-                _constants: &[Vec<F>],
-                witness: &PartialWitness<F>,
-            ) -> PartialWitness<F> {
-                let x_value = witness.get_target(self.x);
-                let x_inv_value = x_value.multiplicative_inverse().expect("x = 0");
-
-                let mut result = PartialWitness::new();
-                result.set_target(self.x_inv, x_inv_value);
-                result
-            }
-        }
-
-        let x_inv = self.add_virtual_target();
-        self.add_generator(InverseGenerator { x, x_inv });
+        let x_inv = self.add_hint(&[x], 1, |values| {
+            vec![values[0].multiplicative_inverse().expect("x = 0")]
+        })[0];
 
         // Enforce that x * x_inv = 1.
         let product = self.mul(x, x_inv);
@@ -736,6 +711,45 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         })
     }
 
+    /// Computes `const_0 * multiplicand_0 * multiplicand_1 + const_1 * addend` using a single
+    /// `ArithmeticGate`.
+    pub fn arithmetic(
+        &mut self,
+        const_0: C::ScalarField,
+        multiplicand_0: Target<C::ScalarField>,
+        multiplicand_1: Target<C::ScalarField>,
+        const_1: C::ScalarField,
+        addend: Target<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        let index = self.num_gates();
+        self.add_gate(ArithmeticGate::new(index), vec![const_0, const_1]);
+        self.copy(
+            multiplicand_0,
+            Target::Wire(Wire {
+                gate: index,
+                input: ArithmeticGate::<C>::WIRE_MULTIPLICAND_0,
+            }),
+        );
+        self.copy(
+            multiplicand_1,
+            Target::Wire(Wire {
+                gate: index,
+                input: ArithmeticGate::<C>::WIRE_MULTIPLICAND_1,
+            }),
+        );
+        self.copy(
+            addend,
+            Target::Wire(Wire {
+                gate: index,
+                input: ArithmeticGate::<C>::WIRE_ADDEND,
+            }),
+        );
+        Target::Wire(Wire {
+            gate: index,
+            input: ArithmeticGate::<C>::WIRE_OUTPUT,
+        })
+    }
+
     /// Computes `-x`.
     pub fn neg(&mut self, x: Target<C::ScalarField>) -> Target<C::ScalarField> {
         let neg_one = self.neg_one_wire();
@@ -820,9 +834,11 @@ impl<C: HaloCurve> CircuitBuilder<C> {
     /// Asserts that the given target's value is small enough to fit in the given number of dibits.
     ///
     /// Note: This is most efficient when `num_dibits` is a multiple of `Base4SumGate::NUM_LIMBS`.
-    pub(crate) fn assert_dibit_length(&mut self, x: Target<C::ScalarField>, num_dibits: usize) {
-        // Get the purported base 4 decomposition of x.
-        let dibits = self.split_base_4(x, num_dibits);
+    pub fn assert_dibit_length(&mut self, x: Target<C::ScalarField>, num_dibits: usize) {
+        // Get the purported base 4 decomposition of x. Base4SumGate accumulates the most
+        // significant limb first, so we reverse the little-endian decomposition.
+        let mut dibits = self.split_base_4(x, num_dibits);
+        dibits.reverse();
 
         // Accumulate each full chunk of NUM_LIMBS dibits using a Base4SumGate.
         let mut sum = self.zero_wire();
@@ -904,69 +920,16 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         inputs: &[Target<C::ScalarField>],
         num_outputs: usize,
     ) -> Vec<Target<C::ScalarField>> {
-        let zero = self.zero_wire();
-        let mut state = vec![zero; RESCUE_SPONGE_WIDTH];
-
-        // Absorb all input chunks.
-        for input_chunk in inputs.chunks(RESCUE_SPONGE_WIDTH - 1) {
-            for i in 0..input_chunk.len() {
-                state[i] = self.add(state[i], input_chunk[i]);
-            }
-            state = self.rescue_permutation(&state);
-        }
-
-        // Squeeze until we have the desired number of outputs.
-        let mut outputs = Vec::new();
-        loop {
-            for i in 0..(RESCUE_SPONGE_WIDTH - 1) {
-                outputs.push(state[i]);
-                if outputs.len() == num_outputs {
-                    return outputs;
-                }
-            }
-            state = self.rescue_permutation(&state);
-        }
+        let params = self.rescue_params.clone();
+        self.rescue_sponge_with_params(&params, inputs, num_outputs)
     }
 
     pub fn rescue_permutation(
         &mut self,
         inputs: &[Target<C::ScalarField>],
     ) -> Vec<Target<C::ScalarField>> {
-        assert_eq!(inputs.len(), RESCUE_SPONGE_WIDTH);
-
-        // Route the input wires.
-        for i in 0..RESCUE_SPONGE_WIDTH {
-            self.copy(
-                inputs[i],
-                Target::Wire(Wire {
-                    gate: self.num_gates(),
-                    input: RescueStepAGate::<C>::wire_acc(i),
-                }),
-            );
-        }
-
-        let all_constants = generate_rescue_constants(RESCUE_SPONGE_WIDTH, self.security_bits);
-        for (a_constants, b_constants) in all_constants.into_iter() {
-            let a_index = self.num_gates();
-            let a_gate = RescueStepAGate::new(a_index);
-            self.add_gate(a_gate, a_constants);
-
-            let b_index = self.num_gates();
-            let b_gate = RescueStepBGate::new(b_index);
-            self.add_gate(b_gate, b_constants);
-        }
-
-        // Use a BufferGate to receive the final accumulator states.
-        let gate = self.num_gates();
-        self.add_gate_no_constants(BufferGate::new(gate));
-        (0..RESCUE_SPONGE_WIDTH)
-            .map(|i| {
-                Target::Wire(Wire {
-                    gate,
-                    input: RescueStepBGate::<C>::wire_acc(i),
-                })
-            })
-            .collect()
+        let params = self.rescue_params.clone();
+        self.rescue_permutation_with_params(&params, inputs)
     }
 
     /// Adds a gate to the circuit, without doing any routing.
@@ -1000,6 +963,73 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         self.generators.push(Box::new(generator));
     }
 
+    /// Adds `num_outputs` virtual targets, which will be populated by applying `hint` to the
+    /// values of `inputs`. This is a lightweight alternative to writing a `WitnessGenerator`.
+    ///
+    /// Like any generator, a hint adds no constraints, so the caller must constrain its outputs.
+    pub fn add_hint<H>(
+        &mut self,
+        inputs: &[Target<C::ScalarField>],
+        num_outputs: usize,
+        hint: H,
+    ) -> Vec<Target<C::ScalarField>>
+    where
+        H: 'static + Sync + Fn(&[C::ScalarField]) -> Vec<C::ScalarField>,
+    {
+        let outputs = self.add_virtual_targets(num_outputs);
+        self.add_hint_to_targets(inputs, &outputs, hint);
+        outputs
+    }
+
+    /// Like `add_hint`, but populates the given targets, which may be any mix of virtual targets
+    /// and gate wires.
+    pub fn add_hint_to_targets<H>(
+        &mut self,
+        inputs: &[Target<C::ScalarField>],
+        outputs: &[Target<C::ScalarField>],
+        hint: H,
+    ) where
+        H: 'static + Sync + Fn(&[C::ScalarField]) -> Vec<C::ScalarField>,
+    {
+        struct HintGenerator<F: Field, H> {
+            inputs: Vec<Target<F>>,
+            outputs: Vec<Target<F>>,
+            hint: H,
+        }
+
+        impl<F: Field, H> WitnessGenerator<F> for HintGenerator<F, H>
+        where
+            H: 'static + Sync + Fn(&[F]) -> Vec<F>,
+        {
+            fn dependencies(&self) -> Vec<Target<F>> {
+                self.inputs.clone()
+            }
+
+            fn generate(
+                &self,
+                _constants: &[Vec<F>],
+                witness: &PartialWitness<F>,
+            ) -> PartialWitness<F> {
+                let output_values = (self.hint)(&witness.get_targets(&self.inputs));
+                assert_eq!(
+                    output_values.len(),
+                    self.outputs.len(),
+                    "Hint returned the wrong number of values"
+                );
+
+                let mut result = PartialWitness::new();
+                result.set_targets(&self.outputs, &output_values);
+                result
+            }
+        }
+
+        self.add_generator(HintGenerator {
+            inputs: inputs.to_vec(),
+            outputs: outputs.to_vec(),
+            hint,
+        });
+    }
+
     pub fn num_gates(&self) -> usize {
         self.gate_constants.len()
     }
@@ -1103,6 +1133,12 @@ impl<C: HaloCurve> CircuitBuilder<C> {
 
         let degree = self.num_gates();
         let degree_pow = log2_strict(degree);
+        // The quotient polynomial is computed over a subgroup of order 8n.
+        assert!(
+            degree_pow + 3 <= C::ScalarField::TWO_ADICITY,
+            "The scalar field's 2-adicity is too small for a circuit of degree {}",
+            degree
+        );
         let routing_target_partitions = self.get_routing_partitions();
         let wire_partitions = routing_target_partitions.to_wire_partitions();
         let sigma = wire_partitions.to_sigma();
@@ -1161,6 +1197,7 @@ impl<C: HaloCurve> CircuitBuilder<C> {
 
         Circuit {
             security_bits,
+            transcript_hash: self.transcript_hash,
             num_public_inputs,
             num_gates_without_pis,
             gate_constants,
@@ -0,0 +1,112 @@
+use crate::{AffinePointTarget, CircuitBuilder, Curve, CurveMulOp, HaloCurve, Target};
+
+/// Represents an ElGamal ciphertext over the curve `InnerC`, whose base field is our native field.
+#[derive(Copy, Clone, Debug)]
+pub struct ElGamalCiphertextTarget<InnerC: Curve> {
+    pub c1: AffinePointTarget<InnerC>,
+    pub c2: AffinePointTarget<InnerC>,
+}
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    pub fn add_virtual_elgamal_ciphertext_target<InnerC: Curve<BaseField = C::ScalarField>>(
+        &mut self,
+    ) -> ElGamalCiphertextTarget<InnerC> {
+        ElGamalCiphertextTarget {
+            c1: self.add_virtual_point_target(),
+            c2: self.add_virtual_point_target(),
+        }
+    }
+
+    /// The in-circuit equivalent of `elgamal_encrypt`. As with `CurveMulOp`, the randomness is
+    /// modelled in the native field, and like `curve_msm`, this assumes its most significant bit is
+    /// unset.
+    pub fn elgamal_encrypt<InnerC: Curve<BaseField = C::ScalarField>>(
+        &mut self,
+        public_key: AffinePointTarget<InnerC>,
+        message: AffinePointTarget<InnerC>,
+        randomness: Target<C::ScalarField>,
+    ) -> ElGamalCiphertextTarget<InnerC> {
+        let c1 = self.curve_mul_fixed_base(InnerC::GENERATOR_AFFINE, randomness);
+        let shared = self.curve_mul(CurveMulOp {
+            scalar: randomness,
+            point: public_key,
+        });
+        let c2 = self.curve_add(message, shared);
+        ElGamalCiphertextTarget { c1, c2 }
+    }
+
+    /// The in-circuit equivalent of `elgamal_reencrypt`, with the same assumptions as
+    /// `elgamal_encrypt`.
+    pub fn elgamal_reencrypt<InnerC: Curve<BaseField = C::ScalarField>>(
+        &mut self,
+        public_key: AffinePointTarget<InnerC>,
+        ciphertext: ElGamalCiphertextTarget<InnerC>,
+        randomness: Target<C::ScalarField>,
+    ) -> ElGamalCiphertextTarget<InnerC> {
+        let c1_delta = self.curve_mul_fixed_base(InnerC::GENERATOR_AFFINE, randomness);
+        let c2_delta = self.curve_mul(CurveMulOp {
+            scalar: randomness,
+            point: public_key,
+        });
+        ElGamalCiphertextTarget {
+            c1: self.curve_add(ciphertext.c1, c1_delta),
+            c2: self.curve_add(ciphertext.c2, c2_delta),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{blake_hash_usize_to_curve, elgamal_encrypt, elgamal_public_key, elgamal_reencrypt, CircuitBuilder, Curve, Field, PartialWitness, Tweedledee, Tweedledum};
+
+    #[test]
+    fn test_elgamal_encrypt_and_reencrypt() {
+        type C = Tweedledee;
+        type InnerC = Tweedledum;
+        type SF = <C as Curve>::ScalarField;
+        let public_key_value = elgamal_public_key::<InnerC>(<InnerC as Curve>::ScalarField::rand());
+        let message_value = blake_hash_usize_to_curve::<InnerC>(42);
+        let randomness_values = [SF::rand(), SF::rand()];
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let public_key = builder.add_virtual_point_target::<InnerC>();
+        let message = builder.add_virtual_point_target::<InnerC>();
+        let randomness = builder.add_virtual_targets(2);
+        let ciphertext = builder.elgamal_encrypt(public_key, message, randomness[0]);
+        let reencrypted = builder.elgamal_reencrypt(public_key, ciphertext, randomness[1]);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_point_target(public_key, public_key_value);
+        partial_witness.set_point_target(message, message_value);
+        partial_witness.set_targets(&randomness, &randomness_values);
+        let witness = circuit.generate_partial_witness(partial_witness);
+
+        let expected_ciphertext = elgamal_encrypt(
+            public_key_value,
+            message_value,
+            randomness_values[0].try_convert().unwrap(),
+        );
+        let expected_reencrypted = elgamal_reencrypt(
+            public_key_value,
+            expected_ciphertext,
+            randomness_values[1].try_convert().unwrap(),
+        );
+        assert_eq!(
+            witness.get_point_target(ciphertext.c1),
+            expected_ciphertext.c1
+        );
+        assert_eq!(
+            witness.get_point_target(ciphertext.c2),
+            expected_ciphertext.c2
+        );
+        assert_eq!(
+            witness.get_point_target(reencrypted.c1),
+            expected_reencrypted.c1
+        );
+        assert_eq!(
+            witness.get_point_target(reencrypted.c2),
+            expected_reencrypted.c2
+        );
+    }
+}
@@ -0,0 +1,403 @@
+use crate::{blake_hash_base_field_to_curve, AffinePoint, BoolTarget, CircuitBuilder, Curve, Field, ForeignFieldTarget, HaloCurve};
+
+/// Represents a point on a curve `FC` whose base field is foreign to the native field `F`.
+#[derive(Clone)]
+pub struct ForeignAffinePointTarget<F: Field, FC: Curve> {
+    pub x: ForeignFieldTarget<F, FC::BaseField>,
+    pub y: ForeignFieldTarget<F, FC::BaseField>,
+}
+
+/// A `[scalar] point` term of a foreign multi-scalar multiplication.
+#[derive(Clone)]
+pub struct ForeignCurveMulOp<F: Field, FC: Curve> {
+    pub scalar: ForeignFieldTarget<F, FC::ScalarField>,
+    pub point: ForeignAffinePointTarget<F, FC>,
+}
+
+/// Represents an ECDSA signature over a foreign curve `FC`.
+#[derive(Clone)]
+pub struct ForeignEcdsaSignatureTarget<F: Field, FC: Curve> {
+    pub r: ForeignFieldTarget<F, FC::ScalarField>,
+    pub s: ForeignFieldTarget<F, FC::ScalarField>,
+}
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    pub fn constant_foreign_affine_point<FC: Curve>(
+        &mut self,
+        point: AffinePoint<FC>,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        assert!(!point.zero);
+        ForeignAffinePointTarget {
+            x: self.constant_foreign_field(point.x),
+            y: self.constant_foreign_field(point.y),
+        }
+    }
+
+    /// Adds a virtual foreign point target, and constrains it to be a valid point on `FC`.
+    pub fn add_virtual_foreign_point_target<FC: Curve>(
+        &mut self,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        let p = ForeignAffinePointTarget {
+            x: self.add_virtual_foreign_field_target(),
+            y: self.add_virtual_foreign_field_target(),
+        };
+        self.foreign_curve_assert_valid(&p);
+        p
+    }
+
+    pub fn add_virtual_foreign_ecdsa_signature_target<FC: Curve>(
+        &mut self,
+    ) -> ForeignEcdsaSignatureTarget<C::ScalarField, FC> {
+        ForeignEcdsaSignatureTarget {
+            r: self.add_virtual_foreign_field_target(),
+            s: self.add_virtual_foreign_field_target(),
+        }
+    }
+
+    /// Add a copy constraint between two foreign point targets.
+    pub fn copy_foreign_curve<FC: Curve>(
+        &mut self,
+        p_1: &ForeignAffinePointTarget<C::ScalarField, FC>,
+        p_2: &ForeignAffinePointTarget<C::ScalarField, FC>,
+    ) {
+        self.copy_foreign_field(&p_1.x, &p_2.x);
+        self.copy_foreign_field(&p_1.y, &p_2.y);
+    }
+
+    /// Assert that a given coordinate pair is on the curve `FC`.
+    pub fn foreign_curve_assert_valid<FC: Curve>(
+        &mut self,
+        p: &ForeignAffinePointTarget<C::ScalarField, FC>,
+    ) {
+        // Recall the short Weierstrass equation: y^2 = x^3 + a*x + b.
+        let a = self.constant_foreign_field(FC::A);
+        let b = self.constant_foreign_field(FC::B);
+        let y_squared = self.foreign_field_mul(&p.y, &p.y);
+        let x_squared = self.foreign_field_mul(&p.x, &p.x);
+        let x_squared_plus_a = self.foreign_field_add(&x_squared, &a);
+        let x_cubed_plus_a_x = self.foreign_field_mul(&x_squared_plus_a, &p.x);
+        let rhs = self.foreign_field_add(&x_cubed_plus_a_x, &b);
+        self.copy_foreign_field(&y_squared, &rhs);
+    }
+
+    pub fn foreign_curve_neg<FC: Curve>(
+        &mut self,
+        p: &ForeignAffinePointTarget<C::ScalarField, FC>,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        let zero = self.constant_foreign_field(FC::BaseField::ZERO);
+        let neg_y = self.foreign_field_sub(&zero, &p.y);
+        ForeignAffinePointTarget {
+            x: p.x.clone(),
+            y: neg_y,
+        }
+    }
+
+    /// Adds two points using the incomplete affine addition formula. The points must have
+    /// distinct `x` coordinates, which is enforced by the slope computation.
+    pub fn foreign_curve_add<FC: Curve>(
+        &mut self,
+        p_1: &ForeignAffinePointTarget<C::ScalarField, FC>,
+        p_2: &ForeignAffinePointTarget<C::ScalarField, FC>,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        // lambda = (y_2 - y_1) / (x_2 - x_1)
+        let dy = self.foreign_field_sub(&p_2.y, &p_1.y);
+        let dx = self.foreign_field_sub(&p_2.x, &p_1.x);
+        let lambda = self.foreign_field_div(&dy, &dx);
+        self.foreign_curve_finish_add(&lambda, p_1, &p_2.x)
+    }
+
+    pub fn foreign_curve_double<FC: Curve>(
+        &mut self,
+        p: &ForeignAffinePointTarget<C::ScalarField, FC>,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        // lambda = (3 x^2 + a) / (2 y)
+        let a = self.constant_foreign_field(FC::A);
+        let x_squared = self.foreign_field_mul(&p.x, &p.x);
+        let numerator =
+            self.foreign_field_add_many(&[x_squared.clone(), x_squared.clone(), x_squared, a]);
+        let denominator = self.foreign_field_add(&p.y, &p.y);
+        let lambda = self.foreign_field_div(&numerator, &denominator);
+        self.foreign_curve_finish_add(&lambda, p, &p.x)
+    }
+
+    pub fn foreign_curve_sub<FC: Curve>(
+        &mut self,
+        p_1: &ForeignAffinePointTarget<C::ScalarField, FC>,
+        p_2: &ForeignAffinePointTarget<C::ScalarField, FC>,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        let neg_p_2 = self.foreign_curve_neg(p_2);
+        self.foreign_curve_add(p_1, &neg_p_2)
+    }
+
+    /// Given the slope `lambda` of the line through `p_1` and another point with `x` coordinate
+    /// `x_2`, computes the third intersection point and negates it.
+    fn foreign_curve_finish_add<FC: Curve>(
+        &mut self,
+        lambda: &ForeignFieldTarget<C::ScalarField, FC::BaseField>,
+        p_1: &ForeignAffinePointTarget<C::ScalarField, FC>,
+        x_2: &ForeignFieldTarget<C::ScalarField, FC::BaseField>,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        // x_3 = lambda^2 - x_1 - x_2
+        // y_3 = lambda (x_1 - x_3) - y_1
+        let lambda_squared = self.foreign_field_mul(lambda, lambda);
+        let x_1_plus_x_2 = self.foreign_field_add(&p_1.x, x_2);
+        let x_3 = self.foreign_field_sub(&lambda_squared, &x_1_plus_x_2);
+        let x_1_minus_x_3 = self.foreign_field_sub(&p_1.x, &x_3);
+        let lambda_dx = self.foreign_field_mul(lambda, &x_1_minus_x_3);
+        let y_3 = self.foreign_field_sub(&lambda_dx, &p_1.y);
+        ForeignAffinePointTarget { x: x_3, y: y_3 }
+    }
+
+    /// Returns `if b { p_1 } else { p_2 }`.
+    pub fn foreign_curve_select<FC: Curve>(
+        &mut self,
+        b: BoolTarget<C::ScalarField>,
+        p_1: &ForeignAffinePointTarget<C::ScalarField, FC>,
+        p_2: &ForeignAffinePointTarget<C::ScalarField, FC>,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        ForeignAffinePointTarget {
+            x: self.foreign_field_select(b, &p_1.x, &p_2.x),
+            y: self.foreign_field_select(b, &p_1.y, &p_2.y),
+        }
+    }
+
+    pub fn foreign_curve_mul<FC: Curve>(
+        &mut self,
+        scalar: &ForeignFieldTarget<C::ScalarField, FC::ScalarField>,
+        p: &ForeignAffinePointTarget<C::ScalarField, FC>,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        self.foreign_curve_msm(&[ForeignCurveMulOp {
+            scalar: scalar.clone(),
+            point: p.clone(),
+        }])
+    }
+
+    /// Computes a sum of `[s] P` terms, sharing the doublings between all terms.
+    ///
+    /// Since we use incomplete addition formulae, the result is only guaranteed to be correct if
+    /// no intermediate sum is the point at infinity, and no addition involves points with equal
+    /// `x` coordinates. This holds with overwhelming probability unless the inputs are chosen
+    /// adversarially, in which case witness generation will simply fail.
+    pub fn foreign_curve_msm<FC: Curve>(
+        &mut self,
+        parts: &[ForeignCurveMulOp<C::ScalarField, FC>],
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        self.foreign_curve_msm_with_bits(parts, FC::ScalarField::BITS)
+    }
+
+    /// Like `foreign_curve_msm`, but only supports scalars which fit in `num_bits` bits.
+    fn foreign_curve_msm_with_bits<FC: Curve>(
+        &mut self,
+        parts: &[ForeignCurveMulOp<C::ScalarField, FC>],
+        num_bits: usize,
+    ) -> ForeignAffinePointTarget<C::ScalarField, FC> {
+        let all_bits: Vec<Vec<BoolTarget<C::ScalarField>>> = parts
+            .iter()
+            .map(|part| self.foreign_field_to_bits(&part.scalar, num_bits))
+            .collect();
+
+        // We start with an arbitrary nonzero point so that the accumulator never needs to encode
+        // the point at infinity. We track its rescaled value and subtract it out at the end.
+        let mut filler = blake_hash_base_field_to_curve::<FC>(FC::BaseField::ZERO);
+        let mut acc = self.constant_foreign_affine_point(filler);
+
+        for i in (0..num_bits).rev() {
+            acc = self.foreign_curve_double(&acc);
+            filler = filler.double();
+
+            for (j, part) in parts.iter().enumerate() {
+                let sum = self.foreign_curve_add(&acc, &part.point);
+                acc = self.foreign_curve_select(all_bits[j][i], &sum, &acc);
+            }
+        }
+
+        let filler_target = self.constant_foreign_affine_point(filler);
+        self.foreign_curve_sub(&acc, &filler_target)
+    }
+
+    /// Verifies an ECDSA signature of `msg_hash` under `public_key`, on the foreign curve `FC`.
+    pub fn verify_ecdsa<FC: Curve>(
+        &mut self,
+        msg_hash: &ForeignFieldTarget<C::ScalarField, FC::ScalarField>,
+        sig: &ForeignEcdsaSignatureTarget<C::ScalarField, FC>,
+        public_key: &ForeignAffinePointTarget<C::ScalarField, FC>,
+    ) {
+        self.verify_ecdsa_with_bits(msg_hash, sig, public_key, FC::ScalarField::BITS)
+    }
+
+    /// Like `verify_ecdsa`, but only supports signatures whose `u_1 = z / s` and `u_2 = r / s`
+    /// fit in `num_bits` bits.
+    fn verify_ecdsa_with_bits<FC: Curve>(
+        &mut self,
+        msg_hash: &ForeignFieldTarget<C::ScalarField, FC::ScalarField>,
+        sig: &ForeignEcdsaSignatureTarget<C::ScalarField, FC>,
+        public_key: &ForeignAffinePointTarget<C::ScalarField, FC>,
+        num_bits: usize,
+    ) {
+        // Inverting r and s checks that both are nonzero.
+        self.foreign_field_inv(&sig.r);
+        let s_inv = self.foreign_field_inv(&sig.s);
+        let u_1 = self.foreign_field_mul(msg_hash, &s_inv);
+        let u_2 = self.foreign_field_mul(&sig.r, &s_inv);
+
+        let g = self.constant_foreign_affine_point(FC::GENERATOR_AFFINE);
+        let point = self.foreign_curve_msm_with_bits(
+            &[
+                ForeignCurveMulOp {
+                    scalar: u_1,
+                    point: g,
+                },
+                ForeignCurveMulOp {
+                    scalar: u_2,
+                    point: public_key.clone(),
+                },
+            ],
+            num_bits,
+        );
+
+        // Check that r = x mod n.
+        let x_reduced = self.reduce::<FC::ScalarField>(&point.x.value);
+        self.copy_foreign_field(&x_reduced, &sig.r);
+    }
+
+    /// Returns the little-endian bits of `x`, which must fit in `num_bits` bits.
+    fn foreign_field_to_bits<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+        num_bits: usize,
+    ) -> Vec<BoolTarget<C::ScalarField>> {
+        self.bigint_to_bits(&x.value, num_bits)
+            .into_iter()
+            .map(BoolTarget::new_unsafe)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{base_to_scalar_reduced, blake_hash_base_field_to_curve, ecdsa_public_key, ecdsa_verify, verify_proof, CircuitBuilder, Curve, EcdsaSignature, Field, ForeignCurveMulOp, PartialWitness, Secp256k1, Tweedledee, Tweedledum};
+
+    type C = Tweedledum;
+    type FC = Secp256k1;
+
+    #[test]
+    fn test_foreign_curve_add_and_double() {
+        let p_value = blake_hash_base_field_to_curve::<FC>(<FC as Curve>::BaseField::rand());
+        let q_value = blake_hash_base_field_to_curve::<FC>(<FC as Curve>::BaseField::rand());
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let p = builder.add_virtual_foreign_point_target::<FC>();
+        let q = builder.constant_foreign_affine_point(q_value);
+        let sum = builder.foreign_curve_add(&p, &q);
+        let double = builder.foreign_curve_double(&p);
+        let diff = builder.foreign_curve_sub(&p, &q);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_foreign_point_target(&p, p_value);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(
+            witness.get_foreign_point_target(&sum),
+            (p_value + q_value).to_affine()
+        );
+        assert_eq!(witness.get_foreign_point_target(&double), p_value.double());
+        assert_eq!(
+            witness.get_foreign_point_target(&diff),
+            (p_value + -q_value).to_affine()
+        );
+    }
+
+    #[test]
+    fn test_foreign_curve_msm_small_scalars() {
+        // A full-width MSM is too large to build in a unit test, so we exercise the same logic with
+        // 2-bit scalars.
+        type SF = <FC as Curve>::ScalarField;
+        let num_bits = 2;
+        let s_values = [SF::from_canonical_u32(3), SF::from_canonical_u32(2)];
+        let p_values = [
+            blake_hash_base_field_to_curve::<FC>(<FC as Curve>::BaseField::rand()),
+            FC::GENERATOR_AFFINE,
+        ];
+        let expected = FC::convert(s_values[0]) * p_values[0].to_projective()
+            + FC::convert(s_values[1]) * p_values[1].to_projective();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let s_0 = builder.add_virtual_foreign_field_target::<SF>();
+        let s_1 = builder.constant_foreign_field(s_values[1]);
+        let p_0 = builder.add_virtual_foreign_point_target::<FC>();
+        let p_1 = builder.constant_foreign_affine_point(p_values[1]);
+        let result = builder.foreign_curve_msm_with_bits(
+            &[
+                ForeignCurveMulOp {
+                    scalar: s_0.clone(),
+                    point: p_0.clone(),
+                },
+                ForeignCurveMulOp {
+                    scalar: s_1,
+                    point: p_1,
+                },
+            ],
+            num_bits,
+        );
+        let circuit = builder.build();
+
+        let partial_witness = || {
+            let mut partial_witness = PartialWitness::new();
+            partial_witness.set_foreign_field_target(&s_0, s_values[0]);
+            partial_witness.set_foreign_point_target(&p_0, p_values[0]);
+            partial_witness
+        };
+        let witness = circuit.generate_partial_witness(partial_witness());
+        assert_eq!(
+            witness.get_foreign_point_target(&result),
+            expected.to_affine()
+        );
+
+        let witness = circuit.generate_witness(partial_witness());
+        let proof = circuit
+            .generate_proof::<Tweedledee>(&witness, &[], true)
+            .unwrap();
+        let vk = circuit.to_vk();
+        verify_proof::<C, Tweedledee>(&[], &proof, &[], &vk, true).unwrap();
+    }
+
+    #[test]
+    fn test_verify_ecdsa_small_scalars() {
+        // A full-width ECDSA circuit is too large to prove in a unit test, so we work backwards from
+        // small u_1 = z / s and u_2 = r / s to a valid signature, which we verify with 2-bit MSM
+        // scalars.
+        type SF = <FC as Curve>::ScalarField;
+        let num_bits = 2;
+        let secret_key = SF::rand();
+        let public_key_value = ecdsa_public_key::<FC>(secret_key);
+        let u_1 = SF::from_canonical_u32(3);
+        let u_2 = SF::from_canonical_u32(2);
+        let point = (FC::convert(u_1) * FC::GENERATOR_PROJECTIVE
+            + FC::convert(u_2) * public_key_value.to_projective())
+        .to_affine();
+        let r = base_to_scalar_reduced::<FC>(point.x);
+        let s = r / u_2;
+        let msg_hash_value = u_1 * s;
+        let sig_value = EcdsaSignature::<FC> { r, s };
+        assert!(ecdsa_verify(msg_hash_value, sig_value, public_key_value));
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let msg_hash = builder.add_virtual_foreign_field_target::<SF>();
+        let sig = builder.add_virtual_foreign_ecdsa_signature_target::<FC>();
+        let public_key = builder.add_virtual_foreign_point_target::<FC>();
+        builder.verify_ecdsa_with_bits(&msg_hash, &sig, &public_key, num_bits);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_foreign_field_target(&msg_hash, msg_hash_value);
+        partial_witness.set_foreign_field_target(&sig.r, sig_value.r);
+        partial_witness.set_foreign_field_target(&sig.s, sig_value.s);
+        partial_witness.set_foreign_point_target(&public_key, public_key_value);
+        let witness = circuit.generate_witness(partial_witness);
+        let proof = circuit
+            .generate_proof::<Tweedledee>(&witness, &[], true)
+            .unwrap();
+        let vk = circuit.to_vk();
+        verify_proof::<C, Tweedledee>(&[], &proof, &[], &vk, true).unwrap();
+    }
+}
@@ -1,7 +1,12 @@
-use crate::{field_to_biguint, BigIntTarget, CircuitBuilder, Field, HaloCurve};
+use crate::{field_to_biguint, BigIntTarget, BoolTarget, BoundedTarget, CircuitBuilder, Field, HaloCurve, PartialWitness, Target, WitnessGenerator, DEFAULT_LIMB_BITS};
 use num::{BigUint, One};
 use std::marker::PhantomData;
 
+/// Returns the order of `FF` as a `BigUint`.
+pub(crate) fn field_order<FF: Field>() -> BigUint {
+    field_to_biguint(FF::NEG_ONE) + BigUint::one()
+}
+
 /// Represents an element of a field `Fq` other than the native field `Fp`.
 #[derive(Clone)]
 pub struct ForeignFieldTarget<Fp: Field, Fq: Field> {
@@ -30,6 +35,22 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         }
     }
 
+    /// Adds a virtual foreign field target, and constrains it to be a canonical encoding of an
+    /// `FF` element.
+    pub fn add_virtual_foreign_field_target<FF: Field>(
+        &mut self,
+    ) -> ForeignFieldTarget<C::ScalarField, FF> {
+        let order = field_order::<FF>();
+        let value = self.add_virtual_bigint_target(&(&order - BigUint::one()), true);
+        let order_target = self.constant_bigint(&order);
+        let lt = self.bigint_cmp(&value, &order_target).lt;
+        self.assert_one(lt);
+        ForeignFieldTarget {
+            value,
+            _foreign_field: PhantomData,
+        }
+    }
+
     pub fn foreign_field_add_many<FF: Field>(
         &mut self,
         terms: &[ForeignFieldTarget<C::ScalarField, FF>],
@@ -56,14 +77,271 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         self.reduce::<FF>(&product)
     }
 
+    pub fn foreign_field_sub<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+        y: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) -> ForeignFieldTarget<C::ScalarField, FF> {
+        struct SubGenerator<F: Field, FF: Field> {
+            x: ForeignFieldTarget<F, FF>,
+            y: ForeignFieldTarget<F, FF>,
+            diff: ForeignFieldTarget<F, FF>,
+        }
+
+        impl<F: Field, FF: Field> WitnessGenerator<F> for SubGenerator<F, FF> {
+            fn dependencies(&self) -> Vec<Target<F>> {
+                [self.x.value.limbs.as_slice(), self.y.value.limbs.as_slice()].concat()
+            }
+
+            fn generate(
+                &self,
+                _constants: &[Vec<F>],
+                witness: &PartialWitness<F>,
+            ) -> PartialWitness<F> {
+                let x: FF = witness.get_foreign_field_target(&self.x);
+                let y: FF = witness.get_foreign_field_target(&self.y);
+
+                let mut result = PartialWitness::new();
+                result.set_foreign_field_target(&self.diff, x - y);
+                result
+            }
+        }
+
+        let diff = self.add_virtual_foreign_field_target::<FF>();
+        self.add_generator(SubGenerator {
+            x: x.clone(),
+            y: y.clone(),
+            diff: diff.clone(),
+        });
+
+        // Check that diff + y = x.
+        let diff_plus_y = self.foreign_field_add(&diff, y);
+        self.copy_foreign_field(&diff_plus_y, x);
+
+        diff
+    }
+
+    pub fn foreign_field_neg<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) -> ForeignFieldTarget<C::ScalarField, FF> {
+        let zero = ForeignFieldTarget::zero();
+        self.foreign_field_sub(&zero, x)
+    }
+
+    /// Returns the inverse of `x`, which must be nonzero.
+    pub fn foreign_field_inv<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) -> ForeignFieldTarget<C::ScalarField, FF> {
+        struct InverseGenerator<F: Field, FF: Field> {
+            x: ForeignFieldTarget<F, FF>,
+            x_inv: ForeignFieldTarget<F, FF>,
+        }
+
+        impl<F: Field, FF: Field> WitnessGenerator<F> for InverseGenerator<F, FF> {
+            fn dependencies(&self) -> Vec<Target<F>> {
+                self.x.value.limbs.clone()
+            }
+
+            fn generate(
+                &self,
+                _constants: &[Vec<F>],
+                witness: &PartialWitness<F>,
+            ) -> PartialWitness<F> {
+                let x: FF = witness.get_foreign_field_target(&self.x);
+                let x_inv = x.multiplicative_inverse().expect("x = 0");
+
+                let mut result = PartialWitness::new();
+                result.set_foreign_field_target(&self.x_inv, x_inv);
+                result
+            }
+        }
+
+        let x_inv = self.add_virtual_foreign_field_target::<FF>();
+        self.add_generator(InverseGenerator {
+            x: x.clone(),
+            x_inv: x_inv.clone(),
+        });
+
+        // Enforce that x * x_inv = 1.
+        let product = self.foreign_field_mul(x, &x_inv);
+        let one = self.constant_foreign_field(FF::ONE);
+        self.copy_foreign_field(&product, &one);
+
+        x_inv
+    }
+
+    /// Returns `x / y`, where `y` must be nonzero.
+    pub fn foreign_field_div<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+        y: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) -> ForeignFieldTarget<C::ScalarField, FF> {
+        let y_inv = self.foreign_field_inv(y);
+        self.foreign_field_mul(x, &y_inv)
+    }
+
+    /// Returns a square root of `x`. Witness generation will fail if `x` is not a quadratic
+    /// residue. Either root may be returned; callers which care should constrain it further.
+    pub fn foreign_field_square_root<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) -> ForeignFieldTarget<C::ScalarField, FF> {
+        struct SquareRootGenerator<F: Field, FF: Field> {
+            x: ForeignFieldTarget<F, FF>,
+            root: ForeignFieldTarget<F, FF>,
+        }
+
+        impl<F: Field, FF: Field> WitnessGenerator<F> for SquareRootGenerator<F, FF> {
+            fn dependencies(&self) -> Vec<Target<F>> {
+                self.x.value.limbs.clone()
+            }
+
+            fn generate(
+                &self,
+                _constants: &[Vec<F>],
+                witness: &PartialWitness<F>,
+            ) -> PartialWitness<F> {
+                let x: FF = witness.get_foreign_field_target(&self.x);
+                let root = x.square_root().expect("x is not a quadratic residue");
+
+                let mut result = PartialWitness::new();
+                result.set_foreign_field_target(&self.root, root);
+                result
+            }
+        }
+
+        let root = self.add_virtual_foreign_field_target::<FF>();
+        self.add_generator(SquareRootGenerator {
+            x: x.clone(),
+            root: root.clone(),
+        });
+
+        // Enforce that root^2 = x.
+        let square = self.foreign_field_mul(&root, &root);
+        self.foreign_field_assert_equal(&square, x);
+
+        root
+    }
+
+    /// Returns `if b { x } else { y }`.
+    pub fn foreign_field_select<FF: Field>(
+        &mut self,
+        b: BoolTarget<C::ScalarField>,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+        y: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) -> ForeignFieldTarget<C::ScalarField, FF> {
+        ForeignFieldTarget {
+            value: self.bigint_select(b, &x.value, &y.value),
+            _foreign_field: PhantomData,
+        }
+    }
+
+    /// Returns `if x == y { 1 } else { 0 }`. Both inputs are checked to be canonical.
+    pub fn foreign_field_is_equal<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+        y: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) -> Target<C::ScalarField> {
+        self.foreign_field_assert_canonical(x);
+        self.foreign_field_assert_canonical(y);
+
+        // With canonical encodings, the elements are equal iff every pair of limbs is equal.
+        let num_limbs = x.value.num_limbs().max(y.value.num_limbs());
+        let x_value = self.bigint_pad_limbs(&x.value, num_limbs);
+        let y_value = self.bigint_pad_limbs(&y.value, num_limbs);
+        let mut result = self.one_wire();
+        for i in 0..num_limbs {
+            let limbs_equal = self.is_equal(x_value.get_limb(i), y_value.get_limb(i));
+            result = self.mul(result, limbs_equal);
+        }
+        result
+    }
+
+    /// Assert that `x` and `y` encode the same element. Both inputs are checked to be canonical.
+    pub fn foreign_field_assert_equal<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+        y: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) {
+        self.foreign_field_assert_canonical(x);
+        self.foreign_field_assert_canonical(y);
+        self.copy_foreign_field(x, y);
+    }
+
+    /// Assert that `x` is less than the order of `FF`. This is free if its upper bound already
+    /// implies as much, which is the case for any target created by the methods above.
+    pub fn foreign_field_assert_canonical<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) {
+        let order = field_order::<FF>();
+        if x.value.max < order {
+            return;
+        }
+        let order_target = self.constant_bigint(&order);
+        let lt = self.bigint_cmp(&x.value, &order_target).lt;
+        self.assert_one(lt);
+    }
+
+    /// Converts a native target to a foreign field element. Every native element must fit in
+    /// `FF`, i.e. the native field's order must not exceed that of `FF`.
+    pub fn target_to_foreign_field<FF: Field>(
+        &mut self,
+        x: Target<C::ScalarField>,
+    ) -> ForeignFieldTarget<C::ScalarField, FF> {
+        let native_order = field_order::<C::ScalarField>();
+        assert!(
+            native_order <= field_order::<FF>(),
+            "Native elements do not all fit in the foreign field"
+        );
+
+        let max = &native_order - BigUint::one();
+        let value = self.target_to_bigint(&BoundedTarget { target: x, max }, DEFAULT_LIMB_BITS);
+
+        // The limbs could otherwise encode x plus a multiple of the native order.
+        let native_order_target = self.constant_bigint(&native_order);
+        let lt = self.bigint_cmp(&value, &native_order_target).lt;
+        self.assert_one(lt);
+
+        ForeignFieldTarget {
+            value,
+            _foreign_field: PhantomData,
+        }
+    }
+
+    /// Converts a foreign field element to a native target. Panics unless `x` is statically known
+    /// to be less than the native field's order.
+    pub fn foreign_field_to_target<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) -> Target<C::ScalarField> {
+        assert!(
+            x.value.max < field_order::<C::ScalarField>(),
+            "Foreign field element may not fit in the native field"
+        );
+        self.bigint_to_target(&x.value).target
+    }
+
+    /// Assert that two foreign field targets encode the same element. Both are assumed to be
+    /// canonical, which is the case for any target created by the methods above.
+    pub fn copy_foreign_field<FF: Field>(
+        &mut self,
+        x: &ForeignFieldTarget<C::ScalarField, FF>,
+        y: &ForeignFieldTarget<C::ScalarField, FF>,
+    ) {
+        self.copy_bigint(&x.value, &y.value);
+    }
+
     /// Returns `x % |FF|` as a `ForeignFieldTarget`.
-    fn reduce<FF: Field>(
+    pub(crate) fn reduce<FF: Field>(
         &mut self,
         x: &BigIntTarget<C::ScalarField>,
     ) -> ForeignFieldTarget<C::ScalarField, FF> {
-        let order = field_to_biguint(FF::NEG_ONE) + BigUint::one();
+        let order = field_order::<FF>();
         let order_target = self.constant_bigint(&order);
-        let value = self.bigint_rem(&x, &order_target);
+        let (_div, value) = self.bigint_div_rem_bounded(x, &order_target, &(&x.max / &order));
         ForeignFieldTarget {
             value,
             _foreign_field: PhantomData,
@@ -73,7 +351,12 @@ impl<C: HaloCurve> CircuitBuilder<C> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{CircuitBuilder, Curve, Field, PartialWitness, Tweedledum};
+    use std::marker::PhantomData;
+
+    use num::BigUint;
+
+    use crate::circuit_foreign_field::{field_order, ForeignFieldTarget};
+    use crate::{field_to_biguint, CircuitBuilder, Curve, Field, PartialWitness, Tweedledee, Tweedledum};
 
     #[test]
     fn test_foreign_field_add() {
@@ -114,4 +397,171 @@ mod tests {
         let actual_z_value: FF = witness.get_foreign_field_target(&z);
         assert_eq!(actual_z_value, expected_z_value);
     }
+
+    #[test]
+    fn test_foreign_field_sub_and_neg() {
+        type C = Tweedledum;
+        type FF = <C as Curve>::BaseField;
+
+        let x_value = FF::rand();
+        let y_value = FF::rand();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.constant_foreign_field(x_value);
+        let y = builder.constant_foreign_field(y_value);
+        let diff = builder.foreign_field_sub::<FF>(&x, &y);
+        let neg = builder.foreign_field_neg::<FF>(&x);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        let actual_diff: FF = witness.get_foreign_field_target(&diff);
+        let actual_neg: FF = witness.get_foreign_field_target(&neg);
+        assert_eq!(actual_diff, x_value - y_value);
+        assert_eq!(actual_neg, -x_value);
+    }
+
+    #[test]
+    fn test_foreign_field_div() {
+        type C = Tweedledum;
+        type FF = <C as Curve>::BaseField;
+
+        let x_value = FF::rand();
+        let y_value = FF::rand();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.constant_foreign_field(x_value);
+        let y = builder.constant_foreign_field(y_value);
+        let quotient = builder.foreign_field_div::<FF>(&x, &y);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        let actual_quotient: FF = witness.get_foreign_field_target(&quotient);
+        assert_eq!(actual_quotient, x_value / y_value);
+    }
+
+    #[test]
+    fn test_foreign_field_is_equal() {
+        type C = Tweedledum;
+        type FF = <C as Curve>::BaseField;
+
+        let x_value = FF::rand();
+        let y_value = FF::rand();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.constant_foreign_field(x_value);
+        let y = builder.constant_foreign_field(y_value);
+        let x_plus_y = builder.foreign_field_add::<FF>(&x, &y);
+        let x_again = builder.foreign_field_sub::<FF>(&x_plus_y, &y);
+        let equal = builder.foreign_field_is_equal::<FF>(&x, &x_again);
+        let not_equal = builder.foreign_field_is_equal::<FF>(&x, &y);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        assert_eq!(witness.get_target(equal), <C as Curve>::ScalarField::ONE);
+        assert_eq!(
+            witness.get_target(not_equal),
+            <C as Curve>::ScalarField::ZERO
+        );
+    }
+
+    #[test]
+    fn test_foreign_field_square_root() {
+        type C = Tweedledum;
+        type FF = <C as Curve>::BaseField;
+
+        let root_value = FF::rand();
+        let x_value = root_value.square();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.constant_foreign_field(x_value);
+        let root = builder.foreign_field_square_root::<FF>(&x);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        let actual_root: FF = witness.get_foreign_field_target(&root);
+        assert!(actual_root == root_value || actual_root == -root_value);
+    }
+
+    /// Returns whether a proof verifies that `x` and `y` encode different elements, where `x` may be
+    /// any integer below twice the order of `FF`, including a non-canonical encoding of `y`.
+    fn not_equal_verifies(x_value: &BigUint, y_value: <Tweedledum as Curve>::BaseField) -> bool {
+        type C = Tweedledum;
+        type FF = <C as Curve>::BaseField;
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = ForeignFieldTarget::<_, FF> {
+            value: builder.add_virtual_bigint_target(&(field_order::<FF>() * 2u32), true),
+            _foreign_field: PhantomData,
+        };
+        let y = builder.add_virtual_foreign_field_target::<FF>();
+        let equal = builder.foreign_field_is_equal::<FF>(&x, &y);
+        builder.assert_zero(equal);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_bigint_target(&x.value, x_value);
+        partial_witness.set_foreign_field_target(&y, y_value);
+        circuit.proof_verifies::<Tweedledee>(partial_witness)
+    }
+
+    #[test]
+    fn test_foreign_field_is_equal_rejects_non_canonical() {
+        type FF = <Tweedledum as Curve>::BaseField;
+
+        let y_value = FF::rand();
+        let other_value = y_value + FF::ONE;
+        assert!(not_equal_verifies(&field_to_biguint(other_value), y_value));
+        let y_plus_order = field_to_biguint(y_value) + field_order::<FF>();
+        assert!(!not_equal_verifies(&y_plus_order, y_value));
+    }
+
+    #[test]
+    fn test_foreign_field_square_root_rejects_wrong_root() {
+        type C = Tweedledum;
+        type FF = <C as Curve>::BaseField;
+
+        let root_value = FF::rand();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.add_virtual_foreign_field_target::<FF>();
+        let root = builder.foreign_field_square_root::<FF>(&x);
+        let circuit = builder.build();
+
+        let partial_witness = || {
+            let mut partial_witness = PartialWitness::new();
+            partial_witness.set_foreign_field_target(&x, root_value.square());
+            partial_witness
+        };
+        assert!(circuit.proof_verifies::<Tweedledee>(partial_witness()));
+
+        let mut wrong_root = PartialWitness::new();
+        wrong_root.set_foreign_field_target(&root, root_value + FF::ONE);
+        let overrides: Vec<_> = root
+            .value
+            .limbs
+            .iter()
+            .map(|&limb| (limb, wrong_root.get_target(limb)))
+            .collect();
+        assert!(!circuit.proof_verifies_with_overrides::<Tweedledee>(partial_witness(), &overrides));
+    }
+
+    #[test]
+    fn test_native_conversions() {
+        type C = Tweedledum;
+        type F = <C as Curve>::ScalarField;
+
+        let x_value = F::rand();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.constant_wire(x_value);
+        let x_foreign = builder.target_to_foreign_field::<F>(x);
+        let x_squared = builder.foreign_field_mul::<F>(&x_foreign, &x_foreign);
+        let x_squared_native = builder.foreign_field_to_target(&x_squared);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        let actual_x_foreign: F = witness.get_foreign_field_target(&x_foreign);
+        assert_eq!(actual_x_foreign, x_value);
+        assert_eq!(witness.get_target(x_squared_native), x_value.square());
+    }
 }
@@ -0,0 +1,190 @@
+use crate::{BoundedTarget, CircuitBuilder, Field, HaloCurve, Target};
+use num::BigUint;
+
+/// A random-access memory, which starts out filled with zeros. Reads and writes are recorded in a
+/// log, and their consistency is only enforced once the log is passed to
+/// `assert_memory_consistent`.
+#[derive(Clone, Debug)]
+pub struct MemoryTarget<F: Field> {
+    address_bits: usize,
+    operations: Vec<MemoryOperation<F>>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct MemoryOperation<F: Field> {
+    address: Target<F>,
+    value: Target<F>,
+    is_write: bool,
+}
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// Creates an empty memory, whose addresses will be constrained to `address_bits` bits.
+    pub fn add_memory(&mut self, address_bits: usize) -> MemoryTarget<C::ScalarField> {
+        MemoryTarget {
+            address_bits,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Reads the value most recently written to `address`, or zero if there is no such write.
+    pub fn memory_read(
+        &mut self,
+        memory: &mut MemoryTarget<C::ScalarField>,
+        address: Target<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        // The hint's inputs are the address, followed by the address and value of each write.
+        let mut inputs = vec![address];
+        for op in memory.operations.iter().filter(|op| op.is_write) {
+            inputs.push(op.address);
+            inputs.push(op.value);
+        }
+        let value = self.add_hint(&inputs, 1, |values| {
+            let value = values[1..]
+                .chunks(2)
+                .rev()
+                .find(|write| write[0] == values[0])
+                .map_or(C::ScalarField::ZERO, |write| write[1]);
+            vec![value]
+        })[0];
+        memory.operations.push(MemoryOperation {
+            address,
+            value,
+            is_write: false,
+        });
+        value
+    }
+
+    /// Writes `value` to `address`.
+    pub fn memory_write(
+        &mut self,
+        memory: &mut MemoryTarget<C::ScalarField>,
+        address: Target<C::ScalarField>,
+        value: Target<C::ScalarField>,
+    ) {
+        memory.operations.push(MemoryOperation {
+            address,
+            value,
+            is_write: true,
+        });
+    }
+
+    /// Checks that every read in the memory's log returned the value most recently written to its
+    /// address, or zero if there was no such write.
+    ///
+    /// We tag each operation with its position in the log, then sort the log by address and time
+    /// using `sort_rows`, which proves that the sorted log is a permutation of the original with a
+    /// network of switches. The sorted log is checked row by row: its (address, time) keys must be
+    /// strictly increasing, and each read must return the value of the previous row if that row
+    /// has the same address, or zero otherwise.
+    pub fn assert_memory_consistent(&mut self, memory: MemoryTarget<C::ScalarField>) {
+        let MemoryTarget {
+            address_bits,
+            operations,
+        } = memory;
+        if operations.is_empty() {
+            return;
+        }
+        let time_bits = operations.len().next_power_of_two().trailing_zeros() as usize;
+        let time_multiplier = C::ScalarField::from_canonical_usize(1 << time_bits);
+
+        let one = self.one_wire();
+        let mut rows = Vec::with_capacity(operations.len());
+        for (time, op) in operations.iter().enumerate() {
+            self.range_check(op.address, address_bits);
+            let time_target = self.constant_wire(C::ScalarField::from_canonical_usize(time));
+            let key = self.arithmetic(
+                time_multiplier,
+                op.address,
+                one,
+                C::ScalarField::ONE,
+                time_target,
+            );
+            let is_write = self.constant_bool(op.is_write).target();
+            rows.push(vec![key, op.address, op.value, is_write]);
+        }
+        let sorted = self.sort_rows(&rows, 1);
+
+        let key_max = (BigUint::from(1u32) << (address_bits + time_bits)) - BigUint::from(1u32);
+        let bounded_key = |row: &Vec<Target<C::ScalarField>>| BoundedTarget {
+            target: row[0],
+            max: key_max.clone(),
+        };
+
+        // The first access to each address must read zero, if it's a read: (1 - w) v = 0.
+        let (value, is_write) = (sorted[0][2], sorted[0][3]);
+        let unwritten = self.mul_sub(is_write, value, value);
+        self.assert_zero(unwritten);
+
+        for (prev, row) in sorted.iter().zip(sorted.iter().skip(1)) {
+            self.assert_lt_bounded(&bounded_key(prev), &bounded_key(row));
+
+            // For a read, (1 - w) (v - same_address * v_prev) = 0.
+            let (address, value, is_write) = (row[1], row[2], row[3]);
+            let same_address = self.is_equal(prev[1], address);
+            let expected = self.mul(same_address, prev[2]);
+            let delta = self.sub(value, expected);
+            let mismatch = self.mul_sub(is_write, delta, delta);
+            self.assert_zero(mismatch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CircuitBuilder, Curve, Field, PartialWitness, Tweedledee, Tweedledum};
+
+    type C = Tweedledum;
+    type F = <C as Curve>::ScalarField;
+
+    #[test]
+    fn test_memory() {
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let mut memory = builder.add_memory(4);
+        let addresses = builder.add_virtual_targets(3);
+        let values = builder.add_virtual_targets(3);
+
+        let unwritten = builder.memory_read(&mut memory, addresses[0]);
+        builder.memory_write(&mut memory, addresses[0], values[0]);
+        builder.memory_write(&mut memory, addresses[1], values[1]);
+        let first = builder.memory_read(&mut memory, addresses[0]);
+        builder.memory_write(&mut memory, addresses[0], values[2]);
+        let second = builder.memory_read(&mut memory, addresses[0]);
+        let third = builder.memory_read(&mut memory, addresses[1]);
+        let fourth = builder.memory_read(&mut memory, addresses[2]);
+        builder.assert_memory_consistent(memory);
+        let circuit = builder.build();
+
+        let address_values: Vec<F> = [3, 11, 3]
+            .iter()
+            .map(|&a| F::from_canonical_usize(a))
+            .collect();
+        let value_values = [F::rand(), F::rand(), F::rand()];
+        let partial_witness = || {
+            let mut partial_witness = PartialWitness::new();
+            partial_witness.set_targets(&addresses, &address_values);
+            partial_witness.set_targets(&values, &value_values);
+            partial_witness
+        };
+        let witness = circuit.generate_partial_witness(partial_witness());
+
+        assert_eq!(witness.get_target(unwritten), F::ZERO);
+        assert_eq!(witness.get_target(first), value_values[0]);
+        assert_eq!(witness.get_target(second), value_values[2]);
+        assert_eq!(witness.get_target(third), value_values[1]);
+        // The third address aliases the first.
+        assert_eq!(witness.get_target(fourth), value_values[2]);
+        assert!(circuit.proof_verifies::<Tweedledee>(partial_witness()));
+
+        // A read must not return a stale value, a wrong value, or a write which hasn't happened yet.
+        for &(read, wrong_value) in &[
+            (second, value_values[0]),
+            (third, F::rand()),
+            (unwritten, value_values[0]),
+        ] {
+            assert!(!circuit.proof_verifies_with_overrides::<Tweedledee>(
+                partial_witness(),
+                &[(read, wrong_value)]
+            ));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{BoolTarget, CircuitBuilder, HaloCurve, RecursiveTranscriptHash, RescueTranscriptHash, Target};
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// The in-circuit equivalent of `merkle_compress`.
+    pub fn merkle_compress<H: RecursiveTranscriptHash<C::ScalarField>>(
+        &mut self,
+        left: Target<C::ScalarField>,
+        right: Target<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        assert!(H::RATE >= 2, "The hash must absorb two nodes at once");
+        let zero = self.zero_wire();
+        let mut state = vec![zero; H::WIDTH];
+        state[0] = left;
+        state[1] = right;
+        H::permute_recursively(self, &state)[0]
+    }
+
+    /// The in-circuit equivalent of `MerkleProof::compute_root`. The index is given as
+    /// little-endian bits.
+    pub fn merkle_root<H: RecursiveTranscriptHash<C::ScalarField>>(
+        &mut self,
+        leaf: Target<C::ScalarField>,
+        index_bits: &[BoolTarget<C::ScalarField>],
+        siblings: &[Target<C::ScalarField>],
+    ) -> Target<C::ScalarField> {
+        assert_eq!(index_bits.len(), siblings.len());
+
+        let mut node = leaf;
+        for (&bit, &sibling) in index_bits.iter().zip(siblings.iter()) {
+            // If the bit is set, the current node is a right child.
+            let left = self.select(bit.target(), sibling, node);
+            let right = self.select(bit.target(), node, sibling);
+            node = self.merkle_compress::<H>(left, right);
+        }
+        node
+    }
+
+    /// Verifies a proof that `leaf` is at the given index of a `MerkleTree` with the given root.
+    /// This uses Rescue, like `MerkleTree` does by default; the tree must use the same security
+    /// level as this builder.
+    pub fn verify_merkle_proof(
+        &mut self,
+        leaf: Target<C::ScalarField>,
+        index_bits: &[BoolTarget<C::ScalarField>],
+        siblings: &[Target<C::ScalarField>],
+        root: Target<C::ScalarField>,
+    ) {
+        self.verify_merkle_proof_with_hash::<RescueTranscriptHash<C::ScalarField>>(
+            leaf, index_bits, siblings, root,
+        )
+    }
+
+    pub fn verify_merkle_proof_with_hash<H: RecursiveTranscriptHash<C::ScalarField>>(
+        &mut self,
+        leaf: Target<C::ScalarField>,
+        index_bits: &[BoolTarget<C::ScalarField>],
+        siblings: &[Target<C::ScalarField>],
+        root: Target<C::ScalarField>,
+    ) {
+        let computed_root = self.merkle_root::<H>(leaf, index_bits, siblings);
+        self.copy(computed_root, root);
+    }
+
+    /// The in-circuit equivalent of `MerkleProof::verify_update`, using Rescue.
+    pub fn verify_merkle_update(
+        &mut self,
+        index_bits: &[BoolTarget<C::ScalarField>],
+        siblings: &[Target<C::ScalarField>],
+        old_leaf: Target<C::ScalarField>,
+        new_leaf: Target<C::ScalarField>,
+        old_root: Target<C::ScalarField>,
+        new_root: Target<C::ScalarField>,
+    ) {
+        self.verify_merkle_update_with_hash::<RescueTranscriptHash<C::ScalarField>>(
+            index_bits, siblings, old_leaf, new_leaf, old_root, new_root,
+        )
+    }
+
+    pub fn verify_merkle_update_with_hash<H: RecursiveTranscriptHash<C::ScalarField>>(
+        &mut self,
+        index_bits: &[BoolTarget<C::ScalarField>],
+        siblings: &[Target<C::ScalarField>],
+        old_leaf: Target<C::ScalarField>,
+        new_leaf: Target<C::ScalarField>,
+        old_root: Target<C::ScalarField>,
+        new_root: Target<C::ScalarField>,
+    ) {
+        self.verify_merkle_proof_with_hash::<H>(old_leaf, index_bits, siblings, old_root);
+        self.verify_merkle_proof_with_hash::<H>(new_leaf, index_bits, siblings, new_root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{merkle_compress, CircuitBuilder, Curve, Field, MerkleTree, PartialWitness, PoseidonTranscriptHash, TranscriptHash, Tweedledee, Tweedledum};
+
+    type F = <Tweedledum as Curve>::ScalarField;
+
+    #[test]
+    fn test_merkle_root() {
+        let leaves: Vec<F> = (0..8).map(|_| F::rand()).collect();
+        let tree = MerkleTree::<F, PoseidonTranscriptHash<F>>::new(leaves.clone(), 128);
+        let index = 6;
+        let proof = tree.prove(index);
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let leaf = builder.add_virtual_target();
+        let index_bits: Vec<_> = (0..3).map(|_| builder.add_virtual_bool_target()).collect();
+        let siblings = builder.add_virtual_targets(3);
+        let root = builder.merkle_root::<PoseidonTranscriptHash<F>>(leaf, &index_bits, &siblings);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_target(leaf, leaves[index]);
+        for (i, &bit) in index_bits.iter().enumerate() {
+            partial_witness.set_bool_target(bit, (index >> i) & 1 == 1);
+        }
+        partial_witness.set_targets(&siblings, &proof.siblings);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(witness.get_target(root), tree.root());
+    }
+
+    /// Checks a one-level proof whose index bit holds `bit`, with a root computed the same way as
+    /// the circuit would if the bit weren't constrained to be binary.
+    fn merkle_proof_verifies(bit: F) -> bool {
+        let hash = PoseidonTranscriptHash::<F>::new(128);
+        let leaf = F::rand();
+        let sibling = F::rand();
+        // select(b, x, y) = b (x - y) + y
+        let left = bit * (sibling - leaf) + leaf;
+        let right = bit * (leaf - sibling) + sibling;
+        let root = merkle_compress(&hash, left, right);
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let leaf_target = builder.add_virtual_target();
+        let index_bit = builder.add_virtual_bool_target();
+        let sibling_target = builder.add_virtual_target();
+        let root_target = builder.constant_wire(root);
+        builder.verify_merkle_proof_with_hash::<PoseidonTranscriptHash<F>>(
+            leaf_target,
+            &[index_bit],
+            &[sibling_target],
+            root_target,
+        );
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_target(leaf_target, leaf);
+        partial_witness.set_target(index_bit.target(), bit);
+        partial_witness.set_target(sibling_target, sibling);
+        circuit.proof_verifies::<Tweedledee>(partial_witness)
+    }
+
+    #[test]
+    fn test_verify_merkle_proof_rejects_non_binary_index() {
+        assert!(merkle_proof_verifies(F::ONE));
+        assert!(!merkle_proof_verifies(F::TWO));
+    }
+}
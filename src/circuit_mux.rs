@@ -0,0 +1,373 @@
+use crate::util::ceil_div_usize;
+use crate::{BoolTarget, BoundedTarget, CircuitBuilder, Field, HaloCurve, Target};
+use num::BigUint;
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// Returns `values[index]`. This constrains `index` to be less than `values.len()`.
+    ///
+    /// The index is split into bits, which drive a binary tree of `select`s, so this costs roughly
+    /// two gates per value.
+    pub fn mux(
+        &mut self,
+        index: Target<C::ScalarField>,
+        values: &[Target<C::ScalarField>],
+    ) -> Target<C::ScalarField> {
+        assert!(!values.is_empty(), "Cannot select from an empty list");
+        let num_bits = values.len().next_power_of_two().trailing_zeros() as usize;
+        let bits = self.to_bits_le(index, num_bits);
+
+        // The bit decomposition only bounds the index by the next power of two.
+        if !values.len().is_power_of_two() {
+            let index_bounded = BoundedTarget {
+                target: index,
+                max: (BigUint::from(1u32) << num_bits) - BigUint::from(1u32),
+            };
+            let len_bounded = BoundedTarget {
+                target: self.constant_wire(C::ScalarField::from_canonical_usize(values.len())),
+                max: BigUint::from(values.len()),
+            };
+            self.assert_lt_bounded(&index_bounded, &len_bounded);
+        }
+
+        let mut layer = values.to_vec();
+        for bit in bits {
+            let mut next_layer = Vec::with_capacity(ceil_div_usize(layer.len(), 2));
+            for pair in layer.chunks(2) {
+                if pair.len() == 2 {
+                    next_layer.push(self.select(bit, pair[1], pair[0]));
+                } else {
+                    // An unpaired value can only be selected when this bit is unset, since the
+                    // index is in range.
+                    next_layer.push(pair[0]);
+                }
+            }
+            layer = next_layer;
+        }
+        layer[0]
+    }
+
+    /// Returns `if b { (y, x) } else { (x, y) }`.
+    pub fn swap_if(
+        &mut self,
+        b: BoolTarget<C::ScalarField>,
+        x: Target<C::ScalarField>,
+        y: Target<C::ScalarField>,
+    ) -> (Target<C::ScalarField>, Target<C::ScalarField>) {
+        // With d = y - x, we compute x + b d and y - b d, so the whole swap takes three gates.
+        let delta = self.sub(y, x);
+        let b = b.target();
+        let first = self.arithmetic(C::ScalarField::ONE, b, delta, C::ScalarField::ONE, x);
+        let second = self.arithmetic(C::ScalarField::NEG_ONE, b, delta, C::ScalarField::ONE, y);
+        (first, second)
+    }
+
+    /// Like `swap_if`, but swaps two rows of targets.
+    #[allow(clippy::type_complexity)]
+    pub fn switch(
+        &mut self,
+        b: BoolTarget<C::ScalarField>,
+        xs: &[Target<C::ScalarField>],
+        ys: &[Target<C::ScalarField>],
+    ) -> (Vec<Target<C::ScalarField>>, Vec<Target<C::ScalarField>>) {
+        assert_eq!(xs.len(), ys.len(), "Rows have different lengths");
+        xs.iter()
+            .zip(ys.iter())
+            .map(|(&x, &y)| self.swap_if(b, x, y))
+            .unzip()
+    }
+
+    /// Routes `rows` through a Beneš network of `switch`es, whose settings are left for the
+    /// caller to generate with `route_benes_network`. Any permutation of the rows can be realized,
+    /// and no other outputs are possible, so this proves that the outputs are a permutation of the
+    /// inputs using only copy constraints and about `n log n` switches.
+    ///
+    /// Returns the permuted rows, along with the (binary-constrained) switch targets.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn benes_network(
+        &mut self,
+        rows: &[Vec<Target<C::ScalarField>>],
+    ) -> (
+        Vec<Vec<Target<C::ScalarField>>>,
+        Vec<Target<C::ScalarField>>,
+    ) {
+        let mut switches = Vec::new();
+        let outputs = self.benes_network_recursive(rows, &mut switches);
+        (outputs, switches)
+    }
+
+    fn benes_network_recursive(
+        &mut self,
+        rows: &[Vec<Target<C::ScalarField>>],
+        switches: &mut Vec<Target<C::ScalarField>>,
+    ) -> Vec<Vec<Target<C::ScalarField>>> {
+        let n = rows.len();
+        if n <= 1 {
+            return rows.to_vec();
+        }
+        if n == 2 {
+            let b = self.add_virtual_bool_target();
+            switches.push(b.target());
+            let (first, second) = self.switch(b, &rows[0], &rows[1]);
+            return vec![first, second];
+        }
+
+        // Each input pair is split between the two subnetworks. With an odd number of rows, the
+        // last one goes straight to the (larger) bottom subnetwork.
+        let half = n / 2;
+        let mut top_inputs = Vec::with_capacity(half);
+        let mut bottom_inputs = Vec::with_capacity(n - half);
+        for i in 0..half {
+            let b = self.add_virtual_bool_target();
+            switches.push(b.target());
+            let (top, bottom) = self.switch(b, &rows[2 * i], &rows[2 * i + 1]);
+            top_inputs.push(top);
+            bottom_inputs.push(bottom);
+        }
+        if n % 2 == 1 {
+            bottom_inputs.push(rows[n - 1].clone());
+        }
+
+        let top_outputs = self.benes_network_recursive(&top_inputs, switches);
+        let bottom_outputs = self.benes_network_recursive(&bottom_inputs, switches);
+
+        let mut outputs = Vec::with_capacity(n);
+        for i in 0..half {
+            let b = self.add_virtual_bool_target();
+            switches.push(b.target());
+            let (first, second) = self.switch(b, &top_outputs[i], &bottom_outputs[i]);
+            outputs.push(first);
+            outputs.push(second);
+        }
+        if n % 2 == 1 {
+            outputs.push(bottom_outputs[half].clone());
+        }
+        outputs
+    }
+
+    /// Returns a permutation of `rows`, sorted by their first `key_len` columns, which are
+    /// compared lexicographically as canonical field elements. The sort is stable.
+    ///
+    /// This only proves that the result is a permutation of `rows`; it is up to the caller to check
+    /// that it is actually sorted.
+    pub(crate) fn sort_rows(
+        &mut self,
+        rows: &[Vec<Target<C::ScalarField>>],
+        key_len: usize,
+    ) -> Vec<Vec<Target<C::ScalarField>>> {
+        let (outputs, switches) = self.benes_network(rows);
+        let keys: Vec<_> = rows
+            .iter()
+            .flat_map(|row| row[..key_len].to_vec())
+            .collect();
+        self.add_hint_to_targets(&keys, &switches, move |values| {
+            let keys: Vec<&[C::ScalarField]> = values.chunks(key_len).collect();
+            let mut order: Vec<usize> = (0..keys.len()).collect();
+            order.sort_by_key(|&i| keys[i]);
+            let mut destinations = vec![0; keys.len()];
+            for (position, &i) in order.iter().enumerate() {
+                destinations[i] = position;
+            }
+            route_benes_network(&destinations)
+                .into_iter()
+                .map(C::ScalarField::from_canonical_bool)
+                .collect()
+        });
+        outputs
+    }
+}
+
+/// Computes settings for the switches of `CircuitBuilder::benes_network`, in the order they were
+/// created, such that input `i` is sent to output `destinations[i]`.
+pub(crate) fn route_benes_network(destinations: &[usize]) -> Vec<bool> {
+    let mut settings = Vec::new();
+    route_benes_network_recursive(destinations, &mut settings);
+    settings
+}
+
+fn route_benes_network_recursive(destinations: &[usize], settings: &mut Vec<bool>) {
+    let n = destinations.len();
+    if n <= 1 {
+        return;
+    }
+    if n == 2 {
+        settings.push(destinations[0] == 1);
+        return;
+    }
+
+    let half = n / 2;
+    let mut sources = vec![0; n];
+    for (i, &d) in destinations.iter().enumerate() {
+        sources[d] = i;
+    }
+
+    // We decide which inputs go through the top subnetwork with the usual looping algorithm. The
+    // two inputs of each input switch must be split between the subnetworks, as must the two
+    // sources of each output switch. Each input is involved in at most one constraint of each
+    // kind, so the constraints form paths and even cycles, which can always be 2-colored. With an
+    // odd number of rows, the last input and the source of the last output must use the bottom
+    // subnetwork; they are the two ends of the same path, which has an even length.
+    let mut in_top: Vec<Option<bool>> = vec![None; n];
+    let mut stack = Vec::new();
+    if n % 2 == 1 {
+        stack.push((n - 1, false));
+        stack.push((sources[n - 1], false));
+    }
+    let mut start = 0;
+    loop {
+        while let Some((i, top)) = stack.pop() {
+            if let Some(existing) = in_top[i] {
+                debug_assert_eq!(existing, top, "Inconsistent routing");
+                continue;
+            }
+            in_top[i] = Some(top);
+            if i < 2 * half {
+                stack.push((i ^ 1, !top));
+            }
+            if destinations[i] < 2 * half {
+                stack.push((sources[destinations[i] ^ 1], !top));
+            }
+        }
+        while start < n && in_top[start].is_some() {
+            start += 1;
+        }
+        if start == n {
+            break;
+        }
+        stack.push((start, true));
+    }
+    let in_top: Vec<bool> = in_top.into_iter().map(Option::unwrap).collect();
+
+    // Within a subnetwork, a row headed to output pair `j` must reach position `j`. Conveniently,
+    // the last output of an odd-sized network is at position `half` of the bottom subnetwork.
+    let mut top_destinations = Vec::with_capacity(half);
+    let mut bottom_destinations = Vec::with_capacity(n - half);
+    for i in 0..half {
+        let swap = !in_top[2 * i];
+        settings.push(swap);
+        let (top, bottom) = if swap {
+            (2 * i + 1, 2 * i)
+        } else {
+            (2 * i, 2 * i + 1)
+        };
+        top_destinations.push(destinations[top] / 2);
+        bottom_destinations.push(destinations[bottom] / 2);
+    }
+    if n % 2 == 1 {
+        bottom_destinations.push(destinations[n - 1] / 2);
+    }
+
+    route_benes_network_recursive(&top_destinations, settings);
+    route_benes_network_recursive(&bottom_destinations, settings);
+
+    for j in 0..half {
+        settings.push(!in_top[sources[2 * j]]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CircuitBuilder, Curve, Field, PartialWitness, Tweedledee, Tweedledum};
+    use rand::{thread_rng, Rng};
+
+    type C = Tweedledum;
+    type F = <C as Curve>::ScalarField;
+
+    #[test]
+    fn test_mux() {
+        for &len in &[1, 2, 5, 8] {
+            let values: Vec<F> = (0..len).map(|_| F::rand()).collect();
+            let index_value = thread_rng().gen_range(0, len);
+
+            let mut builder = CircuitBuilder::<C>::new(128);
+            let value_targets = builder.add_virtual_targets(len);
+            let index = builder.add_virtual_target();
+            let selected = builder.mux(index, &value_targets);
+            let circuit = builder.build();
+
+            let mut partial_witness = PartialWitness::new();
+            partial_witness.set_targets(&value_targets, &values);
+            partial_witness.set_target(index, F::from_canonical_usize(index_value));
+            let witness = circuit.generate_partial_witness(partial_witness);
+            assert_eq!(witness.get_target(selected), values[index_value]);
+        }
+    }
+
+    fn mux_verifies(len: usize, index_value: usize) -> bool {
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let value_targets = builder.add_virtual_targets(len);
+        let index = builder.add_virtual_target();
+        builder.mux(index, &value_targets);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        let values: Vec<F> = (0..len).map(|_| F::rand()).collect();
+        partial_witness.set_targets(&value_targets, &values);
+        partial_witness.set_target(index, F::from_canonical_usize(index_value));
+        circuit.proof_verifies::<Tweedledee>(partial_witness)
+    }
+
+    #[test]
+    fn test_mux_rejects_out_of_range_index() {
+        assert!(mux_verifies(5, 4));
+        // These fit in the three bits used to select, but are out of range.
+        assert!(!mux_verifies(5, 5));
+        assert!(!mux_verifies(5, 7));
+    }
+
+    #[test]
+    fn test_swap_if() {
+        let (x_value, y_value) = (F::rand(), F::rand());
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let no = builder.constant_bool(false);
+        let yes = builder.constant_bool(true);
+        let (x_kept, y_kept) = builder.swap_if(no, x, y);
+        let (y_swapped, x_swapped) = builder.swap_if(yes, x, y);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_target(x, x_value);
+        partial_witness.set_target(y, y_value);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(witness.get_target(x_kept), x_value);
+        assert_eq!(witness.get_target(y_kept), y_value);
+        assert_eq!(witness.get_target(x_swapped), x_value);
+        assert_eq!(witness.get_target(y_swapped), y_value);
+    }
+
+    #[test]
+    fn test_sort_rows() {
+        for len in 1..=17 {
+            let keys: Vec<F> = (0..len)
+                .map(|_| F::from_canonical_usize(thread_rng().gen_range(0, 8)))
+                .collect();
+
+            let mut builder = CircuitBuilder::<C>::new(128);
+            let key_targets = builder.add_virtual_targets(len);
+            let rows: Vec<Vec<_>> = key_targets
+                .iter()
+                .enumerate()
+                .map(|(i, &key)| vec![key, builder.constant_wire_u32(i as u32)])
+                .collect();
+            let sorted = builder.sort_rows(&rows, 1);
+            let circuit = builder.build();
+
+            let mut partial_witness = PartialWitness::new();
+            partial_witness.set_targets(&key_targets, &keys);
+            let witness = circuit.generate_partial_witness(partial_witness);
+
+            let mut expected: Vec<(F, F)> = keys
+                .iter()
+                .enumerate()
+                .map(|(i, &key)| (key, F::from_canonical_usize(i)))
+                .collect();
+            expected.sort_by_key(|&(key, _)| key);
+            let actual: Vec<(F, F)> = sorted
+                .iter()
+                .map(|row| (witness.get_target(row[0]), witness.get_target(row[1])))
+                .collect();
+            assert_eq!(actual, expected);
+        }
+    }
+}
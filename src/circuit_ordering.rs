@@ -1,4 +1,5 @@
-use crate::{CircuitBuilder, Field, HaloCurve, PartialWitness, Target, WitnessGenerator};
+use crate::util::{ceil_div_usize, pad_to_multiple_usize};
+use crate::{Base4SumGate, BoundedTarget, CircuitBuilder, Field, HaloCurve, PartialWitness, Target, WitnessGenerator, DEFAULT_LIMB_BITS};
 use std::cmp::Ordering;
 
 #[derive(Copy, Clone)]
@@ -116,4 +117,294 @@ impl<C: HaloCurve> CircuitBuilder<C> {
         let sum = self.add_many(&[lt, eq, gt]);
         self.assert_one(sum);
     }
+
+    /// Returns `if ordering is lt or eq { 1 } else { 0 }`.
+    pub fn ordering_le(
+        &mut self,
+        ordering: OrderingTarget<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        self.add(ordering.lt, ordering.eq)
+    }
+
+    /// Returns `if ordering is gt or eq { 1 } else { 0 }`.
+    pub fn ordering_ge(
+        &mut self,
+        ordering: OrderingTarget<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        self.add(ordering.gt, ordering.eq)
+    }
+
+    /// Compares two field elements, interpreted as integers in `[0, |F|)`. This works for any
+    /// inputs, but requires a canonical binary decomposition of each, so `cmp_bounded` is much
+    /// cheaper when bounds are known.
+    pub fn cmp(
+        &mut self,
+        x: Target<C::ScalarField>,
+        y: Target<C::ScalarField>,
+    ) -> OrderingTarget<C::ScalarField> {
+        let x_bits = self.to_bits_canonical(x);
+        let y_bits = self.to_bits_canonical(y);
+        let x_bigint = self.bigint_from_bits(&x_bits, DEFAULT_LIMB_BITS);
+        let y_bigint = self.bigint_from_bits(&y_bits, DEFAULT_LIMB_BITS);
+        self.bigint_cmp(&x_bigint, &y_bigint)
+    }
+
+    /// Returns `if x < y { 1 } else { 0 }`, comparing canonical integer representations.
+    pub fn lt(
+        &mut self,
+        x: Target<C::ScalarField>,
+        y: Target<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        self.cmp(x, y).lt
+    }
+
+    /// Returns `if x <= y { 1 } else { 0 }`, comparing canonical integer representations.
+    pub fn le(
+        &mut self,
+        x: Target<C::ScalarField>,
+        y: Target<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        let ordering = self.cmp(x, y);
+        self.ordering_le(ordering)
+    }
+
+    /// Returns `if x > y { 1 } else { 0 }`, comparing canonical integer representations.
+    pub fn gt(
+        &mut self,
+        x: Target<C::ScalarField>,
+        y: Target<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        self.cmp(x, y).gt
+    }
+
+    /// Returns `if x >= y { 1 } else { 0 }`, comparing canonical integer representations.
+    pub fn ge(
+        &mut self,
+        x: Target<C::ScalarField>,
+        y: Target<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        let ordering = self.cmp(x, y);
+        self.ordering_ge(ordering)
+    }
+
+    /// Asserts that `x < y`, comparing canonical integer representations.
+    pub fn assert_lt(&mut self, x: Target<C::ScalarField>, y: Target<C::ScalarField>) {
+        let lt = self.lt(x, y);
+        self.assert_one(lt);
+    }
+
+    /// Returns the smaller of `x` and `y`, comparing canonical integer representations.
+    pub fn min(
+        &mut self,
+        x: Target<C::ScalarField>,
+        y: Target<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        let lt = self.lt(x, y);
+        self.select(lt, x, y)
+    }
+
+    /// Returns the larger of `x` and `y`, comparing canonical integer representations.
+    pub fn max(
+        &mut self,
+        x: Target<C::ScalarField>,
+        y: Target<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        let lt = self.lt(x, y);
+        self.select(lt, y, x)
+    }
+
+    /// Compares two targets whose bounds are already enforced, e.g. with `range_check`. The bounds
+    /// must be well below the field's order, so that differences cannot wrap around.
+    pub fn cmp_bounded(
+        &mut self,
+        x: &BoundedTarget<C::ScalarField>,
+        y: &BoundedTarget<C::ScalarField>,
+    ) -> OrderingTarget<C::ScalarField> {
+        let num_bits = x.max.bits().max(y.max.bits()) as usize;
+        assert!(
+            num_bits + 1 < C::ScalarField::BITS,
+            "Bounds are too large for cmp_bounded; use cmp instead"
+        );
+
+        let ordering = self.add_virtual_ordering_target(true);
+        let OrderingTarget { gt, eq, lt } = ordering;
+        self.add_ordering_generator(ordering, x.target, y.target);
+
+        // Check that eq == (x == y).
+        let is_equal = self.is_equal(x.target, y.target);
+        self.copy(eq, is_equal);
+
+        // Now we want to check that if lt == 1, x <= y, and if gt == 1, x >= y.
+        // (Strict equality is not required here since we already checked the eq case.)
+        // We will do this by computing
+        //     r = lt * (y - x) + gt * (x - y)
+        //       = lt * (y - x) - gt * (y - x)
+        // and range checking r to ensure that no underflow occurs.
+        let delta = self.sub(y.target, x.target);
+        let gt_delta = self.mul(gt, delta);
+        let r = self.mul_sub(lt, delta, gt_delta);
+
+        // We have some flexibility in what upper bound to use for the range check. The max
+        // number of dibits must be enough to hold either input. We will pad to a multiple of
+        // Base4Gate::NUM_LIMBS, since assert_dibit_length is more efficient in that case. We will
+        // still detect any underflow, since that would result in r consuming at least roughly
+        // F_bits - num_bits bits, which will exceed the padded length unless the inputs are
+        // nearly as large as the field.
+        let num_dibits = ceil_div_usize(num_bits, 2);
+        let padded_dibits = pad_to_multiple_usize(num_dibits, Base4SumGate::<C>::NUM_LIMBS);
+        if padded_dibits * 2 + 1 < C::ScalarField::BITS {
+            self.assert_dibit_length(r, padded_dibits);
+        } else {
+            self.range_check(r, num_bits);
+        }
+
+        ordering
+    }
+
+    /// Like `lt`, for targets whose bounds are already enforced.
+    pub fn lt_bounded(
+        &mut self,
+        x: &BoundedTarget<C::ScalarField>,
+        y: &BoundedTarget<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        self.cmp_bounded(x, y).lt
+    }
+
+    /// Like `le`, for targets whose bounds are already enforced.
+    pub fn le_bounded(
+        &mut self,
+        x: &BoundedTarget<C::ScalarField>,
+        y: &BoundedTarget<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        let ordering = self.cmp_bounded(x, y);
+        self.ordering_le(ordering)
+    }
+
+    /// Like `gt`, for targets whose bounds are already enforced.
+    pub fn gt_bounded(
+        &mut self,
+        x: &BoundedTarget<C::ScalarField>,
+        y: &BoundedTarget<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        self.cmp_bounded(x, y).gt
+    }
+
+    /// Like `ge`, for targets whose bounds are already enforced.
+    pub fn ge_bounded(
+        &mut self,
+        x: &BoundedTarget<C::ScalarField>,
+        y: &BoundedTarget<C::ScalarField>,
+    ) -> Target<C::ScalarField> {
+        let ordering = self.cmp_bounded(x, y);
+        self.ordering_ge(ordering)
+    }
+
+    /// Like `assert_lt`, for targets whose bounds are already enforced. This only needs a single
+    /// range check, of `y - x - 1`.
+    pub fn assert_lt_bounded(
+        &mut self,
+        x: &BoundedTarget<C::ScalarField>,
+        y: &BoundedTarget<C::ScalarField>,
+    ) {
+        let num_bits = x.max.bits().max(y.max.bits()) as usize;
+        assert!(
+            num_bits + 1 < C::ScalarField::BITS,
+            "Bounds are too large for assert_lt_bounded; use assert_lt instead"
+        );
+        let one = self.one_wire();
+        let y_minus_one = self.sub(y.target, one);
+        let diff = self.sub(y_minus_one, x.target);
+        self.range_check(diff, num_bits);
+    }
+
+    /// Like `min`, for targets whose bounds are already enforced.
+    pub fn min_bounded(
+        &mut self,
+        x: &BoundedTarget<C::ScalarField>,
+        y: &BoundedTarget<C::ScalarField>,
+    ) -> BoundedTarget<C::ScalarField> {
+        let lt = self.lt_bounded(x, y);
+        BoundedTarget {
+            target: self.select(lt, x.target, y.target),
+            max: (&x.max).min(&y.max).clone(),
+        }
+    }
+
+    /// Like `max`, for targets whose bounds are already enforced.
+    pub fn max_bounded(
+        &mut self,
+        x: &BoundedTarget<C::ScalarField>,
+        y: &BoundedTarget<C::ScalarField>,
+    ) -> BoundedTarget<C::ScalarField> {
+        let lt = self.lt_bounded(x, y);
+        BoundedTarget {
+            target: self.select(lt, y.target, x.target),
+            max: (&x.max).max(&y.max).clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundedTarget, CircuitBuilder, Curve, Field, PartialWitness, Tweedledum};
+    use num::BigUint;
+
+    type C = Tweedledum;
+    type F = <C as Curve>::ScalarField;
+
+    #[test]
+    fn test_cmp_full_width() {
+        let small = F::from_canonical_u64(12345);
+        let large = F::NEG_ONE;
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let small_target = builder.constant_wire(small);
+        let large_target = builder.constant_wire(large);
+        let lt = builder.lt(small_target, large_target);
+        let gt = builder.gt(small_target, large_target);
+        let le_self = builder.le(large_target, large_target);
+        let min = builder.min(large_target, small_target);
+        let max = builder.max(large_target, small_target);
+        builder.assert_lt(small_target, large_target);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        assert_eq!(witness.get_target(lt), F::ONE);
+        assert_eq!(witness.get_target(gt), F::ZERO);
+        assert_eq!(witness.get_target(le_self), F::ONE);
+        assert_eq!(witness.get_target(min), small);
+        assert_eq!(witness.get_target(max), large);
+    }
+
+    #[test]
+    fn test_cmp_bounded() {
+        let max = BigUint::from(u32::MAX);
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = BoundedTarget {
+            target: builder.constant_wire(F::from_canonical_u32(1000)),
+            max: max.clone(),
+        };
+        let y = BoundedTarget {
+            target: builder.constant_wire(F::from_canonical_u32(3_000_000_000)),
+            max,
+        };
+        let lt = builder.lt_bounded(&x, &y);
+        let ge = builder.ge_bounded(&x, &y);
+        let ge_self = builder.ge_bounded(&y, &y);
+        let min = builder.min_bounded(&x, &y);
+        let max = builder.max_bounded(&x, &y);
+        builder.assert_lt_bounded(&x, &y);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        assert_eq!(witness.get_target(lt), F::ONE);
+        assert_eq!(witness.get_target(ge), F::ZERO);
+        assert_eq!(witness.get_target(ge_self), F::ONE);
+        assert_eq!(witness.get_target(min.target), F::from_canonical_u32(1000));
+        assert_eq!(
+            witness.get_target(max.target),
+            F::from_canonical_u32(3_000_000_000)
+        );
+    }
 }
@@ -0,0 +1,54 @@
+use crate::{AffinePointTarget, CircuitBuilder, Curve, HaloCurve, PedersenGenerators, Target};
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// The in-circuit equivalent of `PedersenGenerators::commit`, over the curve `InnerC`. As with
+    /// `CurveMulOp`, the values and blinding factor are modelled in the native field, and like
+    /// `curve_msm`, this assumes their most significant bits are unset.
+    ///
+    /// The generators are constants, so this uses `curve_msm_fixed_base`, which costs one gate per
+    /// bit of each value.
+    pub fn pedersen_commit<InnerC: Curve<BaseField = C::ScalarField>>(
+        &mut self,
+        generators: &PedersenGenerators<InnerC>,
+        values: &[Target<C::ScalarField>],
+        blinding: Target<C::ScalarField>,
+    ) -> AffinePointTarget<InnerC> {
+        assert!(values.len() <= generators.g.len(), "Too many values");
+        let mut points = generators.g[..values.len()].to_vec();
+        points.push(generators.h);
+        let mut scalars = values.to_vec();
+        scalars.push(blinding);
+        self.curve_msm_fixed_base(&points, &scalars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CircuitBuilder, Curve, Field, PartialWitness, PedersenGenerators, Tweedledee, Tweedledum};
+
+    #[test]
+    fn test_pedersen_commit() {
+        type C = Tweedledee;
+        type InnerC = Tweedledum;
+        type SF = <C as Curve>::ScalarField;
+        let generators = PedersenGenerators::<InnerC>::new(3);
+        let values: Vec<SF> = (0..3).map(|_| SF::rand()).collect();
+        let blinding_value = SF::rand();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let value_targets = builder.add_virtual_targets(values.len());
+        let blinding = builder.add_virtual_target();
+        let commitment = builder.pedersen_commit(&generators, &value_targets, blinding);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_targets(&value_targets, &values);
+        partial_witness.set_target(blinding, blinding_value);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        let expected = generators.commit(
+            &SF::try_convert_all(&values).unwrap(),
+            blinding_value.try_convert().unwrap(),
+        );
+        assert_eq!(witness.get_point_target(commitment), expected);
+    }
+}
@@ -0,0 +1,270 @@
+use crate::circuit_mux::route_benes_network;
+use crate::{BoundedTarget, CircuitBuilder, Field, HaloCurve, Target};
+use num::BigUint;
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// Asserts that `ys` is a permutation of `xs`.
+    ///
+    /// We route `xs` through a Beneš network (see `benes_network`), with switch settings found by
+    /// matching up equal values, and copy its outputs to `ys`. This costs about `n log n` switches
+    /// of three gates each, and doesn't rely on any randomness.
+    pub fn assert_permutation(
+        &mut self,
+        xs: &[Target<C::ScalarField>],
+        ys: &[Target<C::ScalarField>],
+    ) {
+        assert_eq!(xs.len(), ys.len(), "Lists have different lengths");
+        let rows: Vec<Vec<_>> = xs.iter().map(|&x| vec![x]).collect();
+        let (outputs, switches) = self.benes_network(&rows);
+        for (output, &y) in outputs.iter().zip(ys.iter()) {
+            self.copy(output[0], y);
+        }
+        let inputs = [xs, ys].concat();
+        self.add_hint_to_targets(&inputs, &switches, |values| {
+            // Sorting both lists pairs up equal values. If the lists aren't permutations of each
+            // other, we route them arbitrarily, and the copy constraints will fail.
+            let (x_values, y_values) = values.split_at(values.len() / 2);
+            let mut x_order: Vec<usize> = (0..x_values.len()).collect();
+            let mut y_order: Vec<usize> = (0..y_values.len()).collect();
+            x_order.sort_by_key(|&i| x_values[i]);
+            y_order.sort_by_key(|&i| y_values[i]);
+            let mut destinations = vec![0; x_values.len()];
+            for (&i, &j) in x_order.iter().zip(y_order.iter()) {
+                destinations[i] = j;
+            }
+            route_benes_network(&destinations)
+                .into_iter()
+                .map(C::ScalarField::from_canonical_bool)
+                .collect()
+        });
+    }
+
+    /// Asserts that `ys` is a permutation of `xs`, where each element is a row of targets.
+    ///
+    /// Rather than switching every column, we compress each row `r` to `sum_j alpha^j r_j`, and
+    /// check that the compressed rows are permuted with `assert_permutation`. The challenge
+    /// `alpha` is drawn in-circuit by hashing both lists, so it is fixed only after every row is,
+    /// and by Schwartz-Zippel, distinct rows collide with probability at most `k / |F|`, where
+    /// `k` is the row length.
+    pub fn assert_permutation_rows(
+        &mut self,
+        xs: &[Vec<Target<C::ScalarField>>],
+        ys: &[Vec<Target<C::ScalarField>>],
+    ) {
+        assert_eq!(xs.len(), ys.len(), "Lists have different lengths");
+        let row_len = xs.first().map_or(0, Vec::len);
+        assert!(
+            xs.iter().chain(ys.iter()).all(|row| row.len() == row_len),
+            "Rows have different lengths"
+        );
+        if row_len <= 1 {
+            let xs: Vec<_> = xs.iter().flatten().copied().collect();
+            let ys: Vec<_> = ys.iter().flatten().copied().collect();
+            self.assert_permutation(&xs, &ys);
+            return;
+        }
+
+        let alpha = self.rescue_hash_n_to_1(&[xs.concat(), ys.concat()].concat());
+        let xs_compressed = self.compress_rows(xs, alpha);
+        let ys_compressed = self.compress_rows(ys, alpha);
+        self.assert_permutation(&xs_compressed, &ys_compressed);
+    }
+
+    /// Computes `sum_j alpha^j r_j` for each row `r`, using Horner's method.
+    fn compress_rows(
+        &mut self,
+        rows: &[Vec<Target<C::ScalarField>>],
+        alpha: Target<C::ScalarField>,
+    ) -> Vec<Target<C::ScalarField>> {
+        rows.iter()
+            .map(|row| {
+                let mut acc = row[row.len() - 1];
+                for &x in row.iter().rev().skip(1) {
+                    acc = self.mul_add(acc, alpha, x);
+                }
+                acc
+            })
+            .collect()
+    }
+
+    /// Returns `xs` sorted in ascending order, comparing canonical integer representations.
+    ///
+    /// The result is checked to be a permutation of `xs`, and each adjacent pair is compared with
+    /// `le`. That needs canonical binary decompositions, so `sort_bounded` is much cheaper when
+    /// bounds are known.
+    pub fn sort(&mut self, xs: &[Target<C::ScalarField>]) -> Vec<Target<C::ScalarField>> {
+        let rows: Vec<Vec<_>> = xs.iter().map(|&x| vec![x]).collect();
+        let sorted: Vec<_> = self
+            .sort_rows(&rows, 1)
+            .into_iter()
+            .map(|row| row[0])
+            .collect();
+        for (&prev, &x) in sorted.iter().zip(sorted.iter().skip(1)) {
+            let le = self.le(prev, x);
+            self.assert_one(le);
+        }
+        sorted
+    }
+
+    /// Like `sort`, for targets whose bounds are already enforced. Adjacent pairs are compared with
+    /// `le_bounded`.
+    pub fn sort_bounded(
+        &mut self,
+        xs: &[BoundedTarget<C::ScalarField>],
+    ) -> Vec<BoundedTarget<C::ScalarField>> {
+        let rows: Vec<Vec<_>> = xs.iter().map(|x| vec![x.target]).collect();
+        let max = xs.iter().map(|x| &x.max).max().cloned().unwrap_or_default();
+        let sorted: Vec<_> = self
+            .sort_rows(&rows, 1)
+            .into_iter()
+            .map(|row| BoundedTarget {
+                target: row[0],
+                max: max.clone(),
+            })
+            .collect();
+        for (prev, x) in sorted.iter().zip(sorted.iter().skip(1)) {
+            let le = self.le_bounded(prev, x);
+            self.assert_one(le);
+        }
+        sorted
+    }
+
+    /// Returns `rows` sorted in ascending order of their first column, with ties kept in their
+    /// original order. The first column of each row must already be constrained to be at most
+    /// `key_max`.
+    ///
+    /// Each row is tagged with its original index, and the tagged rows are sorted by a hint and
+    /// checked to be a permutation of the originals with `assert_permutation_rows`. Comparing
+    /// `key * n + index` rather than the bare key then enforces stability as well as order.
+    pub fn sort_rows_by_key(
+        &mut self,
+        rows: &[Vec<Target<C::ScalarField>>],
+        key_max: &BigUint,
+    ) -> Vec<Vec<Target<C::ScalarField>>> {
+        if rows.is_empty() {
+            return Vec::new();
+        }
+        let n = rows.len();
+        let row_len = rows[0].len();
+        let indexed_rows: Vec<Vec<_>> = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let index = self.constant_wire(C::ScalarField::from_canonical_usize(i));
+                [row.as_slice(), &[index]].concat()
+            })
+            .collect();
+
+        let flat_sorted = self.add_hint(&indexed_rows.concat(), n * (row_len + 1), move |values| {
+            // sort_by_key is stable, so ties stay in order of their indices.
+            let mut sorted_rows: Vec<&[C::ScalarField]> = values.chunks(row_len + 1).collect();
+            sorted_rows.sort_by_key(|row| row[0]);
+            sorted_rows.concat()
+        });
+        let sorted_indexed: Vec<Vec<_>> = flat_sorted
+            .chunks(row_len + 1)
+            .map(|row| row.to_vec())
+            .collect();
+        self.assert_permutation_rows(&indexed_rows, &sorted_indexed);
+
+        // Since indices are less than n, distinct (key, index) pairs give distinct combined keys,
+        // which are ordered lexicographically.
+        let n_target = self.constant_wire(C::ScalarField::from_canonical_usize(n));
+        let combined_max = key_max * BigUint::from(n) + BigUint::from(n - 1);
+        let combined_keys: Vec<_> = sorted_indexed
+            .iter()
+            .map(|row| BoundedTarget {
+                target: self.mul_add(row[0], n_target, row[row_len]),
+                max: combined_max.clone(),
+            })
+            .collect();
+        for (prev, key) in combined_keys.iter().zip(combined_keys.iter().skip(1)) {
+            self.assert_lt_bounded(prev, key);
+        }
+
+        sorted_indexed
+            .into_iter()
+            .map(|row| row[..row_len].to_vec())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundedTarget, CircuitBuilder, Curve, Field, PartialWitness, Tweedledee, Tweedledum};
+    use num::BigUint;
+    use rand::{thread_rng, Rng};
+
+    type C = Tweedledum;
+    type F = <C as Curve>::ScalarField;
+
+    fn small_values(n: usize) -> Vec<F> {
+        (0..n)
+            .map(|_| F::from_canonical_usize(thread_rng().gen_range(0, 16)))
+            .collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        let values = small_values(7);
+        let mut expected = values.clone();
+        expected.sort();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let targets = builder.add_virtual_targets(values.len());
+        let sorted = builder.sort(&targets);
+        let bounded: Vec<_> = targets
+            .iter()
+            .map(|&target| BoundedTarget {
+                target,
+                max: BigUint::from(15u32),
+            })
+            .collect();
+        let sorted_bounded = builder.sort_bounded(&bounded);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_targets(&targets, &values);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(witness.get_targets(&sorted), expected);
+        let sorted_bounded: Vec<_> = sorted_bounded.iter().map(|x| x.target).collect();
+        assert_eq!(witness.get_targets(&sorted_bounded), expected);
+    }
+
+    #[test]
+    fn test_sort_rows_by_key() {
+        // Use few distinct keys, so that there are ties to keep in order.
+        let keys: Vec<F> = (0..9)
+            .map(|_| F::from_canonical_usize(thread_rng().gen_range(0, 4)))
+            .collect();
+        let payloads: Vec<F> = (0..keys.len()).map(|_| F::rand()).collect();
+        let mut expected: Vec<Vec<F>> = keys
+            .iter()
+            .zip(payloads.iter())
+            .map(|(&key, &payload)| vec![key, payload])
+            .collect();
+        expected.sort_by_key(|row| row[0]);
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let key_targets = builder.add_virtual_targets(keys.len());
+        let payload_targets = builder.add_virtual_targets(keys.len());
+        let rows: Vec<Vec<_>> = key_targets
+            .iter()
+            .zip(payload_targets.iter())
+            .map(|(&key, &payload)| vec![key, payload])
+            .collect();
+        let sorted = builder.sort_rows_by_key(&rows, &BigUint::from(15u32));
+        let circuit = builder.build();
+
+        let partial_witness = || {
+            let mut partial_witness = PartialWitness::new();
+            partial_witness.set_targets(&key_targets, &keys);
+            partial_witness.set_targets(&payload_targets, &payloads);
+            partial_witness
+        };
+        let witness = circuit.generate_partial_witness(partial_witness());
+        let actual: Vec<Vec<F>> = sorted.iter().map(|row| witness.get_targets(row)).collect();
+        assert_eq!(actual, expected);
+        assert!(circuit.proof_verifies::<Tweedledee>(partial_witness()));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{BufferGate, CircuitBuilder, Field, HaloCurve, PoseidonGate, PoseidonParams, RescueStepBGate, Target, Wire, POSEIDON_SPONGE_RATE, POSEIDON_SPONGE_WIDTH};
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    pub fn poseidon_hash_n_to_1(
+        &mut self,
+        inputs: &[Target<C::ScalarField>],
+    ) -> Target<C::ScalarField> {
+        let params = self.poseidon_params.clone();
+        self.poseidon_sponge(&params, inputs, 1)[0]
+    }
+
+    pub fn poseidon_hash_n_to_2(
+        &mut self,
+        inputs: &[Target<C::ScalarField>],
+    ) -> (Target<C::ScalarField>, Target<C::ScalarField>) {
+        let params = self.poseidon_params.clone();
+        let outputs = self.poseidon_sponge(&params, inputs, 2);
+        (outputs[0], outputs[1])
+    }
+
+    /// The in-circuit equivalent of `poseidon_sponge`.
+    pub fn poseidon_sponge(
+        &mut self,
+        params: &PoseidonParams<C::ScalarField>,
+        inputs: &[Target<C::ScalarField>],
+        num_outputs: usize,
+    ) -> Vec<Target<C::ScalarField>> {
+        let zero = self.zero_wire();
+        let mut state = vec![zero; POSEIDON_SPONGE_WIDTH];
+
+        // Absorb all input chunks.
+        for input_chunk in inputs.chunks(POSEIDON_SPONGE_RATE) {
+            for i in 0..input_chunk.len() {
+                state[i] = self.add(state[i], input_chunk[i]);
+            }
+            state = self.poseidon_permutation(params, &state);
+        }
+
+        // Squeeze until we have the desired number of outputs.
+        let mut outputs = Vec::new();
+        loop {
+            for i in 0..POSEIDON_SPONGE_RATE {
+                outputs.push(state[i]);
+                if outputs.len() == num_outputs {
+                    return outputs;
+                }
+            }
+            state = self.poseidon_permutation(params, &state);
+        }
+    }
+
+    /// The in-circuit equivalent of `poseidon_permutation`.
+    ///
+    /// Each round's gate adds the next round's constants, so only the first round's constants need
+    /// separate gates. With 128-bit security, this costs 69 gates, compared to 33 for
+    /// `rescue_permutation`.
+    pub fn poseidon_permutation(
+        &mut self,
+        params: &PoseidonParams<C::ScalarField>,
+        inputs: &[Target<C::ScalarField>],
+    ) -> Vec<Target<C::ScalarField>> {
+        assert_eq!(params.width(), POSEIDON_SPONGE_WIDTH);
+        assert_eq!(inputs.len(), POSEIDON_SPONGE_WIDTH);
+        let all_constants = params.round_constants();
+
+        // Add the first round's constants, and route the results into the first round's gate.
+        let one = self.one_wire();
+        let first_round_inputs: Vec<_> = (0..POSEIDON_SPONGE_WIDTH)
+            .map(|i| {
+                self.arithmetic(
+                    all_constants[0][i],
+                    one,
+                    one,
+                    C::ScalarField::ONE,
+                    inputs[i],
+                )
+            })
+            .collect();
+        for (i, &input) in first_round_inputs.iter().enumerate() {
+            self.copy(
+                input,
+                Target::Wire(Wire {
+                    gate: self.num_gates(),
+                    input: PoseidonGate::<C>::wire_acc(i),
+                }),
+            );
+        }
+
+        let num_rounds = all_constants.len();
+        for r in 0..num_rounds {
+            let next_constants = if r + 1 < num_rounds {
+                all_constants[r + 1].clone()
+            } else {
+                vec![C::ScalarField::ZERO; POSEIDON_SPONGE_WIDTH]
+            };
+
+            let index = self.num_gates();
+            if params.is_full_round(r) {
+                self.add_gate(RescueStepBGate::new(index), next_constants);
+            } else {
+                self.add_gate(PoseidonGate::new(index), next_constants);
+            }
+        }
+
+        // Use a BufferGate to receive the final accumulator states.
+        let gate = self.num_gates();
+        self.add_gate_no_constants(BufferGate::new(gate));
+        (0..POSEIDON_SPONGE_WIDTH)
+            .map(|i| {
+                Target::Wire(Wire {
+                    gate,
+                    input: PoseidonGate::<C>::wire_acc(i),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{poseidon_permutation, poseidon_sponge, CircuitBuilder, Curve, Field, PartialWitness, PoseidonParams, Tweedledum, POSEIDON_SPONGE_WIDTH};
+
+    type F = <Tweedledum as Curve>::ScalarField;
+
+    #[test]
+    fn test_poseidon_permutation() {
+        let params = PoseidonParams::new(POSEIDON_SPONGE_WIDTH, 128);
+        let state: Vec<F> = (0..POSEIDON_SPONGE_WIDTH).map(|_| F::rand()).collect();
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let state_targets = builder.add_virtual_targets(POSEIDON_SPONGE_WIDTH);
+        let result = builder.poseidon_permutation(&params, &state_targets);
+        // The documented 69 gates, plus one for the constant wires.
+        assert_eq!(builder.num_gates(), 70);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_targets(&state_targets, &state);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(
+            witness.get_targets(&result),
+            poseidon_permutation(&params, &state)
+        );
+    }
+
+    #[test]
+    fn test_poseidon_sponge() {
+        let params = PoseidonParams::new(POSEIDON_SPONGE_WIDTH, 128);
+        let inputs: Vec<F> = (0..5).map(|_| F::rand()).collect();
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let input_targets = builder.add_virtual_targets(inputs.len());
+        let outputs = builder.poseidon_sponge(&params, &input_targets, 4);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_targets(&input_targets, &inputs);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(
+            witness.get_targets(&outputs),
+            poseidon_sponge(&params, &inputs, 4)
+        );
+    }
+}
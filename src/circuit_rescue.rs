@@ -0,0 +1,153 @@
+use crate::{BufferGate, CircuitBuilder, HaloCurve, RescueParams, RescueStepAGate, RescueStepBGate, RescueVariant, Target, Wire, RESCUE_SPONGE_WIDTH};
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// The in-circuit equivalent of `RescueParams::sponge`.
+    pub fn rescue_sponge_with_params(
+        &mut self,
+        params: &RescueParams<C::ScalarField>,
+        inputs: &[Target<C::ScalarField>],
+        num_outputs: usize,
+    ) -> Vec<Target<C::ScalarField>> {
+        let rate = params.rate();
+        let zero = self.zero_wire();
+        let mut state = vec![zero; params.width()];
+
+        // Absorb all input chunks.
+        for input_chunk in inputs.chunks(rate) {
+            for i in 0..input_chunk.len() {
+                state[i] = self.add(state[i], input_chunk[i]);
+            }
+            state = self.rescue_permutation_with_params(params, &state);
+        }
+
+        // Squeeze until we have the desired number of outputs.
+        let mut outputs = Vec::new();
+        loop {
+            for i in 0..rate {
+                outputs.push(state[i]);
+                if outputs.len() == num_outputs {
+                    return outputs;
+                }
+            }
+            state = self.rescue_permutation_with_params(params, &state);
+        }
+    }
+
+    /// The in-circuit equivalent of `RescueParams::permute`, which costs two gates per round.
+    ///
+    /// Only the original variant with a width of `RESCUE_SPONGE_WIDTH` is supported, since that is
+    /// what our gates implement. Other widths and Rescue-Prime are native-only: wider steps wouldn't
+    /// fit our layout, as a width-8 step needs more round constants than a gate can hold and a
+    /// width-12 state needs more wires than a row has.
+    pub fn rescue_permutation_with_params(
+        &mut self,
+        params: &RescueParams<C::ScalarField>,
+        inputs: &[Target<C::ScalarField>],
+    ) -> Vec<Target<C::ScalarField>> {
+        assert_eq!(
+            params.variant(),
+            RescueVariant::Rescue,
+            "Rescue-Prime is not supported in circuits"
+        );
+        assert_eq!(
+            params.width(),
+            RESCUE_SPONGE_WIDTH,
+            "Only Rescue instances of width RESCUE_SPONGE_WIDTH are supported in circuits"
+        );
+        assert_eq!(inputs.len(), RESCUE_SPONGE_WIDTH);
+
+        // Route the input wires.
+        for i in 0..RESCUE_SPONGE_WIDTH {
+            self.copy(
+                inputs[i],
+                Target::Wire(Wire {
+                    gate: self.num_gates(),
+                    input: RescueStepAGate::<C>::wire_acc(i),
+                }),
+            );
+        }
+
+        for (a_constants, b_constants) in params.round_constants() {
+            let a_index = self.num_gates();
+            let a_gate = RescueStepAGate::new(a_index);
+            self.add_gate(a_gate, a_constants.clone());
+
+            let b_index = self.num_gates();
+            let b_gate = RescueStepBGate::new(b_index);
+            self.add_gate(b_gate, b_constants.clone());
+        }
+
+        // Use a BufferGate to receive the final accumulator states.
+        let gate = self.num_gates();
+        self.add_gate_no_constants(BufferGate::new(gate));
+        (0..RESCUE_SPONGE_WIDTH)
+            .map(|i| {
+                Target::Wire(Wire {
+                    gate,
+                    input: RescueStepBGate::<C>::wire_acc(i),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CircuitBuilder, Curve, Field, PartialWitness, RescueParams, RescueVariant, Tweedledum};
+
+    type F = <Tweedledum as Curve>::ScalarField;
+
+    fn check_permutation(params: RescueParams<F>, expected_gates: usize) {
+        let state: Vec<F> = (0..params.width()).map(|_| F::rand()).collect();
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let state_targets = builder.add_virtual_targets(params.width());
+        let result = builder.rescue_permutation_with_params(&params, &state_targets);
+        assert_eq!(builder.num_gates(), expected_gates);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_targets(&state_targets, &state);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(witness.get_targets(&result), params.permute(&state));
+    }
+
+    #[test]
+    fn test_rescue_permutation() {
+        check_permutation(RescueParams::new(4, 128), 33);
+    }
+
+    #[test]
+    fn test_rescue_sponge_with_capacity() {
+        let params = RescueParams::with_variant(RescueVariant::Rescue, 4, 2, 128);
+        let inputs: Vec<F> = (0..5).map(|_| F::rand()).collect();
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let input_targets = builder.add_virtual_targets(inputs.len());
+        let outputs = builder.rescue_sponge_with_params(&params, &input_targets, 3);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_targets(&input_targets, &inputs);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(witness.get_targets(&outputs), params.sponge(&inputs, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Rescue-Prime is not supported in circuits")]
+    fn test_rescue_prime_permutation_is_native_only() {
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let params = RescueParams::rescue_prime(4, 1, 128);
+        let state_targets = builder.add_virtual_targets(4);
+        builder.rescue_permutation_with_params(&params, &state_targets);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only Rescue instances of width RESCUE_SPONGE_WIDTH")]
+    fn test_wider_rescue_permutation_is_native_only() {
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let params = RescueParams::new(8, 128);
+        let state_targets = builder.add_virtual_targets(8);
+        builder.rescue_permutation_with_params(&params, &state_targets);
+    }
+}
@@ -0,0 +1,67 @@
+use num::BigUint;
+
+use crate::{rsa_pkcs1v15_sha256_encode, BigIntTarget, CircuitBuilder, HaloCurve};
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// Verifies a PKCS#1 v1.5 RSA signature of a SHA-256 digest. The digest is interpreted as a
+    /// big-endian integer, and must fit in 256 bits. `modulus_len` is the modulus length in bytes,
+    /// which determines the padding; this asserts that `modulus` is exactly that long.
+    pub fn verify_rsa_pkcs1v15_sha256(
+        &mut self,
+        digest: &BigIntTarget<C::ScalarField>,
+        signature: &BigIntTarget<C::ScalarField>,
+        modulus: &BigIntTarget<C::ScalarField>,
+        modulus_len: usize,
+        exponent: &BigUint,
+    ) {
+        self.assert_bigint_byte_length(modulus, modulus_len);
+
+        // The signature must be a canonical residue.
+        let lt = self.bigint_cmp(signature, modulus).lt;
+        self.assert_one(lt);
+
+        // The encoding is a constant prefix followed by the digest, so we encode an all-zero
+        // digest and add the actual digest to it.
+        let digest_bits = self.bigint_to_bits(digest, 256);
+        let digest = self.bigint_from_bits(&digest_bits, modulus.limb_bits);
+        let padding = rsa_pkcs1v15_sha256_encode(&[0; 32], modulus_len);
+        let padding = self.constant_bigint_with_limb_bits(&padding, modulus.limb_bits);
+        let expected = self.bigint_add(&padding, &digest);
+
+        let actual = self.bigint_mod_pow_constant(signature, exponent, modulus);
+        self.copy_bigint(&actual, &expected);
+    }
+
+    /// Asserts that `x` is exactly `num_bytes` bytes long, i.e. that its bit length is in
+    /// `(8 * (num_bytes - 1), 8 * num_bytes]`.
+    fn assert_bigint_byte_length(&mut self, x: &BigIntTarget<C::ScalarField>, num_bytes: usize) {
+        let bits = self.bigint_to_bits(x, 8 * num_bytes);
+        let top_byte = self.from_bits_le(&bits[8 * (num_bytes - 1)..]);
+        self.assert_nonzero(top_byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use crate::{CircuitBuilder, PartialWitness, Tweedledee, Tweedledum};
+
+    type C = Tweedledum;
+
+    fn byte_length_holds(x: u32, num_bytes: usize) -> bool {
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.constant_bigint(&BigUint::from(x));
+        builder.assert_bigint_byte_length(&x, num_bytes);
+        let circuit = builder.build();
+        circuit.proof_verifies::<Tweedledee>(PartialWitness::new())
+    }
+
+    #[test]
+    fn test_assert_bigint_byte_length() {
+        assert!(byte_length_holds(0x0100, 2));
+        assert!(byte_length_holds(0xffff, 2));
+        assert!(!byte_length_holds(0xff, 2));
+        assert!(!byte_length_holds(0x01_0100, 2));
+    }
+}
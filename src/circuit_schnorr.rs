@@ -0,0 +1,56 @@
+use crate::{AffinePointTarget, CircuitBuilder, Curve, CurveMulOp, HaloCurve, Target};
+
+/// Represents a Schnorr signature over the curve `InnerC`, whose base field is our native field.
+/// As with `CurveMulOp`, the scalar `s` is modelled in the native field.
+#[derive(Copy, Clone, Debug)]
+pub struct SchnorrSignatureTarget<InnerC: Curve> {
+    pub r: AffinePointTarget<InnerC>,
+    pub s: Target<InnerC::BaseField>,
+}
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    pub fn add_virtual_schnorr_signature_target<InnerC: Curve<BaseField = C::ScalarField>>(
+        &mut self,
+    ) -> SchnorrSignatureTarget<InnerC> {
+        SchnorrSignatureTarget {
+            r: self.add_virtual_point_target(),
+            s: self.add_virtual_target(),
+        }
+    }
+
+    /// Verifies a Schnorr signature of `message` under `public_key`, on the curve `InnerC`. This is
+    /// the in-circuit equivalent of `schnorr_verify`, with the builder's security level.
+    ///
+    /// We check that `[s] G - [e] PK = R` with a single `curve_msm`, which enforces that both `s`
+    /// and `e` are below `2^(BITS - 1)`, as `schnorr_verify` does. `curve_msm_endo` would be
+    /// cheaper, but its gate does not currently produce a consistent witness; see
+    /// `test_curve_mul_inv_endo`.
+    pub fn verify_schnorr<InnerC: Curve<BaseField = C::ScalarField>>(
+        &mut self,
+        message: &[Target<C::ScalarField>],
+        sig: SchnorrSignatureTarget<InnerC>,
+        public_key: AffinePointTarget<InnerC>,
+    ) {
+        self.curve_assert_valid(sig.r);
+        self.curve_assert_valid(public_key);
+
+        let mut inputs = sig.r.to_vec();
+        inputs.extend(public_key.to_vec());
+        inputs.extend_from_slice(message);
+        let challenge = self.rescue_hash_n_to_1(&inputs);
+
+        let g = self.constant_affine_point(InnerC::GENERATOR_AFFINE);
+        let neg_public_key = self.curve_neg(public_key);
+        let point = self.curve_msm(&[
+            CurveMulOp {
+                scalar: sig.s,
+                point: g,
+            },
+            CurveMulOp {
+                scalar: challenge,
+                point: neg_public_key,
+            },
+        ]);
+        self.copy_curve(point, sig.r);
+    }
+}
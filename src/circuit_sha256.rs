@@ -0,0 +1,301 @@
+use crate::sha256::SHA256_K;
+use crate::{sha256_pad, BoolTarget, CircuitBuilder, HaloCurve, U32Target, U8Target, SHA256_IV};
+
+/// A 32-bit word used in SHA-256, along with its little-endian bits if they are ever needed.
+#[derive(Clone)]
+struct Sha256Word<C: HaloCurve> {
+    word: U32Target<C::ScalarField>,
+    bits: Option<Vec<BoolTarget<C::ScalarField>>>,
+}
+
+impl<C: HaloCurve> Sha256Word<C> {
+    fn bits(&self) -> &[BoolTarget<C::ScalarField>] {
+        self.bits.as_ref().expect("Word was not decomposed")
+    }
+}
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// Computes the SHA-256 digest of a byte string. The digest is returned as eight big-endian
+    /// words, as in the native `sha256_compress`.
+    ///
+    /// The message length is fixed when the circuit is built, so the padding is constant.
+    pub fn sha256(
+        &mut self,
+        message: &[U8Target<C::ScalarField>],
+    ) -> [U32Target<C::ScalarField>; 8] {
+        let padding = sha256_pad(&vec![0; message.len()]);
+        let mut padded = message.to_vec();
+        for &byte in &padding[message.len()..] {
+            padded.push(self.constant_u8(byte));
+        }
+
+        let words: Vec<_> = padded
+            .chunks(4)
+            .map(|bytes| self.u32_from_be_bytes(&[bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        self.sha256_padded_words(&words)
+    }
+
+    /// Computes the SHA-256 digest of a message consisting of whole big-endian words.
+    pub fn sha256_words(
+        &mut self,
+        message: &[U32Target<C::ScalarField>],
+    ) -> [U32Target<C::ScalarField>; 8] {
+        let bit_len = message.len() as u64 * 32;
+        let mut padded = message.to_vec();
+        padded.push(self.constant_u32(1 << 31));
+        while padded.len() % 16 != 14 {
+            padded.push(self.constant_u32(0));
+        }
+        padded.push(self.constant_u32((bit_len >> 32) as u32));
+        padded.push(self.constant_u32(bit_len as u32));
+        self.sha256_padded_words(&padded)
+    }
+
+    fn sha256_padded_words(
+        &mut self,
+        words: &[U32Target<C::ScalarField>],
+    ) -> [U32Target<C::ScalarField>; 8] {
+        debug_assert_eq!(words.len() % 16, 0);
+        let mut state = [self.constant_u32(0); 8];
+        for (i, &iv_word) in SHA256_IV.iter().enumerate() {
+            state[i] = self.constant_u32(iv_word);
+        }
+
+        for block in words.chunks(16) {
+            let mut block_array = [block[0]; 16];
+            block_array.copy_from_slice(block);
+            state = self.sha256_compress(&state, &block_array);
+        }
+        state
+    }
+
+    /// Applies the SHA-256 compression function to a single 512-bit block.
+    ///
+    /// This costs roughly 65,400 gates, so a single compression just fits in a circuit of degree
+    /// `2^16`.
+    pub fn sha256_compress(
+        &mut self,
+        state: &[U32Target<C::ScalarField>; 8],
+        block: &[U32Target<C::ScalarField>; 16],
+    ) -> [U32Target<C::ScalarField>; 8] {
+        // Expand the message schedule. Only words 1..=61 are ever fed into a sigma function, so
+        // only those need to be decomposed into bits.
+        let mut w: Vec<Sha256Word<C>> = Vec::with_capacity(64);
+        for (t, &word) in block.iter().enumerate() {
+            w.push(self.sha256_word(word, t != 0));
+        }
+        for t in 16..64 {
+            let s0 = self.sha256_small_sigma(w[t - 15].bits(), 7, 18, 3);
+            let s1 = self.sha256_small_sigma(w[t - 2].bits(), 17, 19, 10);
+            let terms = [w[t - 16].word, s0, w[t - 7].word, s1];
+            w.push(self.sha256_sum(&terms, t <= 61));
+        }
+
+        // The bits of d and h are never used, since they are only ever added.
+        let mut vars: Vec<Sha256Word<C>> = Vec::with_capacity(8);
+        for (i, &word) in state.iter().enumerate() {
+            vars.push(self.sha256_word(word, i != 3 && i != 7));
+        }
+        for t in 0..64 {
+            let (a, b, c, d) = (&vars[0], &vars[1], &vars[2], &vars[3]);
+            let (e, f, g, h) = (&vars[4], &vars[5], &vars[6], &vars[7]);
+
+            let big_s1 = self.sha256_big_sigma(e.bits(), 6, 11, 25);
+            let ch_bits: Vec<_> = (0..32)
+                .map(|i| self.bool_select(e.bits()[i], f.bits()[i], g.bits()[i]))
+                .collect();
+            let ch = self.uint_from_bits(&ch_bits);
+            let big_s0 = self.sha256_big_sigma(a.bits(), 2, 13, 22);
+            // maj(a, b, c) is c where a and b differ, and a (or equivalently b) elsewhere.
+            let maj_bits: Vec<_> = (0..32)
+                .map(|i| {
+                    let a_xor_b = self.bool_xor(a.bits()[i], b.bits()[i]);
+                    self.bool_select(a_xor_b, c.bits()[i], a.bits()[i])
+                })
+                .collect();
+            let maj = self.uint_from_bits(&maj_bits);
+            let k = self.constant_u32(SHA256_K[t]);
+
+            // Rather than computing t1 and t2 separately, we sum all the terms of each new
+            // variable at once, so that each needs only a single carry.
+            let t1_terms = [h.word, big_s1, ch, k, w[t].word];
+            let mut new_e_terms = t1_terms.to_vec();
+            new_e_terms.push(d.word);
+            let mut new_a_terms = t1_terms.to_vec();
+            new_a_terms.extend_from_slice(&[big_s0, maj]);
+
+            // After the last round, the new variables are only added to the state.
+            let new_e = self.sha256_sum(&new_e_terms, t < 63);
+            let new_a = self.sha256_sum(&new_a_terms, t < 63);
+
+            vars.pop();
+            vars.insert(4, new_e);
+            vars.remove(3);
+            vars.insert(0, new_a);
+        }
+
+        let mut result = *state;
+        for i in 0..8 {
+            result[i] = self.uint_wrapping_add(state[i], vars[i].word);
+        }
+        result
+    }
+
+    fn sha256_word(&mut self, word: U32Target<C::ScalarField>, with_bits: bool) -> Sha256Word<C> {
+        let bits = if with_bits {
+            Some(self.uint_to_bits(word))
+        } else {
+            None
+        };
+        Sha256Word { word, bits }
+    }
+
+    fn sha256_sum(
+        &mut self,
+        terms: &[U32Target<C::ScalarField>],
+        with_bits: bool,
+    ) -> Sha256Word<C> {
+        if with_bits {
+            let (word, bits) = self.uint_wrapping_add_many_with_bits(terms);
+            Sha256Word {
+                word,
+                bits: Some(bits),
+            }
+        } else {
+            let word = self.uint_wrapping_add_many(terms);
+            Sha256Word { word, bits: None }
+        }
+    }
+
+    /// Returns `rotr(x, r1) ^ rotr(x, r2) ^ rotr(x, r3)`, given the little-endian bits of `x`.
+    fn sha256_big_sigma(
+        &mut self,
+        x_bits: &[BoolTarget<C::ScalarField>],
+        r1: usize,
+        r2: usize,
+        r3: usize,
+    ) -> U32Target<C::ScalarField> {
+        let bits: Vec<_> = (0..32)
+            .map(|i| {
+                let xor = self.bool_xor(x_bits[(i + r1) % 32], x_bits[(i + r2) % 32]);
+                self.bool_xor(xor, x_bits[(i + r3) % 32])
+            })
+            .collect();
+        self.uint_from_bits(&bits)
+    }
+
+    /// Returns `rotr(x, r1) ^ rotr(x, r2) ^ (x >> s)`, given the little-endian bits of `x`.
+    fn sha256_small_sigma(
+        &mut self,
+        x_bits: &[BoolTarget<C::ScalarField>],
+        r1: usize,
+        r2: usize,
+        s: usize,
+    ) -> U32Target<C::ScalarField> {
+        let bits: Vec<_> = (0..32)
+            .map(|i| {
+                let xor = self.bool_xor(x_bits[(i + r1) % 32], x_bits[(i + r2) % 32]);
+                // The shifted-in bits are zero, so XORing them is a no-op.
+                if i + s < 32 {
+                    self.bool_xor(xor, x_bits[i + s])
+                } else {
+                    xor
+                }
+            })
+            .collect();
+        self.uint_from_bits(&bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{sha256, sha256_compress, CircuitBuilder, PartialWitness, Tweedledum, SHA256_IV};
+
+    #[test]
+    fn test_sha256_compress() {
+        let block: [u32; 16] = [0x61626380, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x18];
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let mut state_targets = [builder.constant_u32(0); 8];
+        for target in state_targets.iter_mut() {
+            *target = builder.add_virtual_u32_target();
+        }
+        let mut block_targets = [builder.constant_u32(0); 16];
+        for (target, &word) in block_targets.iter_mut().zip(block.iter()) {
+            *target = builder.constant_u32(word);
+        }
+        let result = builder.sha256_compress(&state_targets, &block_targets);
+        // Guard against regressions in the compression function's size.
+        assert!(builder.num_gates() <= 66_000);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        for (&target, &word) in state_targets.iter().zip(SHA256_IV.iter()) {
+            partial_witness.set_u32_target(target, word);
+        }
+        let witness = circuit.generate_partial_witness(partial_witness);
+        let expected = sha256_compress(&SHA256_IV, &block);
+        for i in 0..8 {
+            assert_eq!(witness.get_u32_target(result[i]), expected[i]);
+        }
+    }
+
+    fn test_sha256_with_len(len: usize) {
+        let message: Vec<u8> = (0..len).map(|i| (i * 37 + 11) as u8).collect();
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let message_targets: Vec<_> = (0..len).map(|_| builder.add_virtual_u8_target()).collect();
+        let digest = builder.sha256(&message_targets);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        for (&target, &byte) in message_targets.iter().zip(message.iter()) {
+            partial_witness.set_u8_target(target, byte);
+        }
+        let witness = circuit.generate_partial_witness(partial_witness);
+        let digest_bytes: Vec<u8> = digest
+            .iter()
+            .flat_map(|&word| witness.get_u32_target(word).to_be_bytes().to_vec())
+            .collect();
+        assert_eq!(digest_bytes, sha256(&message).to_vec());
+    }
+
+    #[test]
+    fn test_sha256_one_block() {
+        test_sha256_with_len(3);
+    }
+
+    #[test]
+    fn test_sha256_two_blocks() {
+        // 56 bytes is the shortest message whose padding spills into a second block.
+        test_sha256_with_len(56);
+    }
+
+    #[test]
+    fn test_sha256_block_aligned() {
+        // A message filling a whole block needs a second block for its padding alone.
+        test_sha256_with_len(64);
+    }
+
+    #[test]
+    fn test_sha256_words() {
+        let message = [0xdeadbeefu32, 0x01234567, 0x89abcdef];
+        let message_bytes: Vec<u8> = message
+            .iter()
+            .flat_map(|word| word.to_be_bytes().to_vec())
+            .collect();
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let message_targets: Vec<_> = message.iter().map(|&w| builder.constant_u32(w)).collect();
+        let digest = builder.sha256_words(&message_targets);
+        let circuit = builder.build();
+
+        let witness = circuit.generate_partial_witness(PartialWitness::new());
+        let digest_bytes: Vec<u8> = digest
+            .iter()
+            .flat_map(|&word| witness.get_u32_target(word).to_be_bytes().to_vec())
+            .collect();
+        assert_eq!(digest_bytes, sha256(&message_bytes).to_vec());
+    }
+}
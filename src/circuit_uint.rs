@@ -0,0 +1,391 @@
+use num::BigUint;
+
+use crate::util::log2_ceil;
+use crate::{biguint_to_field, field_to_biguint, BoolTarget, CircuitBuilder, Field, HaloCurve, PartialWitness, Target, WitnessGenerator};
+
+/// A target which is constrained to hold an unsigned integer less than `2^BITS`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct UintTarget<F: Field, const BITS: usize> {
+    target: Target<F>,
+}
+
+pub type U8Target<F> = UintTarget<F, 8>;
+pub type U32Target<F> = UintTarget<F, 32>;
+pub type U64Target<F> = UintTarget<F, 64>;
+
+impl<F: Field, const BITS: usize> UintTarget<F, BITS> {
+    /// Wraps a target which is already known to be less than `2^BITS`, without adding any
+    /// constraints.
+    pub(crate) fn new_unsafe(target: Target<F>) -> Self {
+        Self { target }
+    }
+
+    pub fn target(self) -> Target<F> {
+        self.target
+    }
+}
+
+impl<C: HaloCurve> CircuitBuilder<C> {
+    /// Adds a virtual target which is range checked to be less than `2^BITS`.
+    pub fn add_virtual_uint_target<const BITS: usize>(
+        &mut self,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        let target = self.add_virtual_target();
+        self.assert_uint(target)
+    }
+
+    pub fn constant_uint<const BITS: usize>(
+        &mut self,
+        value: u64,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        assert!(
+            BITS >= 64 || value >> BITS == 0,
+            "Constant does not fit in {} bits",
+            BITS
+        );
+        let target = self.constant_wire(C::ScalarField::from_canonical_u64(value));
+        UintTarget::new_unsafe(target)
+    }
+
+    /// Range checks `x` to be less than `2^BITS`, and returns it as a `UintTarget`.
+    pub fn assert_uint<const BITS: usize>(
+        &mut self,
+        x: Target<C::ScalarField>,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        self.range_check(x, BITS);
+        UintTarget::new_unsafe(x)
+    }
+
+    pub fn add_virtual_u8_target(&mut self) -> U8Target<C::ScalarField> {
+        self.add_virtual_uint_target()
+    }
+
+    pub fn constant_u8(&mut self, value: u8) -> U8Target<C::ScalarField> {
+        self.constant_uint(value as u64)
+    }
+
+    pub fn add_virtual_u32_target(&mut self) -> U32Target<C::ScalarField> {
+        self.add_virtual_uint_target()
+    }
+
+    pub fn constant_u32(&mut self, value: u32) -> U32Target<C::ScalarField> {
+        self.constant_uint(value as u64)
+    }
+
+    pub fn add_virtual_u64_target(&mut self) -> U64Target<C::ScalarField> {
+        self.add_virtual_uint_target()
+    }
+
+    pub fn constant_u64(&mut self, value: u64) -> U64Target<C::ScalarField> {
+        self.constant_uint(value)
+    }
+
+    /// Returns `(x + y) mod 2^BITS`.
+    pub fn uint_wrapping_add<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+        y: UintTarget<C::ScalarField, BITS>,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        self.uint_wrapping_add_many(&[x, y])
+    }
+
+    /// Returns the sum of the given integers, mod `2^BITS`.
+    pub fn uint_wrapping_add_many<const BITS: usize>(
+        &mut self,
+        terms: &[UintTarget<C::ScalarField, BITS>],
+    ) -> UintTarget<C::ScalarField, BITS> {
+        if terms.len() <= 1 {
+            return terms
+                .first()
+                .copied()
+                .unwrap_or_else(|| UintTarget::new_unsafe(self.zero_wire()));
+        }
+        let low = self.uint_wrapping_add_many_unchecked::<BITS>(terms);
+        self.assert_uint(low)
+    }
+
+    /// Like `uint_wrapping_add_many`, but also returns the little-endian bits of the sum. This is
+    /// cheaper than calling `uint_to_bits` on the sum, since the decomposition doubles as the
+    /// range check.
+    pub fn uint_wrapping_add_many_with_bits<const BITS: usize>(
+        &mut self,
+        terms: &[UintTarget<C::ScalarField, BITS>],
+    ) -> (
+        UintTarget<C::ScalarField, BITS>,
+        Vec<BoolTarget<C::ScalarField>>,
+    ) {
+        if terms.len() <= 1 {
+            let sum = self.uint_wrapping_add_many(terms);
+            return (sum, self.uint_to_bits(sum));
+        }
+        let low = self.uint_wrapping_add_many_unchecked::<BITS>(terms);
+        let bits = self
+            .to_bits_le(low, BITS)
+            .into_iter()
+            .map(BoolTarget::new_unsafe)
+            .collect();
+        (UintTarget::new_unsafe(low), bits)
+    }
+
+    /// Returns the sum of the given integers mod `2^BITS`, with a range checked carry. The caller
+    /// must range check the result to be less than `2^BITS` for the decomposition to be sound.
+    fn uint_wrapping_add_many_unchecked<const BITS: usize>(
+        &mut self,
+        terms: &[UintTarget<C::ScalarField, BITS>],
+    ) -> Target<C::ScalarField> {
+
+        // The sum is less than `terms.len() * 2^BITS`, so the carry fits in this many bits.
+        let carry_bits = log2_ceil(terms.len());
+        assert!(
+            BITS + carry_bits < C::ScalarField::BITS,
+            "Sum could overflow the field"
+        );
+
+        let terms: Vec<_> = terms.iter().map(|t| t.target).collect();
+        let sum = self.add_many(&terms);
+        let carry = self.add_virtual_target();
+
+        struct CarryGenerator<F: Field> {
+            sum: Target<F>,
+            carry: Target<F>,
+            bits: usize,
+        }
+
+        impl<F: Field> WitnessGenerator<F> for CarryGenerator<F> {
+            fn dependencies(&self) -> Vec<Target<F>> {
+                vec![self.sum]
+            }
+
+            fn generate(
+                &self,
+                _constants: &[Vec<F>],
+                witness: &PartialWitness<F>,
+            ) -> PartialWitness<F> {
+                let sum = field_to_biguint(witness.get_target(self.sum));
+                let carry = sum >> self.bits;
+
+                let mut result = PartialWitness::new();
+                result.set_target(self.carry, biguint_to_field(carry));
+                result
+            }
+        }
+
+        self.add_generator(CarryGenerator {
+            sum,
+            carry,
+            bits: BITS,
+        });
+
+        // Enforce sum = carry * 2^BITS + low, with carry range checked here and low range checked
+        // by the caller. Since the field is larger than 2^(BITS + carry_bits), this decomposition
+        // is unique.
+        let base = self.constant_wire(biguint_to_field(BigUint::from(1u32) << BITS));
+        let carry_times_base = self.mul(carry, base);
+        let low = self.sub(sum, carry_times_base);
+        self.range_check(carry, carry_bits);
+        low
+    }
+
+    /// Returns the little-endian bits of `x`.
+    pub fn uint_to_bits<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+    ) -> Vec<BoolTarget<C::ScalarField>> {
+        self.to_bits_le(x.target, BITS)
+            .into_iter()
+            .map(BoolTarget::new_unsafe)
+            .collect()
+    }
+
+    /// Joins `BITS` little-endian bits into an integer.
+    pub fn uint_from_bits<const BITS: usize>(
+        &mut self,
+        bits: &[BoolTarget<C::ScalarField>],
+    ) -> UintTarget<C::ScalarField, BITS> {
+        assert_eq!(bits.len(), BITS);
+        // Since each bit is binary, the result is necessarily less than 2^BITS.
+        let bits: Vec<_> = bits.iter().map(|b| b.target()).collect();
+        UintTarget::new_unsafe(self.from_bits_le(&bits))
+    }
+
+    /// Joins four bytes, most significant first, into a 32-bit word.
+    pub fn u32_from_be_bytes(
+        &mut self,
+        bytes: &[U8Target<C::ScalarField>; 4],
+    ) -> U32Target<C::ScalarField> {
+        // Since each byte is range checked, the result is necessarily less than 2^32.
+        let base = self.constant_wire_u32(1 << 8);
+        let mut sum = self.zero_wire();
+        for byte in bytes {
+            sum = self.mul_add(sum, base, byte.target);
+        }
+        UintTarget::new_unsafe(sum)
+    }
+
+    pub fn uint_and<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+        y: UintTarget<C::ScalarField, BITS>,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        self.uint_bitwise(x, y, Self::bool_and)
+    }
+
+    pub fn uint_or<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+        y: UintTarget<C::ScalarField, BITS>,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        self.uint_bitwise(x, y, Self::bool_or)
+    }
+
+    pub fn uint_xor<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+        y: UintTarget<C::ScalarField, BITS>,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        self.uint_bitwise(x, y, Self::bool_xor)
+    }
+
+    fn uint_bitwise<Op, const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+        y: UintTarget<C::ScalarField, BITS>,
+        op: Op,
+    ) -> UintTarget<C::ScalarField, BITS>
+    where
+        Op: Fn(
+            &mut Self,
+            BoolTarget<C::ScalarField>,
+            BoolTarget<C::ScalarField>,
+        ) -> BoolTarget<C::ScalarField>,
+    {
+        let x_bits = self.uint_to_bits(x);
+        let y_bits = self.uint_to_bits(y);
+        let bits: Vec<_> = x_bits
+            .into_iter()
+            .zip(y_bits)
+            .map(|(x_bit, y_bit)| op(self, x_bit, y_bit))
+            .collect();
+        self.uint_from_bits(&bits)
+    }
+
+    /// Returns the bitwise complement of `x`, i.e. `2^BITS - 1 - x`.
+    pub fn uint_not<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        let max = self.constant_wire(biguint_to_field((BigUint::from(1u32) << BITS) - 1u32));
+        UintTarget::new_unsafe(self.sub(max, x.target))
+    }
+
+    pub fn uint_rotate_left<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+        n: usize,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        let mut bits = self.uint_to_bits(x);
+        bits.rotate_right(n % BITS);
+        self.uint_from_bits(&bits)
+    }
+
+    pub fn uint_rotate_right<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+        n: usize,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        let mut bits = self.uint_to_bits(x);
+        bits.rotate_left(n % BITS);
+        self.uint_from_bits(&bits)
+    }
+
+    /// Returns `(x << n) mod 2^BITS`.
+    pub fn uint_shl<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+        n: usize,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        let n = n.min(BITS);
+        let x_bits = self.uint_to_bits(x);
+        let mut bits = vec![self.constant_bool(false); n];
+        bits.extend_from_slice(&x_bits[..BITS - n]);
+        self.uint_from_bits(&bits)
+    }
+
+    pub fn uint_shr<const BITS: usize>(
+        &mut self,
+        x: UintTarget<C::ScalarField, BITS>,
+        n: usize,
+    ) -> UintTarget<C::ScalarField, BITS> {
+        let n = n.min(BITS);
+        let x_bits = self.uint_to_bits(x);
+        let mut bits = x_bits[n..].to_vec();
+        bits.resize(BITS, self.constant_bool(false));
+        self.uint_from_bits(&bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CircuitBuilder, PartialWitness, Tweedledum};
+
+    #[test]
+    fn test_u32_ops() {
+        let x_value = 0xdead_beefu32;
+        let y_value = 0x8765_4321u32;
+        let z_value = 0xffff_fff0u32;
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let x = builder.add_virtual_u32_target();
+        let y = builder.add_virtual_u32_target();
+        let z = builder.constant_u32(z_value);
+        let sum = builder.uint_wrapping_add(x, y);
+        let sum_many = builder.uint_wrapping_add_many(&[x, y, z]);
+        let and = builder.uint_and(x, y);
+        let or = builder.uint_or(x, y);
+        let xor = builder.uint_xor(x, y);
+        let not = builder.uint_not(x);
+        let rotl = builder.uint_rotate_left(x, 7);
+        let rotr = builder.uint_rotate_right(x, 7);
+        let shl = builder.uint_shl(x, 7);
+        let shr = builder.uint_shr(x, 7);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_u32_target(x, x_value);
+        partial_witness.set_u32_target(y, y_value);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        assert_eq!(witness.get_u32_target(sum), x_value.wrapping_add(y_value));
+        assert_eq!(
+            witness.get_u32_target(sum_many),
+            x_value.wrapping_add(y_value).wrapping_add(z_value)
+        );
+        assert_eq!(witness.get_u32_target(and), x_value & y_value);
+        assert_eq!(witness.get_u32_target(or), x_value | y_value);
+        assert_eq!(witness.get_u32_target(xor), x_value ^ y_value);
+        assert_eq!(witness.get_u32_target(not), !x_value);
+        assert_eq!(witness.get_u32_target(rotl), x_value.rotate_left(7));
+        assert_eq!(witness.get_u32_target(rotr), x_value.rotate_right(7));
+        assert_eq!(witness.get_u32_target(shl), x_value << 7);
+        assert_eq!(witness.get_u32_target(shr), x_value >> 7);
+    }
+
+    #[test]
+    fn test_u64_wrapping_add() {
+        let x_value = 0xffff_ffff_ffff_fff0u64;
+        let y_value = 0x1234_5678_9abc_def0u64;
+
+        let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+        let x = builder.add_virtual_u64_target();
+        let y = builder.constant_u64(y_value);
+        let sum = builder.uint_wrapping_add(x, y);
+        let rotr = builder.uint_rotate_right(sum, 13);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_uint_target(x, x_value);
+        let witness = circuit.generate_partial_witness(partial_witness);
+        let expected = x_value.wrapping_add(y_value);
+        assert_eq!(witness.get_uint_target(sum), expected);
+        assert_eq!(witness.get_uint_target(rotr), expected.rotate_right(13));
+    }
+}
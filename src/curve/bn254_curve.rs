@@ -0,0 +1,80 @@
+use crate::{AffinePoint, Curve, Field, HaloCurve, Bn254Base, Bn254Scalar};
+use serde::{Deserialize, Serialize};
+
+/// The BN254 (a.k.a. alt_bn128) curve, `y^2 = x^3 + 3`, which forms a cycle with Grumpkin.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct Bn254;
+
+impl Curve for Bn254 {
+    type BaseField = Bn254Base;
+    type ScalarField = Bn254Scalar;
+
+    const A: Bn254Base = Bn254Base::ZERO;
+    const B: Bn254Base = Bn254Base::THREE;
+
+    const GENERATOR_AFFINE: AffinePoint<Self> = AffinePoint {
+        x: Bn254Base::ONE,
+        y: Bn254Base::TWO,
+        zero: false,
+    };
+}
+
+impl HaloCurve for Bn254 {
+    const ZETA: Self::BaseField = Bn254Base {
+        limbs: [
+            0x71930c11d782e155, 0xa6bb947cffbe3323,
+            0xaa303344d4741444, 0x2c3b3f0d26594943
+        ],
+    };
+    const ZETA_SCALAR: Self::ScalarField = Bn254Scalar {
+        limbs: [
+            0x93e7cede4a0329b3, 0x7d4fdca77a96c167,
+            0x8be4ba08b19a750a, 0x1cbd5653a5661c25
+        ],
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::{Curve, HaloCurve, ProjectivePoint};
+    use crate::{Field, Bn254};
+
+    /// A simple, somewhat inefficient implementation of multiplication which is used as a reference
+    /// for correctness.
+    fn mul_naive(
+        lhs: <Bn254 as Curve>::ScalarField,
+        rhs: ProjectivePoint<Bn254>,
+    ) -> ProjectivePoint<Bn254> {
+        let mut g = rhs;
+        let mut sum = ProjectivePoint::ZERO;
+        for limb in lhs.to_canonical().iter() {
+            for j in 0..64 {
+                if (limb >> j & 1u64) != 0u64 {
+                    sum = sum + g;
+                }
+                g = g.double();
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn test_endomorphism_bn254() {
+        type C = Bn254;
+        let g = C::convert(<C as Curve>::ScalarField::rand()) * C::GENERATOR_PROJECTIVE;
+        let g = g.to_affine();
+        let h = g.endomorphism();
+        assert_eq!(
+            h,
+            mul_naive(Bn254::ZETA_SCALAR, g.to_projective()).to_affine()
+        );
+    }
+
+    #[test]
+    fn is_safe_curve() {
+        type C = Bn254;
+        assert!(
+           C::is_safe_curve()
+        );
+    }
+}
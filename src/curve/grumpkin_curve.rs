@@ -0,0 +1,105 @@
+use crate::{AffinePoint, Curve, Field, HaloCurve, Bn254Scalar, Bn254Base};
+use serde::{Deserialize, Serialize};
+
+/// The Grumpkin curve, `y^2 = x^3 - 17`, whose base field is the scalar field of BN254 and
+/// vice versa.
+///
+/// Its scalar field has 2-adicity 1, which is too small for our FFTs, so circuits can't be built
+/// over Grumpkin. It can only serve as the `InnerC` of BN254 circuits, which rules out recursion
+/// over this cycle in either direction.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct Grumpkin;
+
+impl Curve for Grumpkin {
+    type BaseField = Bn254Scalar;
+    type ScalarField = Bn254Base;
+
+    const A: Bn254Scalar = Bn254Scalar::ZERO;
+    // -17
+    const B: Bn254Scalar = Bn254Scalar {
+        limbs: [
+            0xdd7056026000005a, 0x223fa97acb319311,
+            0xcc388229877910c0, 0x034394632b724eaa
+        ],
+    };
+
+    const GENERATOR_AFFINE: AffinePoint<Self> = AffinePoint {
+        x: Bn254Scalar::ONE,
+        y: Bn254Scalar {
+            limbs: [
+                0x11b2dff1448c41d8, 0x23d3446f21c77dc3,
+                0xaa7b8cf435dfafbb, 0x14b34cf69dc25d68
+            ],
+        },
+        zero: false,
+    };
+}
+
+impl HaloCurve for Grumpkin {
+    const ZETA: Self::BaseField = Bn254Scalar {
+        limbs: [
+            0x93e7cede4a0329b3, 0x7d4fdca77a96c167,
+            0x8be4ba08b19a750a, 0x1cbd5653a5661c25
+        ],
+    };
+    const ZETA_SCALAR: Self::ScalarField = Bn254Base {
+        limbs: [
+            0x71930c11d782e155, 0xa6bb947cffbe3323,
+            0xaa303344d4741444, 0x2c3b3f0d26594943
+        ],
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::{Curve, HaloCurve, ProjectivePoint};
+    use crate::{CircuitBuilder, Field, Grumpkin};
+
+    /// A simple, somewhat inefficient implementation of multiplication which is used as a reference
+    /// for correctness.
+    fn mul_naive(
+        lhs: <Grumpkin as Curve>::ScalarField,
+        rhs: ProjectivePoint<Grumpkin>,
+    ) -> ProjectivePoint<Grumpkin> {
+        let mut g = rhs;
+        let mut sum = ProjectivePoint::ZERO;
+        for limb in lhs.to_canonical().iter() {
+            for j in 0..64 {
+                if (limb >> j & 1u64) != 0u64 {
+                    sum = sum + g;
+                }
+                g = g.double();
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn test_endomorphism_grumpkin() {
+        type C = Grumpkin;
+        let g = C::convert(<C as Curve>::ScalarField::rand()) * C::GENERATOR_PROJECTIVE;
+        let g = g.to_affine();
+        let h = g.endomorphism();
+        assert_eq!(
+            h,
+            mul_naive(Grumpkin::ZETA_SCALAR, g.to_projective()).to_affine()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "2-adicity is too small")]
+    fn test_circuits_unsupported() {
+        let mut builder = CircuitBuilder::<Grumpkin>::new(128);
+        let x = builder.add_virtual_target();
+        builder.assert_nonzero(x);
+        builder.build();
+    }
+
+    #[test]
+    fn is_safe_curve() {
+        type C = Grumpkin;
+        assert!(
+           C::is_safe_curve()
+        );
+    }
+}
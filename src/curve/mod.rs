@@ -1,22 +1,28 @@
 pub use bls12_377_curve::*;
+pub use bn254_curve::*;
 pub use curve::*;
 pub use curve_adds::*;
 pub use curve_msm::*;
 pub use curve_multiplication::*;
 pub use curve_summations::*;
+pub use grumpkin_curve::*;
 pub use tweedledee_curve::*;
 pub use tweedledum_curve::*;
 pub use pallas_curve::*;
+pub use secp256k1_curve::*;
 pub use vesta_curve::*;
 
 mod bls12_377_curve;
+mod bn254_curve;
 #[allow(clippy::module_inception)]
 mod curve;
 mod curve_adds;
 mod curve_msm;
 mod curve_multiplication;
 mod curve_summations;
+mod grumpkin_curve;
 mod tweedledee_curve;
 mod tweedledum_curve;
 mod pallas_curve;
+mod secp256k1_curve;
 mod vesta_curve;
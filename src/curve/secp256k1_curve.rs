@@ -0,0 +1,115 @@
+use crate::{AffinePoint, Curve, Field, HaloCurve, Secp256k1Base, Secp256k1Scalar};
+use serde::{Deserialize, Serialize};
+
+/// The secp256k1 curve, `y^2 = x^3 + 7`, as used by Bitcoin and Ethereum signatures. It does not
+/// form a cycle with any of our other curves, so it is mainly useful as a foreign curve.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct Secp256k1;
+
+impl Curve for Secp256k1 {
+    type BaseField = Secp256k1Base;
+    type ScalarField = Secp256k1Scalar;
+
+    const A: Secp256k1Base = Secp256k1Base::ZERO;
+    const B: Secp256k1Base = Secp256k1Base {
+        limbs: [0x0000000700001ab7, 0, 0, 0],
+    };
+
+    const GENERATOR_AFFINE: AffinePoint<Self> = AffinePoint {
+        x: Secp256k1Base {
+            limbs: [
+                0xd7362e5a487e2097, 0x231e295329bc66db,
+                0x979f48c033fd129c, 0x9981e643e9089f48
+            ],
+        },
+        y: Secp256k1Base {
+            limbs: [
+                0xb15ea6d2d3dbabe2, 0x8dfc5d5d1f1dc64d,
+                0x70b6b59aac19c136, 0xcf3f851fd4a582d6
+            ],
+        },
+        zero: false,
+    };
+}
+
+/// The GLV endomorphism constants: `ZETA` is the cube root of unity `beta` in the base field, and
+/// `ZETA_SCALAR` is the corresponding eigenvalue `lambda`, so that `(beta x, y) = [lambda] (x, y)`.
+impl HaloCurve for Secp256k1 {
+    const ZETA: Self::BaseField = Secp256k1Base {
+        limbs: [
+            0x58a4361c8e81894e, 0x03fde1631c4b80af,
+            0xf8e98978d02e3905, 0x7a4a36aebcbb3d53
+        ],
+    };
+    const ZETA_SCALAR: Self::ScalarField = Secp256k1Scalar {
+        limbs: [
+            0xf07deb3dc9926c9e, 0x2c93e7ad83c6944c,
+            0x73a9660652697d91, 0x532840178558d639
+        ],
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::{Curve, HaloCurve, ProjectivePoint};
+    use crate::{Field, Secp256k1, Secp256k1Base};
+
+    /// A simple, somewhat inefficient implementation of multiplication which is used as a reference
+    /// for correctness.
+    fn mul_naive(
+        lhs: <Secp256k1 as Curve>::ScalarField,
+        rhs: ProjectivePoint<Secp256k1>,
+    ) -> ProjectivePoint<Secp256k1> {
+        let mut g = rhs;
+        let mut sum = ProjectivePoint::ZERO;
+        for limb in lhs.to_canonical().iter() {
+            for j in 0..64 {
+                if (limb >> j & 1u64) != 0u64 {
+                    sum = sum + g;
+                }
+                g = g.double();
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn test_endomorphism_secp256k1() {
+        type C = Secp256k1;
+        let g = C::convert(<C as Curve>::ScalarField::rand()) * C::GENERATOR_PROJECTIVE;
+        let g = g.to_affine();
+        let h = g.endomorphism();
+        assert_eq!(
+            h,
+            mul_naive(Secp256k1::ZETA_SCALAR, g.to_projective()).to_affine()
+        );
+    }
+
+    #[test]
+    fn test_generator_double() {
+        type C = Secp256k1;
+        let g = C::GENERATOR_AFFINE;
+        assert!(g.is_valid());
+        let g2 = g.double();
+        assert_eq!(
+            g2.x,
+            Secp256k1Base::from_canonical([
+                0xabac09b95c709ee5, 0x5c778e4b8cef3ca7, 0x3045406e95c07cd8, 0xc6047f9441ed7d6d,
+            ])
+        );
+        assert_eq!(
+            g2.y,
+            Secp256k1Base::from_canonical([
+                0x236431a950cfe52a, 0xf7f632653266d0e1, 0xa3c58419466ceaee, 0x1ae168fea63dc339,
+            ])
+        );
+    }
+
+    #[test]
+    fn is_safe_curve() {
+        type C = Secp256k1;
+        assert!(
+           C::is_safe_curve()
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use num::BigUint;
+
+use crate::{biguint_to_field, field_to_biguint, AffinePoint, Curve, Field};
+
+/// An ECDSA signature over the curve `C`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct EcdsaSignature<C: Curve> {
+    pub r: C::ScalarField,
+    pub s: C::ScalarField,
+}
+
+/// Reduces a base field element modulo the order of the scalar field, as ECDSA does with the `x`
+/// coordinate of the nonce point. Unlike `Curve::try_convert_b2s`, this never fails.
+pub fn base_to_scalar_reduced<C: Curve>(x: C::BaseField) -> C::ScalarField {
+    let scalar_order = field_to_biguint(C::ScalarField::NEG_ONE) + BigUint::from(1u32);
+    biguint_to_field(field_to_biguint(x) % scalar_order)
+}
+
+/// Computes the public key `[secret_key] G` associated with a secret key.
+pub fn ecdsa_public_key<C: Curve>(secret_key: C::ScalarField) -> AffinePoint<C> {
+    (C::convert(secret_key) * C::GENERATOR_PROJECTIVE).to_affine()
+}
+
+/// Signs a message hash using the given nonce. Returns `None` if the nonce yields a degenerate
+/// signature, in which case the caller should retry with a fresh nonce.
+pub fn ecdsa_sign_with_nonce<C: Curve>(
+    msg_hash: C::ScalarField,
+    secret_key: C::ScalarField,
+    nonce: C::ScalarField,
+) -> Option<EcdsaSignature<C>> {
+    let k_inv = nonce.multiplicative_inverse()?;
+    let nonce_point = (C::convert(nonce) * C::GENERATOR_PROJECTIVE).to_affine();
+    let r = base_to_scalar_reduced::<C>(nonce_point.x);
+    if r.is_zero() {
+        return None;
+    }
+    let s = k_inv * (msg_hash + r * secret_key);
+    if s.is_zero() {
+        return None;
+    }
+    Some(EcdsaSignature { r, s })
+}
+
+/// Signs a message hash with a random nonce.
+pub fn ecdsa_sign<C: Curve>(
+    msg_hash: C::ScalarField,
+    secret_key: C::ScalarField,
+) -> EcdsaSignature<C> {
+    loop {
+        if let Some(sig) = ecdsa_sign_with_nonce(msg_hash, secret_key, C::ScalarField::rand()) {
+            return sig;
+        }
+    }
+}
+
+/// Verifies an ECDSA signature of a message hash against a public key.
+pub fn ecdsa_verify<C: Curve>(
+    msg_hash: C::ScalarField,
+    sig: EcdsaSignature<C>,
+    public_key: AffinePoint<C>,
+) -> bool {
+    if public_key.zero || !public_key.is_valid() {
+        return false;
+    }
+    let s_inv = match sig.s.multiplicative_inverse() {
+        Some(s_inv) if sig.r.is_nonzero() => s_inv,
+        _ => return false,
+    };
+    let u1 = msg_hash * s_inv;
+    let u2 = sig.r * s_inv;
+    let point = C::convert(u1) * C::GENERATOR_PROJECTIVE
+        + C::convert(u2) * public_key.to_projective();
+    let point = point.to_affine();
+    !point.zero && base_to_scalar_reduced::<C>(point.x) == sig.r
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ecdsa_public_key, ecdsa_sign, ecdsa_sign_with_nonce, ecdsa_verify, Field, Secp256k1,
+        Secp256k1Base, Secp256k1Scalar,
+    };
+
+    type C = Secp256k1;
+
+    #[test]
+    fn test_ecdsa_known_signature() {
+        let secret_key = Secp256k1Scalar::from_canonical([
+            0x7b9d2c4e6f8a0b1c, 0x2b4d6f8a0c1e3f5a, 0x7c8e0b1d3f5a7c9e, 0x3a8f1c5e9b2d4f6a,
+        ]);
+        let nonce = Secp256k1Scalar::from_canonical([
+            0x0e2f4a6b8c0d1e3f, 0x5c7d9e1f2a4b6c8d, 0x0a2b4c6d8e0f1a3b, 0x4b2e8f1a3c5d7e9f,
+        ]);
+        let msg_hash = Secp256k1Scalar::from_canonical([
+            0x4addd200126d9069, 0xfc2d4b1fa3d67728, 0xb92dc18148a1d65d, 0x7f83b1657ff1fc53,
+        ]);
+
+        let public_key = ecdsa_public_key::<C>(secret_key);
+        assert_eq!(
+            public_key.x,
+            Secp256k1Base::from_canonical([
+                0x25368f846cd65630, 0x6923f43eea16ad72, 0x722e2d6361b72689, 0x4a89df16b66aab34,
+            ])
+        );
+        assert_eq!(
+            public_key.y,
+            Secp256k1Base::from_canonical([
+                0x6c0d5ca4a162d459, 0x71c28d99d5c70b89, 0x31b2ff52c0164fdf, 0xbb17a972ca1d54c3,
+            ])
+        );
+
+        let sig = ecdsa_sign_with_nonce::<C>(msg_hash, secret_key, nonce).unwrap();
+        assert_eq!(
+            sig.r,
+            Secp256k1Scalar::from_canonical([
+                0xa27d9e8ae7fd775e, 0x556618c11f148644, 0xaa78ff9ead61929e, 0xd8ade63f40901714,
+            ])
+        );
+        assert_eq!(
+            sig.s,
+            Secp256k1Scalar::from_canonical([
+                0x601435977c816f29, 0xa1acab8cb92d9a71, 0xa87064afadd857c1, 0x369a3f5d6aa46d8d,
+            ])
+        );
+        assert!(ecdsa_verify(msg_hash, sig, public_key));
+    }
+
+    #[test]
+    fn test_ecdsa_sign_and_verify() {
+        let secret_key = Secp256k1Scalar::rand();
+        let public_key = ecdsa_public_key::<C>(secret_key);
+        let msg_hash = Secp256k1Scalar::rand();
+        let sig = ecdsa_sign::<C>(msg_hash, secret_key);
+        assert!(ecdsa_verify(msg_hash, sig, public_key));
+
+        // A different message, a tampered signature or a different key must all be rejected.
+        assert!(!ecdsa_verify(msg_hash + Secp256k1Scalar::ONE, sig, public_key));
+        let mut bad_sig = sig;
+        bad_sig.s = bad_sig.s + Secp256k1Scalar::ONE;
+        assert!(!ecdsa_verify(msg_hash, bad_sig, public_key));
+        let other_key = ecdsa_public_key::<C>(Secp256k1Scalar::rand());
+        assert!(!ecdsa_verify(msg_hash, sig, other_key));
+    }
+}
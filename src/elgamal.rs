@@ -0,0 +1,75 @@
+use crate::{AffinePoint, Curve};
+
+/// An ElGamal ciphertext `([r] G, M + [r] PK)` of a message point `M` under a public key `PK`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ElGamalCiphertext<C: Curve> {
+    pub c1: AffinePoint<C>,
+    pub c2: AffinePoint<C>,
+}
+
+/// Computes the public key `[secret_key] G` associated with a secret key.
+pub fn elgamal_public_key<C: Curve>(secret_key: C::ScalarField) -> AffinePoint<C> {
+    (C::convert(secret_key) * C::GENERATOR_PROJECTIVE).to_affine()
+}
+
+/// Encrypts a message point using the given randomness.
+pub fn elgamal_encrypt<C: Curve>(
+    public_key: AffinePoint<C>,
+    message: AffinePoint<C>,
+    randomness: C::ScalarField,
+) -> ElGamalCiphertext<C> {
+    let c1 = C::convert(randomness) * C::GENERATOR_PROJECTIVE;
+    let c2 = message.to_projective() + C::convert(randomness) * public_key.to_projective();
+    ElGamalCiphertext {
+        c1: c1.to_affine(),
+        c2: c2.to_affine(),
+    }
+}
+
+/// Re-randomizes a ciphertext, giving a fresh encryption of the same message which can't be
+/// linked to the original without the secret key.
+pub fn elgamal_reencrypt<C: Curve>(
+    public_key: AffinePoint<C>,
+    ciphertext: ElGamalCiphertext<C>,
+    randomness: C::ScalarField,
+) -> ElGamalCiphertext<C> {
+    let c1 = ciphertext.c1.to_projective() + C::convert(randomness) * C::GENERATOR_PROJECTIVE;
+    let c2 = ciphertext.c2.to_projective() + C::convert(randomness) * public_key.to_projective();
+    ElGamalCiphertext {
+        c1: c1.to_affine(),
+        c2: c2.to_affine(),
+    }
+}
+
+/// Recovers the message point `c2 - [secret_key] c1`.
+pub fn elgamal_decrypt<C: Curve>(
+    secret_key: C::ScalarField,
+    ciphertext: ElGamalCiphertext<C>,
+) -> AffinePoint<C> {
+    let shared = C::convert(secret_key) * ciphertext.c1.to_projective();
+    (ciphertext.c2.to_projective() + -shared).to_affine()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{blake_hash_usize_to_curve, elgamal_decrypt, elgamal_encrypt, elgamal_public_key, elgamal_reencrypt, Curve, Field, Tweedledum};
+
+    type C = Tweedledum;
+    type SF = <C as Curve>::ScalarField;
+
+    #[test]
+    fn test_elgamal_encrypt_and_reencrypt() {
+        let secret_key = SF::rand();
+        let public_key = elgamal_public_key::<C>(secret_key);
+        let message = blake_hash_usize_to_curve::<C>(42);
+
+        let ciphertext = elgamal_encrypt(public_key, message, SF::rand());
+        assert_eq!(elgamal_decrypt(secret_key, ciphertext), message);
+
+        let reencrypted = elgamal_reencrypt(public_key, ciphertext, SF::rand());
+        assert_ne!(reencrypted, ciphertext);
+        assert_eq!(elgamal_decrypt(secret_key, reencrypted), message);
+
+        assert_ne!(elgamal_decrypt(SF::rand(), ciphertext), message);
+    }
+}
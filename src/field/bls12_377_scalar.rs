@@ -1,236 +1,27 @@
 //! This module implements field arithmetic for BLS12-377's scalar field.
 
-use std::cmp::Ordering::Less;
-use std::convert::TryInto;
-use std::ops::{Add, Div, Mul, Neg, Sub};
-use rand::Rng;
-
-use unroll::unroll_for_loops;
-
-use crate::{add_no_overflow, cmp, Field, sub, field_to_biguint, rand_range, rand_range_from_rng};
-use crate::nonzero_multiplicative_inverse;
-use std::cmp::Ordering;
-use std::fmt;
-use std::fmt::{Display, Formatter};
+use crate::{monty_field, MontyRepr};
 
 /// An element of the BLS12 group's scalar field.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
 pub struct Bls12377Scalar {
     /// Montgomery representation, encoded with little-endian u64 limbs.
     pub limbs: [u64; 4],
 }
 
-impl Bls12377Scalar {
+impl MontyRepr<4> for Bls12377Scalar {
     /// The order of the field:
     /// 8444461749428370424248824938781546531375899335154063827935233455917409239041
-    pub const ORDER: [u64; 4] = [725501752471715841, 6461107452199829505, 6968279316240510977, 1345280370688173398];
-
-    /// R in the context of the Montgomery reduction, i.e. 2^256 % |F|.
-    pub(crate) const R: [u64; 4] =
-        [9015221291577245683, 8239323489949974514, 1646089257421115374, 958099254763297437];
-
-    /// R^2 in the context of the Montgomery reduction, i.e. 2^256^2 % |F|.
-    pub(crate) const R2: [u64; 4] =
-        [2726216793283724667, 14712177743343147295, 12091039717619697043, 81024008013859129];
-
-    /// R^3 in the context of the Montgomery reduction, i.e. 2^256^3 % |F|.
-    pub(crate) const R3: [u64; 4] =
-        [7656847007262524748, 7083357369969088153, 12818756329091487507, 432872940405820890];
-
-    /// In the context of Montgomery multiplication, µ = -|F|^-1 mod 2^64.
-    const MU: u64 = 725501752471715839;
-
-    pub fn from_canonical(c: [u64; 4]) -> Self {
-        // We compute M(c, R^2) = c * R^2 * R^-1 = c * R.
-        Self { limbs: Self::montgomery_multiply(c, Self::R2) }
-    }
-
-    pub fn to_canonical(&self) -> [u64; 4] {
-        // Let x * R = self. We compute M(x * R, 1) = x * R * R^-1 = x.
-        Self::montgomery_multiply(self.limbs, [1, 0, 0, 0])
-    }
-
-    #[unroll_for_loops]
-    fn montgomery_multiply(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
-        // Interleaved Montgomery multiplication, as described in Algorithm 2 of
-        // https://eprint.iacr.org/2017/1057.pdf
-
-        // Note that in the loop below, to avoid explicitly shifting c, we will treat i as the least
-        // significant digit and wrap around.
-        let mut c = [0u64; 5];
-
-        for i in 0..4 {
-            // Add a[i] b to c.
-            let mut carry = 0;
-            for j in 0..4 {
-                let result = c[(i + j) % 5] as u128 + a[i] as u128 * b[j] as u128 + carry as u128;
-                c[(i + j) % 5] = result as u64;
-                carry = (result >> 64) as u64;
-            }
-            c[(i + 4) % 5] += carry;
-
-            // q = u c mod r = u c[0] mod r.
-            let q = Self::MU.wrapping_mul(c[i]);
-
-            // C += N q
-            carry = 0;
-            for j in 0..4 {
-                let result = c[(i + j) % 5] as u128 + q as u128 * Self::ORDER[j] as u128 + carry as u128;
-                c[(i + j) % 5] = result as u64;
-                carry = (result >> 64) as u64;
-            }
-            c[(i + 4) % 5] += carry;
-
-            debug_assert_eq!(c[i], 0);
-        }
-
-        let mut result = [c[4], c[0], c[1], c[2]];
-        // Final conditional subtraction.
-        if cmp(result, Self::ORDER) != Less {
-            result = sub(result, Self::ORDER);
-        }
-        result
-    }
-}
-
-impl Add<Bls12377Scalar> for Bls12377Scalar {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self {
-        // First we do a widening addition, then we reduce if necessary.
-        let sum = add_no_overflow(self.limbs, rhs.limbs);
-        let limbs = if cmp(sum, Self::ORDER) == Less {
-            sum
-        } else {
-            sub(sum, Self::ORDER)
-        };
-        Self { limbs }
-    }
-}
-
-impl Sub<Bls12377Scalar> for Bls12377Scalar {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self {
-        let limbs = if cmp(self.limbs, rhs.limbs) == Less {
-            // Underflow occurs, so we compute the difference as `self + (-rhs)`.
-            add_no_overflow(self.limbs, (-rhs).limbs)
-        } else {
-            // No underflow, so it's faster to subtract directly.
-            sub(self.limbs, rhs.limbs)
-        };
-        Self { limbs }
-    }
-}
-
-impl Mul<Self> for Bls12377Scalar {
-    type Output = Self;
-
-    fn mul(self, rhs: Self) -> Self {
-        Self { limbs: Self::montgomery_multiply(self.limbs, rhs.limbs) }
-    }
-}
-
-impl Div<Bls12377Scalar> for Bls12377Scalar {
-    type Output = Self;
-
-    fn div(self, rhs: Self) -> Self {
-        self * rhs.multiplicative_inverse().expect("No inverse")
-    }
-}
-
-impl Neg for Bls12377Scalar {
-    type Output = Self;
-
-    fn neg(self) -> Self {
-        if self == Self::ZERO {
-            Self::ZERO
-        } else {
-            Self { limbs: sub(Self::ORDER, self.limbs) }
-        }
-    }
+    const ORDER: [u64; 4] = [725501752471715841, 6461107452199829505, 6968279316240510977, 1345280370688173398];
 }
 
-impl Field for Bls12377Scalar {
-    const BITS: usize = 253;
-    const BYTES: usize = 32;
-
-    const ZERO: Self = Self { limbs: [0; 4] };
-    const ONE: Self = Self { limbs: Self::R };
-    const TWO: Self = Self { limbs: [17304940830682775525, 10017539527700119523, 14770643272311271387, 570918138838421475] };
-    const THREE: Self = Self { limbs: [7147916296078753751, 11795755565450264533, 9448453213491875784, 183737022913545514] };
-    const FOUR: Self = Self { limbs: [16163137587655999434, 1588334981690687431, 11094542470912991159, 1141836277676842951] };
-    const FIVE: Self = Self { limbs: [6006113053051977660, 3366551019440832441, 5772352412093595556, 754655161751966990] };
-    const NEG_ONE: Self = Self { limbs: [10157024534604021774, 16668528035959406606, 5322190058819395602, 387181115924875961] };
-
-    const MULTIPLICATIVE_SUBGROUP_GENERATOR: Self = Self { limbs: [1855201571499933546, 8511318076631809892, 6222514765367795509, 1122129207579058019] };
-
-    /// x^11 is a permutation in this field.
-    const ALPHA: Self = Self { limbs: [1855201571499933546, 8511318076631809892, 6222514765367795509, 1122129207579058019] };
-
-    const TWO_ADICITY: usize = 47;
-
-    /// 60001509534603559531609739528203892656505753216962260608619555
-    const T: Self = Self { limbs: [725501752471715841, 6461107452199829505, 6968279316240510977, 1345280370688042326] };
-
-    fn to_canonical_u64_vec(&self) -> Vec<u64> {
-        self.to_canonical().to_vec()
-    }
-
-    fn from_canonical_u64_vec(v: Vec<u64>) -> Self {
-        Self::from_canonical(v[..].try_into().unwrap())
-    }
-
-    fn from_canonical_u64(n: u64) -> Self {
-        Self::from_canonical([n, 0, 0, 0])
-    }
-
-    fn is_valid_canonical_u64(v: &[u64]) -> bool {
-        v.len() == 4 && cmp(v[..].try_into().unwrap(), Self::ORDER) == Less
-    }
-
-    fn multiplicative_inverse_assuming_nonzero(&self) -> Self {
-        // Let x R = self. We compute M((x R)^-1, R^3) = x^-1 R^-1 R^3 R^-1 = x^-1 R.
-        let self_r_inv = nonzero_multiplicative_inverse(self.limbs, Self::ORDER);
-        Self { limbs: Self::montgomery_multiply(self_r_inv, Self::R3) }
-    }
-
-    fn rand() -> Self {
-        Self {
-            limbs: rand_range(Self::ORDER),
-        }
-    }
-
-    fn rand_from_rng<R: Rng>(rng: &mut R) -> Self {
-        Self {
-            limbs: rand_range_from_rng(Self::ORDER, rng),
-        }
-    }
-}
-
-impl Ord for Bls12377Scalar {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.cmp_helper(other)
-    }
-}
-
-impl PartialOrd for Bls12377Scalar {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Display for Bls12377Scalar {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        field_to_biguint(*self).fmt(f)
-    }
-}
+monty_field!(Bls12377Scalar, 4, generator: 11, alpha: 11);
 
 #[cfg(test)]
 mod tests {
     use crate::{Bls12377Scalar, Field};
     use crate::conversions::u64_slice_to_biguint;
-    use crate::test_arithmetic;
+    use crate::MontyRepr;
 
     #[test]
     fn bls12scalar_to_and_from_canonical() {
@@ -337,15 +128,4 @@ mod tests {
             }
         }
     }
-
-    #[test]
-    fn primitive_root_order() {
-        for n_power in 0..10 {
-            let root = Bls12377Scalar::primitive_root_of_unity(n_power);
-            let order = Bls12377Scalar::generator_order(root);
-            assert_eq!(order, 1 << n_power, "2^{}'th primitive root", n_power);
-        }
-    }
-
-    test_arithmetic!(crate::Bls12377Scalar);
 }
@@ -0,0 +1,20 @@
+use crate::{monty_field, MontyRepr};
+
+/// An element of the BN254 group's base field, which is also the scalar field of Grumpkin.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Bn254Base {
+    /// Montgomery representation, encoded with little-endian u64 limbs.
+    pub limbs: [u64; 4],
+}
+
+impl MontyRepr<4> for Bn254Base {
+    /// The order of the field: 0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47
+    const ORDER: [u64; 4] = [
+        0x3c208c16d87cfd47,
+        0x97816a916871ca8d,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ];
+}
+
+monty_field!(Bn254Base, 4, generator: 3, alpha: 5);
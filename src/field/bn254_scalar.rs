@@ -0,0 +1,20 @@
+use crate::{monty_field, MontyRepr};
+
+/// An element of the BN254 group's scalar field, which is also the base field of Grumpkin.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Bn254Scalar {
+    /// Montgomery representation, encoded with little-endian u64 limbs.
+    pub limbs: [u64; 4],
+}
+
+impl MontyRepr<4> for Bn254Scalar {
+    /// The order of the field: 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001
+    const ORDER: [u64; 4] = [
+        0x43e1f593f0000001,
+        0x2833e84879b97091,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ];
+}
+
+monty_field!(Bn254Scalar, 4, generator: 5, alpha: 5);
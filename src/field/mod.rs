@@ -1,18 +1,26 @@
 pub use bls12_377_base::*;
 pub use bls12_377_scalar::*;
+pub use bn254_base::*;
+pub use bn254_scalar::*;
 pub use field::*;
 pub use tweedledee_base::*;
 pub use tweedledum_base::*;
 pub use monty::*;
 pub use pallas_base::*;
+pub use secp256k1_base::*;
+pub use secp256k1_scalar::*;
 pub use vesta_base::*;
 
 mod bls12_377_base;
 mod bls12_377_scalar;
+mod bn254_base;
+mod bn254_scalar;
 #[allow(clippy::module_inception)]
 mod field;
 mod tweedledee_base;
 mod tweedledum_base;
 mod monty;
 mod pallas_base;
+mod secp256k1_base;
+mod secp256k1_scalar;
 mod vesta_base;
@@ -1,7 +1,6 @@
 use std::cmp::Ordering::Less;
-use unroll::unroll_for_loops;
 
-use crate::{add_no_overflow, sub, cmp, mul2, nonzero_multiplicative_inverse};
+use crate::{add_no_overflow, cmp, mul2, nonzero_multiplicative_inverse, one_array, overflowing_add, sub, wrapping_sub};
 
 // TODO: These low-level functions should be collected together in their own
 // module.
@@ -17,35 +16,45 @@ fn mul_add(a: u64, b: u64, c: u64) -> (u64, u64) {
     ((t >> 64) as u64, t as u64)
 }
 
-
-pub trait MontyRepr {
+/// Montgomery arithmetic modulo an odd `ORDER < 2^(64 N)`, on little-endian `u64` limbs.
+///
+/// Only `ORDER` needs to be given; the other constants are derived from it at compile time.
+pub trait MontyRepr<const N: usize> {
     /// The order of the field
-    const ORDER: [u64; 4];
-    /// Twice the order of the field
-    const ORDER_X2: [u64; 4];
-    /// R in the context of the Montgomery reduction, i.e. 2^256 % |F|.
-    const R: [u64; 4];
-    /// R^2 in the context of the Montgomery reduction, i.e. 2^(256*2) % |F|.
-    const R2: [u64; 4];
-    /// R^3 in the context of the Montgomery reduction, i.e. 2^(256*3) % |F|.
-    const R3: [u64; 4];
+    const ORDER: [u64; N];
+    /// R in the context of the Montgomery reduction, i.e. 2^(64*N) % |F|.
+    const R: [u64; N] = monty_r(Self::ORDER);
+    /// R^2 in the context of the Montgomery reduction, i.e. 2^(64*N*2) % |F|.
+    const R2: [u64; N] = mul_mod(Self::R, Self::R, Self::ORDER);
+    /// R^3 in the context of the Montgomery reduction, i.e. 2^(64*N*3) % |F|.
+    const R3: [u64; N] = mul_mod(Self::R2, Self::R, Self::ORDER);
     /// In the context of Montgomery multiplication, µ = -|F|^-1 mod 2^64.
-    const MU: u64;
+    const MU: u64 = monty_mu(Self::ORDER[0]);
 
-    const ZERO: [u64; 4] = [0u64; 4];
-    const ONE: [u64; 4] = Self::R;
+    /// Whether the most significant bit of `ORDER` is clear, so that the sum of two reduced
+    /// elements fits in `N` limbs.
+    const HAS_SPARE_BIT: bool = Self::ORDER[N - 1] >> 63 == 0;
 
-    fn monty_add(lhs: [u64; 4], rhs: [u64; 4]) -> [u64; 4] {
-        // First we do a widening addition, then we reduce if necessary.
-        let sum = add_no_overflow(lhs, rhs);
-        if cmp(sum, Self::ORDER) == Less {
-            sum
+    fn monty_add(lhs: [u64; N], rhs: [u64; N]) -> [u64; N] {
+        if Self::HAS_SPARE_BIT {
+            // First we do a widening addition, then we reduce if necessary.
+            let sum = add_no_overflow(lhs, rhs);
+            if cmp(sum, Self::ORDER) == Less {
+                sum
+            } else {
+                sub(sum, Self::ORDER)
+            }
         } else {
-            sub(sum, Self::ORDER)
+            let (sum, overflow) = overflowing_add(lhs, rhs);
+            if overflow || cmp(sum, Self::ORDER) != Less {
+                wrapping_sub(sum, Self::ORDER)
+            } else {
+                sum
+            }
         }
     }
 
-    fn monty_sub(lhs: [u64; 4], rhs: [u64; 4]) -> [u64; 4] {
+    fn monty_sub(lhs: [u64; N], rhs: [u64; N]) -> [u64; N] {
         if cmp(lhs, rhs) == Less {
             // Underflow occurs, so we compute the difference as `self + (-rhs)`.
             add_no_overflow(lhs, Self::monty_neg(rhs))
@@ -55,102 +64,102 @@ pub trait MontyRepr {
         }
     }
 
-    fn monty_neg(limbs: [u64; 4]) -> [u64; 4] {
-        if limbs == Self::ZERO {
-            Self::ZERO
+    fn monty_neg(limbs: [u64; N]) -> [u64; N] {
+        if limbs == [0; N] {
+            limbs
         } else {
             sub(Self::ORDER, limbs)
         }
     }
 
-    #[unroll_for_loops]
-    fn monty_multiply(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
-        // Interleaved Montgomery multiplication, as described in Algorithm 2 of
-        // https://eprint.iacr.org/2017/1057.pdf
+    fn monty_multiply(a: [u64; N], b: [u64; N]) -> [u64; N] {
+        // Coarsely integrated operand scanning (CIOS), as described in "Analyzing and Comparing
+        // Montgomery Multiplication Algorithms" by Koç et al. The accumulator is `c`, plus a top
+        // word `c_hi` which is at most 1; this makes the algorithm correct for any odd order
+        // below 2^(64 N), including those without a spare bit.
+        let mut c = [0u64; N];
+        let mut c_hi = 0u64;
 
-        // Note that in the loop below, to avoid explicitly shifting c, we will treat i as the least
-        // significant digit and wrap around.
-        let mut c = [0u64; 5];
-
-        for i in 0..4 {
+        for i in 0..N {
             // Add a[i] b to c.
             let mut carry = 0;
-            for j in 0..4 {
-                let result = c[(i + j) % 5] as u128 + a[i] as u128 * b[j] as u128 + carry as u128;
-                c[(i + j) % 5] = result as u64;
-                carry = (result >> 64) as u64;
+            for j in 0..N {
+                let (hi, lo) = mul_add_cy_in(a[i], b[j], c[j], carry);
+                c[j] = lo;
+                carry = hi;
             }
-            c[(i + 4) % 5] += carry;
-
-            // q = u c mod r = u c[0] mod r.
-            let q = Self::MU.wrapping_mul(c[i]);
+            let (c_top, overflow_1) = c_hi.overflowing_add(carry);
 
-            // C += N q
-            carry = 0;
-            for j in 0..4 {
-                let result =
-                    c[(i + j) % 5] as u128 + q as u128 * Self::ORDER[j] as u128 + carry as u128;
-                c[(i + j) % 5] = result as u64;
-                carry = (result >> 64) as u64;
+            // Add q N to c, where q = µ c[0] mod 2^64, so that c becomes divisible by 2^64, then
+            // shift c down by one limb.
+            let q = Self::MU.wrapping_mul(c[0]);
+            let (mut carry, lo) = mul_add(q, Self::ORDER[0], c[0]);
+            debug_assert_eq!(lo, 0);
+            for j in 1..N {
+                let (hi, lo) = mul_add_cy_in(q, Self::ORDER[j], c[j], carry);
+                c[j - 1] = lo;
+                carry = hi;
             }
-            c[(i + 4) % 5] += carry;
-
-            debug_assert_eq!(c[i], 0);
+            let (c_top, overflow_2) = c_top.overflowing_add(carry);
+            c[N - 1] = c_top;
+            c_hi = overflow_1 as u64 + overflow_2 as u64;
         }
 
-        let mut result = [c[4], c[0], c[1], c[2]];
         // Final conditional subtraction.
-        if cmp(result, Self::ORDER) != Less {
-            result = sub(result, Self::ORDER);
+        if c_hi != 0 || cmp(c, Self::ORDER) != Less {
+            c = wrapping_sub(c, Self::ORDER);
         }
-        result
+        c
     }
 
-    #[unroll_for_loops]
-    fn monty_square(a: [u64; 4]) -> [u64; 4] {
-        let mut c = [0u64; 4];
+    fn monty_square(a: [u64; N]) -> [u64; N] {
+        // The dedicated squaring below relies on the top two bits of the order being clear.
+        if Self::ORDER[N - 1] >> 62 != 0 {
+            return Self::monty_multiply(a, a);
+        }
+
+        let mut c = [0u64; N];
         let mut hi = 0u64;
 
-        for i in 0 .. 4 {
+        for i in 0..N {
             // u holds the off-diagonal part of the square calculation. Only the
             // first N - i elements are used.
-            let mut u = [0u64; 4];
+            let mut u = [0u64; N];
             let mut hi_in = 0u64;
-            for j in i+1 .. 4 {
+            for j in i + 1..N {
                 let (hi_out, lo) = mul_add(a[j], a[i], hi_in);
-                u[j - (i+1)] = lo;
+                u[j - (i + 1)] = lo;
                 hi_in = hi_out;
             }
-            u[4 - (i+1)] = hi_in;
+            u[N - (i + 1)] = hi_in;
             u = mul2(u);
             let (mut hi_in, lo) = mul_add(a[i], a[i], c[i]);
             c[i] = lo;
-            for j in i+1 .. 4 {
+            for j in i + 1..N {
                 // c[j] = c[j] + u[j] + hi_in
                 let (t, cy1) = c[j].overflowing_add(hi_in);
-                let (t, cy2) = u[j - (i+1)].overflowing_add(t);
+                let (t, cy2) = u[j - (i + 1)].overflowing_add(t);
                 c[j] = t;
                 hi_in = (cy1 as u64) + (cy2 as u64);
             }
 
             let (t, cy1) = hi.overflowing_add(hi_in);
-            let (t, cy2) = u[4 - (i+1)].overflowing_add(t);
+            let (t, cy2) = u[N - (i + 1)].overflowing_add(t);
             hi = t;
-            debug_assert_eq!(cy1 | cy2, false);
+            debug_assert!(!(cy1 | cy2));
 
             let m = c[0].wrapping_mul(Self::MU);
             let (mut hi_in, lo) = mul_add(Self::ORDER[0], m, c[0]);
             debug_assert_eq!(lo, 0u64);
-            for j in 1 .. 4 {
+            for j in 1..N {
                 let (hi_out, lo) = mul_add_cy_in(Self::ORDER[j], m, c[j], hi_in);
                 c[j - 1] = lo;
                 hi_in = hi_out;
             }
             let (t, cy) = hi.overflowing_add(hi_in);
-            c[4 - 1] = t;
+            c[N - 1] = t;
             hi = cy as u64;
         }
-        // NB: This is only true for the Tweedle* curves.
         debug_assert_eq!(hi, 0u64);
         // Final conditional subtraction.
         if cmp(c, Self::ORDER) != Less {
@@ -159,20 +168,404 @@ pub trait MontyRepr {
         c
     }
 
-    fn monty_inverse(limbs: [u64; 4]) -> [u64; 4] {
+    fn monty_inverse(limbs: [u64; N]) -> [u64; N] {
+        if !Self::HAS_SPARE_BIT {
+            // The binary inversion algorithm needs a spare bit, so we fall back to computing
+            // x^(|F| - 2), which stays in Montgomery form throughout.
+            let mut two = [0; N];
+            two[0] = 2;
+            let exponent = wrapping_sub(Self::ORDER, two);
+            let mut result = Self::R;
+            for i in (0..64 * N).rev() {
+                result = Self::monty_square(result);
+                if (exponent[i / 64] >> (i % 64)) & 1 != 0 {
+                    result = Self::monty_multiply(result, limbs);
+                }
+            }
+            return result;
+        }
+
         // Let x R = self. We compute M((x R)^-1, R^3) = x^-1 R^-1 R^3 R^-1 =
         // x^-1 R.
         let self_r_inv = nonzero_multiplicative_inverse(limbs, Self::ORDER);
         Self::monty_multiply(self_r_inv, Self::R3)
     }
 
-    fn from_monty(c: [u64; 4]) -> [u64; 4] {
+    fn from_monty(c: [u64; N]) -> [u64; N] {
         // We compute M(c, R^2) = c * R^2 * R^-1 = c * R.
         Self::monty_multiply(c, Self::R2)
     }
 
-    fn to_monty(limbs: [u64; 4]) -> [u64; 4] {
+    fn to_monty(limbs: [u64; N]) -> [u64; N] {
         // Let x * R = limbs. We compute M(x * R, 1) = x * R * R^-1 = x.
-        Self::monty_multiply(limbs, [1, 0, 0, 0])
+        Self::monty_multiply(limbs, one_array![u64; N])
+    }
+}
+
+// The functions below are used to derive constants at compile time, so they're written with
+// `while` loops and are not optimized for speed.
+
+const fn geq<const N: usize>(a: [u64; N], b: [u64; N]) -> bool {
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+/// Computes `a + b mod m`, assuming `a, b < m`.
+const fn add_mod<const N: usize>(a: [u64; N], b: [u64; N], m: [u64; N]) -> [u64; N] {
+    let (sum, overflow) = overflowing_add(a, b);
+    if overflow || geq(sum, m) {
+        wrapping_sub(sum, m)
+    } else {
+        sum
+    }
+}
+
+/// Computes `a b mod m`, assuming `a < m`.
+pub(crate) const fn mul_mod<const N: usize>(a: [u64; N], b: [u64; N], m: [u64; N]) -> [u64; N] {
+    let mut result = [0; N];
+    let mut i = 64 * N;
+    while i > 0 {
+        i -= 1;
+        result = add_mod(result, result, m);
+        if (b[i / 64] >> (i % 64)) & 1 != 0 {
+            result = add_mod(result, a, m);
+        }
+    }
+    result
+}
+
+/// Computes `2^(64 N) mod m`.
+pub(crate) const fn monty_r<const N: usize>(m: [u64; N]) -> [u64; N] {
+    let mut result = [0; N];
+    result[0] = 1;
+    let mut i = 0;
+    while i < 64 * N {
+        result = add_mod(result, result, m);
+        i += 1;
+    }
+    result
+}
+
+/// Computes `-m^-1 mod 2^64` using Newton's method, where each iteration doubles the number of
+/// correct bits.
+pub(crate) const fn monty_mu(m_0: u64) -> u64 {
+    let mut inv = 1u64;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(m_0.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
+
+/// The Montgomery form of a small integer `n < m`, i.e. `n R mod m`.
+pub(crate) const fn monty_from_u64<const N: usize>(n: u64, r: [u64; N], m: [u64; N]) -> [u64; N] {
+    let mut n_limbs = [0; N];
+    n_limbs[0] = n;
+    mul_mod(r, n_limbs, m)
+}
+
+/// The number of bits in `m`.
+pub(crate) const fn num_bits<const N: usize>(m: [u64; N]) -> usize {
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        if m[i] != 0 {
+            return 64 * i + 64 - m[i].leading_zeros() as usize;
+        }
     }
+    0
+}
+
+/// The largest `s` such that `2^s` divides `m - 1`, for odd `m`.
+pub(crate) const fn two_adicity<const N: usize>(m: [u64; N]) -> usize {
+    let mut m_minus_one = m;
+    m_minus_one[0] -= 1;
+    let mut i = 0;
+    while m_minus_one[i] == 0 {
+        i += 1;
+    }
+    64 * i + m_minus_one[i].trailing_zeros() as usize
+}
+
+/// The Montgomery form of `(m - 1) / 2^two_adicity(m)`.
+pub(crate) const fn monty_t<const N: usize>(r: [u64; N], m: [u64; N]) -> [u64; N] {
+    let s = two_adicity(m);
+    let mut m_minus_one = m;
+    m_minus_one[0] -= 1;
+    let mut t = [0; N];
+    let mut i = 0;
+    while i < N {
+        let src = i + s / 64;
+        if src < N {
+            t[i] = m_minus_one[src] >> (s % 64);
+            if s % 64 != 0 && src + 1 < N {
+                t[i] |= m_minus_one[src + 1] << (64 - s % 64);
+            }
+        }
+        i += 1;
+    }
+    mul_mod(r, t, m)
+}
+
+/// Implements `Field` for a type with a `limbs: [u64; N]` field and a `MontyRepr<N>` impl, along
+/// with arithmetic operators, ordering, formatting and serde. Also generates the standard field
+/// test suite. `generator` is a generator of the multiplicative group, and `alpha` the smallest
+/// `a` such that `x^a` is a permutation.
+#[macro_export]
+macro_rules! monty_field {
+    ($field:ident, $n:literal, generator: $generator:expr, alpha: $alpha:expr) => {
+        impl $field {
+            pub fn from_canonical(c: [u64; $n]) -> Self {
+                Self { limbs: <Self as $crate::MontyRepr<$n>>::from_monty(c) }
+            }
+
+            pub fn to_canonical(&self) -> [u64; $n] {
+                <Self as $crate::MontyRepr<$n>>::to_monty(self.limbs)
+            }
+
+            const fn from_small(n: u64) -> Self {
+                Self {
+                    limbs: $crate::monty_from_u64(
+                        n,
+                        <Self as $crate::MontyRepr<$n>>::R,
+                        <Self as $crate::MontyRepr<$n>>::ORDER,
+                    ),
+                }
+            }
+        }
+
+        impl std::ops::Add<$field> for $field {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { limbs: <Self as $crate::MontyRepr<$n>>::monty_add(self.limbs, rhs.limbs) }
+            }
+        }
+
+        impl std::ops::Sub<$field> for $field {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { limbs: <Self as $crate::MontyRepr<$n>>::monty_sub(self.limbs, rhs.limbs) }
+            }
+        }
+
+        impl std::ops::Mul<$field> for $field {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                Self {
+                    limbs: <Self as $crate::MontyRepr<$n>>::monty_multiply(self.limbs, rhs.limbs),
+                }
+            }
+        }
+
+        impl std::ops::Div<$field> for $field {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self {
+                self * $crate::Field::multiplicative_inverse(&rhs).expect("No inverse")
+            }
+        }
+
+        impl std::ops::Neg for $field {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { limbs: <Self as $crate::MontyRepr<$n>>::monty_neg(self.limbs) }
+            }
+        }
+
+        impl $crate::Field for $field {
+            const BITS: usize = $crate::num_bits(<Self as $crate::MontyRepr<$n>>::ORDER);
+            const BYTES: usize = Self::BITS.div_ceil(8);
+
+            const ZERO: Self = Self { limbs: [0; $n] };
+            const ONE: Self = Self { limbs: <Self as $crate::MontyRepr<$n>>::R };
+            const TWO: Self = Self::from_small(2);
+            const THREE: Self = Self::from_small(3);
+            const FOUR: Self = Self::from_small(4);
+            const FIVE: Self = Self::from_small(5);
+            const NEG_ONE: Self = Self {
+                limbs: $crate::wrapping_sub(
+                    <Self as $crate::MontyRepr<$n>>::ORDER,
+                    <Self as $crate::MontyRepr<$n>>::R,
+                ),
+            };
+
+            const MULTIPLICATIVE_SUBGROUP_GENERATOR: Self = Self::from_small($generator);
+
+            const ALPHA: Self = Self::from_small($alpha);
+
+            const TWO_ADICITY: usize = $crate::two_adicity(<Self as $crate::MontyRepr<$n>>::ORDER);
+
+            const T: Self = Self {
+                limbs: $crate::monty_t(
+                    <Self as $crate::MontyRepr<$n>>::R,
+                    <Self as $crate::MontyRepr<$n>>::ORDER,
+                ),
+            };
+
+            fn to_canonical_u64_vec(&self) -> Vec<u64> {
+                self.to_canonical().to_vec()
+            }
+
+            fn from_canonical_u64_vec(v: Vec<u64>) -> Self {
+                Self::from_canonical(std::convert::TryInto::try_into(&v[..]).unwrap())
+            }
+
+            fn from_canonical_u64(n: u64) -> Self {
+                let mut limbs = [0; $n];
+                limbs[0] = n;
+                Self::from_canonical(limbs)
+            }
+
+            fn is_valid_canonical_u64(v: &[u64]) -> bool {
+                v.len() == $n
+                    && $crate::cmp(
+                        std::convert::TryInto::try_into(v).unwrap(),
+                        <Self as $crate::MontyRepr<$n>>::ORDER,
+                    ) == std::cmp::Ordering::Less
+            }
+
+            fn multiplicative_inverse_assuming_nonzero(&self) -> Self {
+                Self { limbs: <Self as $crate::MontyRepr<$n>>::monty_inverse(self.limbs) }
+            }
+
+            fn rand() -> Self {
+                Self { limbs: $crate::rand_range(<Self as $crate::MontyRepr<$n>>::ORDER) }
+            }
+
+            fn rand_from_rng<R: rand::Rng>(rng: &mut R) -> Self {
+                Self {
+                    limbs: $crate::rand_range_from_rng(<Self as $crate::MontyRepr<$n>>::ORDER, rng),
+                }
+            }
+
+            #[inline(always)]
+            fn square(&self) -> Self {
+                Self { limbs: <Self as $crate::MontyRepr<$n>>::monty_square(self.limbs) }
+            }
+        }
+
+        impl Ord for $field {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                $crate::Field::cmp_helper(self, other)
+            }
+        }
+
+        impl PartialOrd for $field {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl std::fmt::Display for $field {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", $crate::field_to_biguint(*self))
+            }
+        }
+
+        impl std::fmt::Debug for $field {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{} {}", stringify!($field), $crate::field_to_biguint(*self))
+            }
+        }
+
+        $crate::impl_serde_field!($field);
+
+        #[cfg(test)]
+        mod monty_field_tests {
+            $crate::test_monty_field!($crate::$field, $n);
+        }
+    };
+}
+
+/// The standard test suite for fields implemented with `monty_field!`.
+#[macro_export]
+macro_rules! test_monty_field {
+    ($field:ty, $n:literal) => {
+        use num::BigUint;
+
+        use $crate::conversions::u64_slice_to_biguint;
+        use $crate::{Field, MontyRepr};
+
+        fn order() -> BigUint {
+            u64_slice_to_biguint(&<$field as MontyRepr<$n>>::ORDER)
+        }
+
+        #[test]
+        fn montgomery_constants() {
+            let order = order();
+            let r = (BigUint::from(1u32) << (64 * $n)) % &order;
+            assert_eq!(u64_slice_to_biguint(&<$field as MontyRepr<$n>>::R), r);
+            assert_eq!(u64_slice_to_biguint(&<$field as MontyRepr<$n>>::R2), &r * &r % &order);
+            assert_eq!(
+                u64_slice_to_biguint(&<$field as MontyRepr<$n>>::R3),
+                &r * &r * &r % &order
+            );
+            let mu = <$field as MontyRepr<$n>>::MU;
+            assert_eq!(mu.wrapping_mul(<$field as MontyRepr<$n>>::ORDER[0]), u64::MAX);
+        }
+
+        #[test]
+        fn field_constants() {
+            let order = order();
+            assert_eq!(<$field>::BITS as u64, order.bits());
+            assert_eq!(<$field>::TWO, <$field>::ONE + <$field>::ONE);
+            assert_eq!(<$field>::THREE, <$field>::TWO + <$field>::ONE);
+            assert_eq!(<$field>::FOUR, <$field>::THREE + <$field>::ONE);
+            assert_eq!(<$field>::FIVE, <$field>::FOUR + <$field>::ONE);
+            assert_eq!(<$field>::NEG_ONE + <$field>::ONE, <$field>::ZERO);
+            assert_eq!(
+                <$field>::T * <$field>::TWO.exp_usize(<$field>::TWO_ADICITY),
+                <$field>::NEG_ONE
+            );
+            assert!(<$field>::T.to_canonical_bool_vec()[0], "T should be odd");
+        }
+
+        #[test]
+        fn to_and_from_canonical() {
+            let order = order();
+            for _ in 0..10 {
+                let x = <$field>::rand();
+                let x_canonical = x.to_canonical();
+                assert_eq!(<$field>::from_canonical(x_canonical), x);
+                assert_eq!(
+                    u64_slice_to_biguint(&x.limbs),
+                    u64_slice_to_biguint(&x_canonical) * u64_slice_to_biguint(&<$field as MontyRepr<$n>>::R) % &order
+                );
+            }
+        }
+
+        #[test]
+        fn primitive_root_order() {
+            for n_power in 0..10.min(<$field>::TWO_ADICITY + 1) {
+                let root = <$field>::primitive_root_of_unity(n_power);
+                let order = <$field>::generator_order(root);
+                assert_eq!(order, 1 << n_power, "2^{}'th primitive root", n_power);
+            }
+        }
+
+        #[test]
+        fn valid_canonical_vec() {
+            let small = <$field>::ONE.to_canonical_u64_vec();
+            assert!(<$field>::is_valid_canonical_u64(&small));
+
+            let order = <$field as MontyRepr<$n>>::ORDER.to_vec();
+            assert!(!<$field>::is_valid_canonical_u64(&order));
+
+            let limbs = vec![1; $n + 1];
+            assert!(!<$field>::is_valid_canonical_u64(&limbs));
+        }
+
+        $crate::test_arithmetic!($field);
+    };
 }
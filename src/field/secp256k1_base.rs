@@ -0,0 +1,20 @@
+use crate::{monty_field, MontyRepr};
+
+/// An element of the secp256k1 base field.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Secp256k1Base {
+    /// Montgomery representation, encoded with little-endian u64 limbs.
+    pub limbs: [u64; 4],
+}
+
+impl MontyRepr<4> for Secp256k1Base {
+    /// The order of the field: 2^256 - 2^32 - 977
+    const ORDER: [u64; 4] = [
+        0xfffffffefffffc2f,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+    ];
+}
+
+monty_field!(Secp256k1Base, 4, generator: 3, alpha: 5);
@@ -0,0 +1,20 @@
+use crate::{monty_field, MontyRepr};
+
+/// An element of the secp256k1 scalar field.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Secp256k1Scalar {
+    /// Montgomery representation, encoded with little-endian u64 limbs.
+    pub limbs: [u64; 4],
+}
+
+impl MontyRepr<4> for Secp256k1Scalar {
+    /// The order of the field: 0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141
+    const ORDER: [u64; 4] = [
+        0xbfd25e8cd0364141,
+        0xbaaedce6af48a03b,
+        0xfffffffffffffffe,
+        0xffffffffffffffff,
+    ];
+}
+
+monty_field!(Secp256k1Scalar, 4, generator: 7, alpha: 5);
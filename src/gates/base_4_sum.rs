@@ -1,7 +1,7 @@
 use std::marker::PhantomData;
 
 use crate::gates::Gate;
-use crate::{CircuitBuilder, Curve, Field, HaloCurve, PartialWitness, Target, WitnessGenerator, NUM_ROUTED_WIRES, NUM_WIRES};
+use crate::{CircuitBuilder, Curve, Field, HaloCurve, PartialWitness, Target, Wire, WitnessGenerator, NUM_ROUTED_WIRES, NUM_WIRES};
 
 /// A gate for accumulating base-4 limbs.
 pub struct Base4SumGate<C: Curve> {
@@ -98,27 +98,141 @@ impl<C: HaloCurve> Gate<C> for Base4SumGate<C> {
 
 impl<C: HaloCurve> WitnessGenerator<C::ScalarField> for Base4SumGate<C> {
     fn dependencies(&self) -> Vec<Target<C::ScalarField>> {
-        Vec::new()
+        // Limb values are generated elsewhere, typically by a single generator for an entire
+        // decomposition. Once they and the old accumulator are known, we fill in the new
+        // accumulator.
+        let mut deps = vec![Target::Wire(Wire {
+            gate: self.index,
+            input: Self::WIRE_ACC_OLD,
+        })];
+        deps.extend((0..Self::NUM_LIMBS).map(|i| {
+            Target::Wire(Wire {
+                gate: self.index,
+                input: Self::wire_limb(i),
+            })
+        }));
+        deps
     }
 
     fn generate(
         &self,
         _constants: &[Vec<C::ScalarField>],
-        _witness: &PartialWitness<C::ScalarField>,
+        witness: &PartialWitness<C::ScalarField>,
     ) -> PartialWitness<C::ScalarField> {
-        // For base 4 decompositions, we don't do any witness generation on a per-gate level.
-        // Instead, we have a single generator which generates values for an entire decomposition.
-        PartialWitness::new()
+        let acc_old = witness.get_wire(Wire {
+            gate: self.index,
+            input: Self::WIRE_ACC_OLD,
+        });
+        let mut acc_new = acc_old;
+        for i in 0..Self::NUM_LIMBS {
+            let limb = witness.get_wire(Wire {
+                gate: self.index,
+                input: Self::wire_limb(i),
+            });
+            acc_new = acc_new.quadruple() + limb;
+        }
+
+        let mut result = PartialWitness::new();
+        result.set_wire(
+            Wire {
+                gate: self.index,
+                input: Self::WIRE_ACC_NEW,
+            },
+            acc_new,
+        );
+        result
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{test_gate_low_degree, Base4SumGate, Tweedledum};
+    use crate::{test_gate_low_degree, verify_proof, Base4SumGate, Circuit, CircuitBuilder, Curve, Field, PartialWitness, Target, Tweedledee, Tweedledum, Wire};
+
+    type C = Tweedledum;
+    type F = <C as Curve>::ScalarField;
+    type B4 = Base4SumGate<C>;
 
     test_gate_low_degree!(
         low_degree_Base4SumGate,
         Tweedledum,
         Base4SumGate<Tweedledum>
     );
+
+    fn prove_and_verify(circuit: &Circuit<C>, partial_witness: PartialWitness<F>) {
+        let witness = circuit.generate_witness(partial_witness);
+        let proof = circuit
+            .generate_proof::<Tweedledee>(&witness, &[], true)
+            .unwrap();
+        let vk = circuit.to_vk();
+        verify_proof::<C, Tweedledee>(&[], &proof, &[], &vk, true).unwrap();
+    }
+
+    #[test]
+    fn test_generates_acc_new() {
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let index = builder.num_gates();
+        builder.add_gate_no_constants(B4::new(index));
+        let circuit = builder.build();
+
+        let acc_old = F::rand();
+        let limbs: Vec<F> = (0..B4::NUM_LIMBS)
+            .map(|i| F::from_canonical_usize(i % 4))
+            .collect();
+        let limb_targets: Vec<Target<F>> = (0..B4::NUM_LIMBS)
+            .map(|i| {
+                Target::Wire(Wire {
+                    gate: index,
+                    input: B4::wire_limb(i),
+                })
+            })
+            .collect();
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_wire(
+            Wire {
+                gate: index,
+                input: B4::WIRE_ACC_OLD,
+            },
+            acc_old,
+        );
+        partial_witness.set_targets(&limb_targets, &limbs);
+        let witness = circuit.generate_partial_witness(partial_witness);
+
+        let expected = limbs.iter().fold(acc_old, |acc, &l| acc.quadruple() + l);
+        assert_eq!(
+            witness.get_wire(Wire {
+                gate: index,
+                input: B4::WIRE_ACC_NEW,
+            }),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_assert_all_base_4_partial_chunk() {
+        // Fewer limbs than a gate holds, so the unused limb wires must be filled in.
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let limbs = builder.add_virtual_targets(3);
+        builder.assert_all_base_4(&limbs);
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_targets(&limbs, &[F::ZERO, F::TWO, F::from_canonical_usize(3)]);
+        prove_and_verify(&circuit, partial_witness);
+    }
+
+    #[test]
+    fn test_assert_dibit_length() {
+        // One full Base4SumGate chunk plus a partial one, so that both paths must agree on the
+        // order of the dibits.
+        let num_dibits = B4::NUM_LIMBS + 3;
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let x = builder.add_virtual_target();
+        builder.assert_dibit_length(x, num_dibits);
+        let circuit = builder.build();
+
+        let x_value = F::from_canonical_u64(0x1234_5678_9abc_def0 >> (64 - 2 * num_dibits));
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_target(x, x_value);
+        prove_and_verify(&circuit, partial_witness);
+    }
 }
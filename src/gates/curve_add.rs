@@ -6,6 +6,10 @@ use crate::{CircuitBuilder, Curve, Field, HaloCurve, PartialWitness, Target, Wir
 /// A gate which performs incomplete point addition, conditioned on an input bit. In order to
 /// facilitate MSMs which use this gate, it also adds the bit to an accumulator.
 ///
+/// The gate's two constants may hold a fixed addend. A nonzero constant forces the corresponding
+/// addend coordinate to equal it, which saves routing the addend from constant wires, while a zero
+/// constant leaves the coordinate unconstrained. Thus a fixed addend can't have a zero coordinate.
+///
 /// `C` is the curve whose points are being added.
 pub struct CurveAddGate<C: HaloCurve, InnerC: Curve<BaseField = C::ScalarField>> {
     pub index: usize,
@@ -39,7 +43,7 @@ impl<C: HaloCurve, InnerC: Curve<BaseField = C::ScalarField>> Gate<C> for CurveA
     const PREFIX: &'static [bool] = &[true, false, true, false, true];
 
     fn evaluate_unfiltered(
-        _local_constant_values: &[InnerC::BaseField],
+        local_constant_values: &[InnerC::BaseField],
         local_wire_values: &[InnerC::BaseField],
         right_wire_values: &[InnerC::BaseField],
         _below_wire_values: &[InnerC::BaseField],
@@ -62,6 +66,8 @@ impl<C: HaloCurve, InnerC: Curve<BaseField = C::ScalarField>> Gate<C> for CurveA
         let scalar_bit = local_wire_values[Self::WIRE_SCALAR_BIT];
         let inverse = local_wire_values[Self::WIRE_INVERSE];
         let lambda = local_wire_values[Self::WIRE_LAMBDA];
+        let fixed_x2 = local_constant_values[Self::PREFIX.len()];
+        let fixed_y2 = local_constant_values[Self::PREFIX.len() + 1];
 
         let computed_lambda = (y1 - y2) * inverse;
         let x3 = lambda.square() - x1 - x2;
@@ -81,12 +87,14 @@ impl<C: HaloCurve, InnerC: Curve<BaseField = C::ScalarField>> Gate<C> for CurveA
             scalar_acc_new - (scalar_acc_old.double() + scalar_bit),
             scalar_bit * not_scalar_bit,
             inverse * (x1 - x2) - InnerC::BaseField::ONE,
+            fixed_x2 * (x2 - fixed_x2),
+            fixed_y2 * (y2 - fixed_y2),
         ]
     }
 
     fn evaluate_unfiltered_recursively(
         builder: &mut CircuitBuilder<C>,
-        _local_constant_values: &[Target<C::ScalarField>],
+        local_constant_values: &[Target<C::ScalarField>],
         local_wire_values: &[Target<C::ScalarField>],
         right_wire_values: &[Target<C::ScalarField>],
         _below_wire_values: &[Target<C::ScalarField>],
@@ -109,6 +117,8 @@ impl<C: HaloCurve, InnerC: Curve<BaseField = C::ScalarField>> Gate<C> for CurveA
         let scalar_bit = local_wire_values[Self::WIRE_SCALAR_BIT];
         let inverse = local_wire_values[Self::WIRE_INVERSE];
         let lambda = local_wire_values[Self::WIRE_LAMBDA];
+        let fixed_x2 = local_constant_values[Self::PREFIX.len()];
+        let fixed_y2 = local_constant_values[Self::PREFIX.len() + 1];
 
         let x1_minus_x2 = builder.sub(x1, x2);
         let x1_plus_x2 = builder.add(x1, x2);
@@ -131,6 +141,9 @@ impl<C: HaloCurve, InnerC: Curve<BaseField = C::ScalarField>> Gate<C> for CurveA
         let double_scalar_acc_old = builder.double(scalar_acc_old);
         let computed_scalar_acc_new = builder.add(double_scalar_acc_old, scalar_bit);
 
+        let x2_minus_fixed_x2 = builder.sub(x2, fixed_x2);
+        let y2_minus_fixed_y2 = builder.sub(y2, fixed_y2);
+
         vec![
             builder.sub(computed_lambda, lambda),
             builder.sub(computed_x4, x4),
@@ -138,6 +151,8 @@ impl<C: HaloCurve, InnerC: Curve<BaseField = C::ScalarField>> Gate<C> for CurveA
             builder.sub(computed_scalar_acc_new, scalar_acc_new),
             assert_binary_recursively(builder, scalar_bit),
             assert_inverses_recursively(builder, inverse, x1_minus_x2),
+            builder.mul(fixed_x2, x2_minus_fixed_x2),
+            builder.mul(fixed_y2, y2_minus_fixed_y2),
         ]
     }
 }
@@ -167,6 +182,10 @@ impl<C: HaloCurve, InnerC: Curve<BaseField = C::ScalarField>> WitnessGenerator<C
                 gate: self.index,
                 input: Self::WIRE_ADDEND_Y,
             }),
+            Target::Wire(Wire {
+                gate: self.index,
+                input: Self::WIRE_SCALAR_BIT,
+            }),
         ]
     }
 
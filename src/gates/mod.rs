@@ -1,20 +1,26 @@
 //! For reference, here is our gate prefix tree:
 //!
 //! ```text
-//! 101001 PublicInputGate
-//! 101000 CurveAddGate
-//! 10111* CurveDblGate
-//! 11**** CurveEndoGate
-//! 1000** Base4SumGate
-//! 101010 BufferGate
-//! 10110* ConstantGate
-//! 1001** ArithmeticGate
-//! 00**** RescueStepAGate
-//! 01**** RescueStepBGate
+//! 101001* PublicInputGate
+//! 101000* BufferGate
+//! 10101** CurveAddGate
+//! 10111** CurveDblGate
+//! 11***** CurveEndoGate
+//! 1000*** Base4SumGate
+//! 10110** ConstantGate
+//! 1001*** ArithmeticGate
+//! 000**** RescueStepAGate
+//! 001**** PoseidonGate
+//! 01***** RescueStepBGate
 //! ```
 //!
 //! The `*`s above represent constants which are not used in the gate prefix, and are thus available
 //! for gate configuration.
+//!
+//! Prefixes are stored in each gate's constants, so any change to this tree, or to the number of
+//! constants, changes every circuit's constant polynomials and invalidates all existing
+//! `VerificationKey`s. Adding `PoseidonGate` did so by lengthening `RescueStepAGate`'s prefix and
+//! raising `NUM_CONSTANTS` from 6 to 7.
 
 pub use arithmetic::*;
 pub use base_4_sum::*;
@@ -23,6 +29,7 @@ pub use constant::*;
 pub use curve_add::*;
 pub use curve_dbl::*;
 pub use curve_endo::*;
+pub use poseidon::*;
 pub use public_input::*;
 pub use rescue_a::*;
 pub use rescue_b::*;
@@ -36,6 +43,7 @@ mod constant;
 mod curve_add;
 mod curve_dbl;
 mod curve_endo;
+mod poseidon;
 mod public_input;
 mod rescue_a;
 mod rescue_b;
@@ -43,6 +51,10 @@ mod rescue_b;
 pub const RESCUE_SPONGE_WIDTH: usize = 4;
 pub const RESCUE_SPONGE_RATE: usize = RESCUE_SPONGE_WIDTH - 1;
 
+/// Poseidon's full rounds use `RescueStepBGate`, so its sponge must have the same width as Rescue's.
+pub const POSEIDON_SPONGE_WIDTH: usize = RESCUE_SPONGE_WIDTH;
+pub const POSEIDON_SPONGE_RATE: usize = POSEIDON_SPONGE_WIDTH - 1;
+
 pub fn evaluate_all_constraints<C: HaloCurve, InnerC: HaloCurve<BaseField = C::ScalarField>>(
     local_constant_values: &[C::ScalarField],
     local_wire_values: &[C::ScalarField],
@@ -104,6 +116,12 @@ pub fn evaluate_all_constraints<C: HaloCurve, InnerC: HaloCurve<BaseField = C::S
             right_wire_values,
             below_wire_values,
         ),
+        PoseidonGate::<C>::evaluate_filtered(
+            local_constant_values,
+            local_wire_values,
+            right_wire_values,
+            below_wire_values,
+        ),
         RescueStepBGate::<C>::evaluate_filtered(
             local_constant_values,
             local_wire_values,
@@ -198,6 +216,13 @@ pub fn evaluate_all_constraints_recursively<
             right_wire_values,
             below_wire_values,
         ),
+        PoseidonGate::<C>::evaluate_filtered_recursively(
+            builder,
+            local_constant_values,
+            local_wire_values,
+            right_wire_values,
+            below_wire_values,
+        ),
         RescueStepBGate::<C>::evaluate_filtered_recursively(
             builder,
             local_constant_values,
@@ -0,0 +1,150 @@
+use std::marker::PhantomData;
+
+use crate::gates::Gate;
+use crate::{mds_matrix, CircuitBuilder, Field, HaloCurve, PartialWitness, Target, Wire, WitnessGenerator, POSEIDON_SPONGE_WIDTH};
+
+/// A partial round of Poseidon, i.e. one where only the first element goes through the `x^5`
+/// layer. The round constants of the following round are added at the end, so that each round
+/// needs exactly one gate.
+///
+/// Full rounds have the same shape as Rescue's second step, so they use `RescueStepBGate`.
+pub struct PoseidonGate<C: HaloCurve> {
+    pub index: usize,
+    _phantom: PhantomData<C>,
+}
+
+impl<C: HaloCurve> PoseidonGate<C> {
+    pub fn new(index: usize) -> Self {
+        PoseidonGate {
+            index,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the index of the `i`th accumulator wire.
+    pub fn wire_acc(i: usize) -> usize {
+        i
+    }
+}
+
+impl<C: HaloCurve> Gate<C> for PoseidonGate<C> {
+    const NAME: &'static str = "PoseidonGate";
+
+    const PREFIX: &'static [bool] = &[false, false, true];
+
+    fn evaluate_unfiltered(
+        local_constant_values: &[C::ScalarField],
+        local_wire_values: &[C::ScalarField],
+        right_wire_values: &[C::ScalarField],
+        _below_wire_values: &[C::ScalarField],
+    ) -> Vec<C::ScalarField> {
+        let mut sbox_outs: Vec<C::ScalarField> = (0..POSEIDON_SPONGE_WIDTH)
+            .map(|i| local_wire_values[Self::wire_acc(i)])
+            .collect();
+        sbox_outs[0] = sbox_outs[0].exp_usize(5);
+
+        let outs: Vec<C::ScalarField> = (0..POSEIDON_SPONGE_WIDTH)
+            .map(|i| right_wire_values[Self::wire_acc(i)])
+            .collect();
+
+        let mds = mds_matrix::<C::ScalarField>(POSEIDON_SPONGE_WIDTH);
+
+        let mut constraints = Vec::new();
+        for i in 0..POSEIDON_SPONGE_WIDTH {
+            let mut computed_out_i = local_constant_values[Self::PREFIX.len() + i];
+            for j in 0..POSEIDON_SPONGE_WIDTH {
+                computed_out_i = computed_out_i + mds.get(i, j) * sbox_outs[j];
+            }
+            constraints.push(computed_out_i - outs[i]);
+        }
+        constraints
+    }
+
+    fn evaluate_unfiltered_recursively(
+        builder: &mut CircuitBuilder<C>,
+        local_constant_values: &[Target<C::ScalarField>],
+        local_wire_values: &[Target<C::ScalarField>],
+        right_wire_values: &[Target<C::ScalarField>],
+        _below_wire_values: &[Target<C::ScalarField>],
+    ) -> Vec<Target<C::ScalarField>> {
+        let mut sbox_outs: Vec<Target<C::ScalarField>> = (0..POSEIDON_SPONGE_WIDTH)
+            .map(|i| local_wire_values[Self::wire_acc(i)])
+            .collect();
+        sbox_outs[0] = builder.exp_constant_usize(sbox_outs[0], 5);
+
+        let outs: Vec<Target<C::ScalarField>> = (0..POSEIDON_SPONGE_WIDTH)
+            .map(|i| right_wire_values[Self::wire_acc(i)])
+            .collect();
+
+        let mds = mds_matrix::<C::ScalarField>(POSEIDON_SPONGE_WIDTH);
+
+        let mut constraints = Vec::new();
+        for i in 0..POSEIDON_SPONGE_WIDTH {
+            let mut computed_out_i = local_constant_values[Self::PREFIX.len() + i];
+            for j in 0..POSEIDON_SPONGE_WIDTH {
+                let mds_entry = builder.constant_wire(mds.get(i, j));
+                computed_out_i = builder.mul_add(mds_entry, sbox_outs[j], computed_out_i);
+            }
+            constraints.push(builder.sub(computed_out_i, outs[i]));
+        }
+        constraints
+    }
+}
+
+impl<C: HaloCurve> WitnessGenerator<C::ScalarField> for PoseidonGate<C> {
+    fn dependencies(&self) -> Vec<Target<C::ScalarField>> {
+        (0..POSEIDON_SPONGE_WIDTH)
+            .map(|i| {
+                Target::Wire(Wire {
+                    gate: self.index,
+                    input: Self::wire_acc(i),
+                })
+            })
+            .collect()
+    }
+
+    fn generate(
+        &self,
+        constants: &[Vec<C::ScalarField>],
+        witness: &PartialWitness<C::ScalarField>,
+    ) -> PartialWitness<C::ScalarField> {
+        let constants = &constants[self.index];
+
+        let mut sbox_outs: Vec<C::ScalarField> = (0..POSEIDON_SPONGE_WIDTH)
+            .map(|i| {
+                witness.get_wire(Wire {
+                    gate: self.index,
+                    input: Self::wire_acc(i),
+                })
+            })
+            .collect();
+        sbox_outs[0] = sbox_outs[0].exp_usize(5);
+
+        let mds = mds_matrix::<C::ScalarField>(POSEIDON_SPONGE_WIDTH);
+
+        let mut result = PartialWitness::new();
+        for i in 0..POSEIDON_SPONGE_WIDTH {
+            let mut out_i = constants[Self::PREFIX.len() + i];
+            for j in 0..POSEIDON_SPONGE_WIDTH {
+                out_i = out_i + mds.get(i, j) * sbox_outs[j];
+            }
+            let wire_out_i = Wire {
+                gate: self.index + 1,
+                input: Self::wire_acc(i),
+            };
+            result.set_wire(wire_out_i, out_i);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_gate_low_degree, PoseidonGate, Tweedledum};
+
+    test_gate_low_degree!(
+        low_degree_PoseidonGate,
+        Tweedledum,
+        PoseidonGate<Tweedledum>
+    );
+}
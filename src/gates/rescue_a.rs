@@ -31,7 +31,7 @@ impl<C: HaloCurve> RescueStepAGate<C> {
 impl<C: HaloCurve> Gate<C> for RescueStepAGate<C> {
     const NAME: &'static str = "RescueStepAGate";
 
-    const PREFIX: &'static [bool] = &[false, false];
+    const PREFIX: &'static [bool] = &[false, false, false];
 
     fn evaluate_unfiltered(
         local_constant_values: &[C::ScalarField],
@@ -1,7 +1,8 @@
 use crate::plonk_challenger::Challenger;
-use crate::plonk_util::{halo_n, halo_n_mul, powers, reduce_with_powers};
+use crate::plonk_util::{halo_g, halo_n, halo_n_mul, halo_s, pedersen_hash, powers, reduce_with_powers};
+use crate::poly_commit::PolynomialCommitmentScheme;
 use crate::util::log2_strict;
-use crate::{msm_execute_parallel, msm_parallel, msm_precompute, AffinePoint, Curve, Field, HaloCurve, PolynomialCommitment, ProjectivePoint, SchnorrProof};
+use crate::{blake_hash_usize_to_curve, msm_execute_parallel, msm_parallel, msm_precompute, AffinePoint, Curve, Field, HaloCurve, MsmPrecomputation, PolynomialCommitment, ProjectivePoint, SchnorrProof, TranscriptHash};
 use anyhow::Result;
 use rayon::prelude::*;
 
@@ -13,7 +14,7 @@ pub struct OpeningProof<C: HaloCurve> {
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn batch_opening_proof<C: HaloCurve>(
+pub fn batch_opening_proof<C: HaloCurve, H: TranscriptHash<C::BaseField>>(
     polynomials_coeffs: &[&[C::ScalarField]],
     commitments: &[PolynomialCommitment<C>],
     opening_points: &[C::ScalarField],
@@ -25,7 +26,7 @@ pub fn batch_opening_proof<C: HaloCurve>(
     u_scaling: C::ScalarField,
     degree: usize,
     security_bits: usize,
-    challenger: &mut Challenger<C::BaseField>,
+    challenger: &mut Challenger<C::BaseField, H>,
 ) -> Result<OpeningProof<C>> {
     // Normally we would reduce these lists using powers of u, but for the sake of efficiency
     // (particularly in the recursive verifier) we instead use n(u^i) for each u^i, where n is
@@ -93,8 +94,8 @@ pub fn batch_opening_proof<C: HaloCurve>(
                 + C::convert(C::ScalarField::inner_product(a_hi, b_lo)) * u_prime;
 
             let mut challenger_fork = challenger.clone();
-            challenger_fork.observe_proj_points(&[halo_l_j, halo_r_j]);
-            let r_bf = challenger_fork.get_challenge();
+            challenger_fork.append_proj_points("halo_lr", &[halo_l_j, halo_r_j]);
+            let r_bf = challenger_fork.challenge("halo_challenge");
             let r_sf = r_bf.try_convert::<C::ScalarField>()?;
             let r_bits = &r_sf.to_canonical_bool_vec()[..security_bits];
             let u_j_squared = halo_n::<C>(r_bits);
@@ -154,21 +155,21 @@ fn build_halo_b<C: Curve>(
         .collect()
 }
 
-fn schnorr_protocol<C: HaloCurve>(
+fn schnorr_protocol<C: HaloCurve, H: TranscriptHash<C::BaseField>>(
     halo_a: C::ScalarField,
     halo_b: C::ScalarField,
     halo_g: AffinePoint<C>,
     randomness: C::ScalarField,
     u_curve: ProjectivePoint<C>,
     pedersen_h: ProjectivePoint<C>,
-    challenger: &mut Challenger<C::BaseField>,
+    challenger: &mut Challenger<C::BaseField, H>,
 ) -> SchnorrProof<C> {
     let (d, s) = (C::ScalarField::rand(), C::ScalarField::rand());
     let r_curve = C::convert(d) * (halo_g.to_projective() + C::convert(halo_b) * u_curve)
         + C::convert(s) * pedersen_h;
 
-    challenger.observe_proj_point(r_curve);
-    let chall_bf = challenger.get_challenge();
+    challenger.append_proj_point("schnorr_r", r_curve);
+    let chall_bf = challenger.challenge("schnorr");
     let chall = chall_bf
         .try_convert::<C::ScalarField>()
         .expect("Improbable");
@@ -221,3 +222,255 @@ pub fn verify_ipa<C: HaloCurve>(
         == C::convert(schnorr_proof.z1) * (halo_g.to_projective() + C::convert(halo_b) * u_prime)
             + C::convert(schnorr_proof.z2) * pedersen_h.to_projective()
 }
+
+/// Parameters of the Halo inner product argument.
+#[derive(Debug, Clone)]
+pub struct IpaParams<C: HaloCurve> {
+    pub pedersen_g: Vec<AffinePoint<C>>,
+    pub pedersen_g_msm_precomputation: MsmPrecomputation<C>,
+    pub pedersen_h: AffinePoint<C>,
+    pub u_curve: AffinePoint<C>,
+    pub security_bits: usize,
+}
+
+impl<C: HaloCurve> IpaParams<C> {
+    /// Generates parameters for polynomials with up to `degree` coefficients. The generators are
+    /// derived the same way as in `CircuitBuilder::build`.
+    pub fn new(degree: usize, security_bits: usize) -> Self {
+        let pedersen_g: Vec<_> = (0..degree).map(blake_hash_usize_to_curve::<C>).collect();
+        let pedersen_g_msm_precomputation =
+            msm_precompute(&AffinePoint::batch_to_projective(&pedersen_g), 8);
+        Self {
+            pedersen_g,
+            pedersen_g_msm_precomputation,
+            pedersen_h: blake_hash_usize_to_curve::<C>(degree),
+            u_curve: blake_hash_usize_to_curve::<C>(degree + 1),
+            security_bits,
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.pedersen_g.len()
+    }
+
+    fn padded(&self, coeffs: &[C::ScalarField]) -> Vec<C::ScalarField> {
+        assert!(coeffs.len() <= self.degree(), "Polynomial is too large for these parameters");
+        let mut padded = coeffs.to_vec();
+        padded.resize(self.degree(), C::ScalarField::ZERO);
+        padded
+    }
+}
+
+/// The Halo inner product argument, with linear-time verification of the final `G` point.
+pub struct Ipa;
+
+impl Ipa {
+    /// Draws the challenges `(v, u, u_scaling)` used to batch openings, in the same order as the
+    /// Plonk prover.
+    fn get_batching_challenges<C: HaloCurve, H: TranscriptHash<C::BaseField>>(
+        challenger: &mut Challenger<C::BaseField, H>,
+    ) -> Result<(C::ScalarField, C::ScalarField, C::ScalarField)> {
+        let v_bf = challenger.challenge("v");
+        let u_bf = challenger.challenge("u");
+        let u_scaling_bf = challenger.challenge("u_scaling");
+        Ok((
+            C::try_convert_b2s(v_bf)?,
+            C::try_convert_b2s(u_bf)?,
+            C::try_convert_b2s(u_scaling_bf)?,
+        ))
+    }
+}
+
+impl<C: HaloCurve> PolynomialCommitmentScheme<C> for Ipa {
+    type ProverParams = IpaParams<C>;
+    type VerifierParams = IpaParams<C>;
+    type OpeningProof = OpeningProof<C>;
+
+    fn commit(
+        params: &IpaParams<C>,
+        coeffs: &[C::ScalarField],
+        blinding: bool,
+    ) -> PolynomialCommitment<C> {
+        PolynomialCommitment::coeffs_to_commitment(
+            &params.padded(coeffs),
+            &params.pedersen_g_msm_precomputation,
+            params.pedersen_h,
+            blinding,
+        )
+    }
+
+    fn open<H: TranscriptHash<C::BaseField>>(
+        params: &IpaParams<C>,
+        polynomials_coeffs: &[&[C::ScalarField]],
+        commitments: &[PolynomialCommitment<C>],
+        opening_points: &[C::ScalarField],
+        challenger: &mut Challenger<C::BaseField, H>,
+    ) -> Result<OpeningProof<C>> {
+        let (v, u, u_scaling) = Self::get_batching_challenges::<C, H>(challenger)?;
+        let padded_coeffs = polynomials_coeffs
+            .iter()
+            .map(|coeffs| params.padded(coeffs))
+            .collect::<Vec<_>>();
+        batch_opening_proof(
+            &padded_coeffs.iter().map(|c| c.as_slice()).collect::<Vec<_>>(),
+            commitments,
+            opening_points,
+            &params.pedersen_g,
+            params.pedersen_h.to_projective(),
+            params.u_curve,
+            u,
+            v,
+            u_scaling,
+            params.degree(),
+            params.security_bits,
+            challenger,
+        )
+    }
+
+    fn verify<H: TranscriptHash<C::BaseField>>(
+        params: &IpaParams<C>,
+        commitments: &[AffinePoint<C>],
+        opening_points: &[C::ScalarField],
+        openings: &[Vec<C::ScalarField>],
+        proof: &OpeningProof<C>,
+        challenger: &mut Challenger<C::BaseField, H>,
+    ) -> Result<bool> {
+        let security_bits = params.security_bits;
+        if openings.len() != opening_points.len()
+            || openings.iter().any(|o| o.len() != commitments.len())
+            || proof.halo_l.len() != log2_strict(params.degree())
+            || proof.halo_r.len() != proof.halo_l.len()
+        {
+            return Ok(false);
+        }
+
+        let (v, u, u_scaling) = Self::get_batching_challenges::<C, H>(challenger)?;
+
+        // Reduce the commitments and the openings using n(u^i), as in `batch_opening_proof`.
+        let actual_scalars = powers(u, commitments.len())
+            .iter()
+            .map(|u_pow| halo_n::<C>(&u_pow.to_canonical_bool_vec()[..security_bits]))
+            .collect::<Vec<_>>();
+        let c_reduction = msm_parallel(
+            &actual_scalars,
+            &AffinePoint::batch_to_projective(commitments),
+            8,
+        );
+        let opening_reductions = openings
+            .iter()
+            .map(|opening| C::ScalarField::inner_product(opening, &actual_scalars))
+            .collect::<Vec<_>>();
+        let reduced_opening = reduce_with_powers(&opening_reductions, v);
+
+        let u_prime = halo_n_mul(&u_scaling.to_canonical_bool_vec()[..security_bits], params.u_curve)
+            .to_projective();
+
+        let mut halo_us = Vec::new();
+        for (&l, &r) in proof.halo_l.iter().zip(proof.halo_r.iter()) {
+            challenger.append_affine_points("halo_lr", &[l, r]);
+            let r_sf = challenger
+                .challenge("halo_challenge")
+                .try_convert::<C::ScalarField>()?;
+            let u_j_squared = halo_n::<C>(&r_sf.to_canonical_bool_vec()[..security_bits]);
+            match u_j_squared.square_root() {
+                Some(u_j) => halo_us.push(u_j),
+                None => return Ok(false),
+            }
+        }
+
+        challenger.append_affine_point("schnorr_r", proof.schnorr_proof.r);
+        let schnorr_challenge = C::try_convert_b2s(challenger.challenge("schnorr"))?;
+
+        let halo_bs = opening_points
+            .iter()
+            .map(|&p| halo_g(p, &halo_us))
+            .collect::<Vec<_>>();
+        let halo_b = reduce_with_powers(&halo_bs, v);
+
+        if !verify_ipa::<C>(
+            &proof.halo_l,
+            &proof.halo_r,
+            proof.halo_g,
+            c_reduction,
+            reduced_opening,
+            halo_b,
+            &halo_us,
+            u_prime,
+            params.pedersen_h,
+            schnorr_challenge,
+            proof.schnorr_proof,
+        ) {
+            return Ok(false);
+        }
+
+        // Finally, perform the linear-time check that `halo_g = <s, G>`.
+        Ok(proof.halo_g
+            == pedersen_hash(&halo_s(&halo_us), &params.pedersen_g_msm_precomputation).to_affine())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::halo::{Ipa, IpaParams};
+    use crate::plonk_challenger::Challenger;
+    use crate::plonk_util::eval_poly;
+    use crate::poly_commit::PolynomialCommitmentScheme;
+    use crate::{Curve, Field, PolynomialCommitment, Tweedledee};
+
+    type C = Tweedledee;
+    type SF = <C as Curve>::ScalarField;
+
+    #[test]
+    fn test_ipa_batch_opening() -> Result<()> {
+        let degree = 16;
+        let params = IpaParams::<C>::new(degree, 128);
+        let polys: Vec<Vec<SF>> = (0..3)
+            .map(|i| (0..degree - i).map(|_| SF::rand()).collect())
+            .collect();
+        let mut commitments = polys
+            .iter()
+            .map(|p| Ipa::commit(&params, p, true))
+            .collect::<Vec<_>>();
+        PolynomialCommitment::batch_to_affine(&mut commitments);
+        let commitments_affine = PolynomialCommitment::commitments_to_affine_vec(&commitments);
+        let points = [SF::rand(), SF::rand()];
+        let mut openings = points
+            .iter()
+            .map(|&z| polys.iter().map(|p| eval_poly(p, z)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut prover_challenger: Challenger<_> = Challenger::new("test_ipa", 128);
+        prover_challenger.append_affine_points("commitments", &commitments_affine);
+        let proof = Ipa::open(
+            &params,
+            &polys.iter().map(|p| p.as_slice()).collect::<Vec<_>>(),
+            &commitments,
+            &points,
+            &mut prover_challenger,
+        )?;
+
+        let mut verifier_challenger: Challenger<_> = Challenger::new("test_ipa", 128);
+        verifier_challenger.append_affine_points("commitments", &commitments_affine);
+        assert!(Ipa::verify(
+            &params,
+            &commitments_affine,
+            &points,
+            &openings,
+            &proof,
+            &mut verifier_challenger.clone(),
+        )?);
+
+        openings[1][2] = openings[1][2] + SF::ONE;
+        assert!(!Ipa::verify(
+            &params,
+            &commitments_affine,
+            &points,
+            &openings,
+            &proof,
+            &mut verifier_challenger,
+        )?);
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{rescue_sponge, AffinePoint, Curve, Field};
+use crate::{AffinePoint, Curve, Field, RescueParams, RESCUE_SPONGE_WIDTH};
     
 pub fn hash_u32_to_curve<C: Curve>(seed: u32, security_bits: usize) -> AffinePoint<C> {
     let seed_f = C::BaseField::from_canonical_u32(seed);
@@ -81,11 +81,12 @@ pub fn hash_base_field_to_curve<C: Curve>(
     security_bits: usize,
 ) -> AffinePoint<C> {
     // Based on the MapToGroup method of BLS.
+    let rescue = RescueParams::new(RESCUE_SPONGE_WIDTH, security_bits);
     let mut i = 0;
     loop {
         // Let (x, y_neg) = H(seed, i).
-        let inputs = vec![seed, C::BaseField::from_canonical_u32(i)];
-        let outputs = rescue_sponge(inputs, 2, security_bits);
+        let inputs = [seed, C::BaseField::from_canonical_u32(i)];
+        let outputs = rescue.sponge(&inputs, 2);
         let x = outputs[0];
         let y_neg = outputs[1].to_canonical_bool_vec()[0];
 
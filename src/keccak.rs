@@ -0,0 +1,215 @@
+/// The Keccak-f[1600] round constants, applied to lane `(0, 0)` in the iota step.
+pub(crate) const KECCAK_ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets used in the rho step, indexed by `x + 5 y`.
+pub(crate) const KECCAK_ROTATIONS: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// The number of bytes absorbed per permutation by Keccak-256, i.e. `(1600 - 2 * 256) / 8`.
+pub const KECCAK256_RATE: usize = 136;
+
+/// Applies a single round of Keccak-f[1600] to a state of 25 lanes, indexed by `x + 5 y`.
+pub(crate) fn keccak_f1600_round(state: &mut [u64; 25], round_constant: u64) {
+    // Theta
+    let mut c = [0u64; 5];
+    for x in 0..5 {
+        c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+    }
+    for x in 0..5 {
+        let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        for y in 0..5 {
+            state[x + 5 * y] ^= d;
+        }
+    }
+
+    // Rho and pi
+    let mut b = [0u64; 25];
+    for x in 0..5 {
+        for y in 0..5 {
+            b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                state[x + 5 * y].rotate_left(KECCAK_ROTATIONS[x + 5 * y]);
+        }
+    }
+
+    // Chi
+    for x in 0..5 {
+        for y in 0..5 {
+            state[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+        }
+    }
+
+    // Iota
+    state[0] ^= round_constant;
+}
+
+/// Applies the Keccak-f[1600] permutation to a state of 25 lanes, indexed by `x + 5 y`.
+pub fn keccak_f1600(state: &mut [u64; 25]) {
+    for &round_constant in KECCAK_ROUND_CONSTANTS.iter() {
+        keccak_f1600_round(state, round_constant);
+    }
+}
+
+/// Pads a message with Keccak's original `pad10*1` rule, as used by Ethereum. Note that this
+/// differs from SHA3-256, which uses a different domain separation byte.
+pub fn keccak256_pad(message: &[u8]) -> Vec<u8> {
+    let mut padded = message.to_vec();
+    padded.push(0x01);
+    while !padded.len().is_multiple_of(KECCAK256_RATE) {
+        padded.push(0);
+    }
+    *padded.last_mut().unwrap() |= 0x80;
+    padded
+}
+
+/// Computes the Keccak-256 digest of a message.
+pub fn keccak256(message: &[u8]) -> [u8; 32] {
+    let state = absorb_padded(&keccak256_pad(message), KECCAK256_RATE);
+
+    let mut digest = [0u8; 32];
+    for (digest_bytes, lane) in digest.chunks_mut(8).zip(state.iter()) {
+        digest_bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    digest
+}
+
+/// Absorbs an already padded message into a zero state, one `rate`-byte block at a time.
+fn absorb_padded(padded: &[u8], rate: usize) -> [u64; 25] {
+    let mut state = [0u64; 25];
+    for block in padded.chunks(rate) {
+        for (lane, lane_bytes) in state.iter_mut().zip(block.chunks(8)) {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(lane_bytes);
+            *lane ^= u64::from_le_bytes(bytes);
+        }
+        keccak_f1600(&mut state);
+    }
+    state
+}
+
+/// The number of bytes absorbed or squeezed per SHAKE256 permutation, i.e. `(1600 - 2 * 256) / 8`.
+pub const SHAKE256_RATE: usize = 136;
+
+/// The SHAKE256 extendable-output function from FIPS 202.
+pub struct Shake256 {
+    state: [u64; 25],
+    /// The number of bytes of the current block which have already been squeezed.
+    offset: usize,
+}
+
+impl Shake256 {
+    /// Absorbs `message`, after which any amount of output can be squeezed.
+    pub fn new(message: &[u8]) -> Self {
+        // SHAKE uses the domain separation bits `1111`, followed by `pad10*1`.
+        let mut padded = message.to_vec();
+        padded.push(0x1f);
+        while !padded.len().is_multiple_of(SHAKE256_RATE) {
+            padded.push(0);
+        }
+        *padded.last_mut().unwrap() |= 0x80;
+
+        Shake256 {
+            state: absorb_padded(&padded, SHAKE256_RATE),
+            offset: 0,
+        }
+    }
+
+    /// Fills `output` with the next bytes of output.
+    pub fn squeeze(&mut self, output: &mut [u8]) {
+        for byte in output.iter_mut() {
+            if self.offset == SHAKE256_RATE {
+                keccak_f1600(&mut self.state);
+                self.offset = 0;
+            }
+            *byte = (self.state[self.offset / 8] >> (8 * (self.offset % 8))) as u8;
+            self.offset += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{keccak256, keccak_f1600, Shake256};
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_keccak_f1600() {
+        // The first state from the Keccak team's KeccakF-1600-IntermediateValues.txt.
+        let mut state = [0u64; 25];
+        keccak_f1600(&mut state);
+        assert_eq!(state[0], 0xf1258f7940e1dde7);
+        assert_eq!(state[1], 0x84d5ccf933c0478a);
+        assert_eq!(state[24], 0xeaf1ff7b5ceca249);
+    }
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            to_hex(&keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            to_hex(&keccak256(b"abc")),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+        // 136 bytes fill a whole block, so the padding needs a second one.
+        assert_eq!(
+            to_hex(&keccak256(&[0u8; 136])),
+            "3a5912a7c5faa06ee4fe906253e339467a9ce87d533c65be3c15cb231cdb25f9"
+        );
+    }
+
+    #[test]
+    fn test_shake256() {
+        let squeeze = |message: &[u8], len: usize| {
+            let mut output = vec![0; len];
+            Shake256::new(message).squeeze(&mut output);
+            output
+        };
+        assert_eq!(
+            to_hex(&squeeze(b"", 32)),
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f"
+        );
+        assert_eq!(
+            to_hex(&squeeze(b"abc", 32)),
+            "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739"
+        );
+        assert_eq!(
+            to_hex(&squeeze(&[0u8; 136], 32)),
+            "ea947b835fec1f9b0a7eabba901deb7881fd9999a1cbd5ccbb5a9afab7f6fe70"
+        );
+        // Squeezing more than one block needs further permutations.
+        assert_eq!(
+            to_hex(&squeeze(b"abc", 300)[268..]),
+            "2ddf384af3334560ea1d363966caa7d8ddcbec7da52b42215c11d5f8ee57f341"
+        );
+    }
+}
@@ -0,0 +1,357 @@
+//! KZG polynomial commitments, an alternative to the Halo IPA for deployments which don't need
+//! recursion. Opening proofs contain one group element per opening point, and verification takes
+//! two pairings regardless of the polynomials' degree.
+//!
+//! `Kzg` is only available through `PolynomialCommitmentScheme` for now. `Circuit::generate_proof`
+//! and `verify_proof` still use the IPA, as our `Proof` format is built around its opening proof.
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use anyhow::Result;
+use rand::Rng;
+
+use crate::plonk_challenger::Challenger;
+use crate::plonk_util::{eval_poly, powers, reduce_with_powers};
+use crate::poly_commit::PolynomialCommitmentScheme;
+use crate::util::ceil_div_usize;
+use crate::{msm_execute_parallel, msm_parallel, msm_precompute, AffinePoint, Curve, Field, MsmPrecomputation, PolynomialCommitment, ProjectivePoint, TranscriptHash};
+
+/// A bilinear pairing `e: G1 x G2 -> GT`, where `G1` is the curve polynomials are committed over.
+pub trait PairingEngine {
+    type G1: Curve;
+    /// An element of `G2`, in whatever representation the engine uses.
+    type G2Affine: Copy + Debug;
+
+    fn g2_generator() -> Self::G2Affine;
+
+    fn g2_mul(point: Self::G2Affine, scalar: <Self::G1 as Curve>::ScalarField) -> Self::G2Affine;
+
+    /// Returns whether `prod_i e(a_i, b_i)` is the identity of `GT`.
+    fn pairing_product_is_one(pairs: &[(AffinePoint<Self::G1>, Self::G2Affine)]) -> bool;
+}
+
+/// A structured reference string, i.e. `[tau^i] G` for `i < degree` along with `H` and `[tau] H`.
+pub struct KzgSrs<E: PairingEngine> {
+    pub powers_of_tau_g: Vec<AffinePoint<E::G1>>,
+    pub powers_of_tau_g_msm_precomputation: MsmPrecomputation<E::G1>,
+    pub h: E::G2Affine,
+    pub tau_h: E::G2Affine,
+    pub security_bits: usize,
+}
+
+impl<E: PairingEngine> KzgSrs<E> {
+    /// Generates an SRS from a locally sampled `tau`. Whoever runs this learns `tau` and can forge
+    /// openings, so this is only suitable for tests.
+    pub fn generate_insecure<R: Rng>(degree: usize, security_bits: usize, rng: &mut R) -> Self {
+        let tau = <E::G1 as Curve>::ScalarField::rand_from_rng(rng);
+        let generator = E::G1::GENERATOR_PROJECTIVE;
+        let powers_of_tau_g_proj = powers(tau, degree)
+            .into_iter()
+            .map(|tau_pow| E::G1::convert(tau_pow) * generator)
+            .collect::<Vec<_>>();
+        let h = E::g2_generator();
+        Self {
+            powers_of_tau_g: ProjectivePoint::batch_to_affine(&powers_of_tau_g_proj),
+            powers_of_tau_g_msm_precomputation: msm_precompute(&powers_of_tau_g_proj, 8),
+            h,
+            tau_h: E::g2_mul(h, tau),
+            security_bits,
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.powers_of_tau_g.len()
+    }
+
+    pub fn verifier_key(&self) -> KzgVerifierKey<E> {
+        KzgVerifierKey {
+            g: self.powers_of_tau_g[0],
+            h: self.h,
+            tau_h: self.tau_h,
+            security_bits: self.security_bits,
+        }
+    }
+
+    fn commit_coeffs(&self, coeffs: &[<E::G1 as Curve>::ScalarField]) -> ProjectivePoint<E::G1> {
+        assert!(coeffs.len() <= self.degree(), "Polynomial is too large for this SRS");
+        let mut padded = coeffs.to_vec();
+        padded.resize(self.degree(), <E::G1 as Curve>::ScalarField::ZERO);
+        msm_execute_parallel(&self.powers_of_tau_g_msm_precomputation, &padded)
+    }
+}
+
+/// The part of a `KzgSrs` needed for verification.
+pub struct KzgVerifierKey<E: PairingEngine> {
+    pub g: AffinePoint<E::G1>,
+    pub h: E::G2Affine,
+    pub tau_h: E::G2Affine,
+    pub security_bits: usize,
+}
+
+/// A batched KZG opening proof, with one witness commitment per opening point.
+#[derive(Debug, Clone)]
+pub struct KzgOpeningProof<C: Curve> {
+    pub witnesses: Vec<AffinePoint<C>>,
+}
+
+/// The KZG scheme. Commitments are not hiding, so `commit` must be called with `blinding = false`.
+pub struct Kzg<E: PairingEngine>(PhantomData<E>);
+
+impl<E: PairingEngine> Kzg<E> {
+    /// Converts a challenge to a scalar by keeping its `security_bits` lowest bits. This is needed
+    /// since pairing-friendly curves usually have a base field much larger than their scalar field.
+    fn get_challenge<H: TranscriptHash<<E::G1 as Curve>::BaseField>>(
+        challenger: &mut Challenger<<E::G1 as Curve>::BaseField, H>,
+        label: &'static str,
+        security_bits: usize,
+    ) -> <E::G1 as Curve>::ScalarField {
+        type SF<E> = <<E as PairingEngine>::G1 as Curve>::ScalarField;
+        let limbs = challenger.challenge(label).to_canonical_u64_vec();
+        assert!(
+            security_bits <= 64 * limbs.len(),
+            "Challenges have fewer than {} bits",
+            security_bits
+        );
+
+        // Keep every limb below `security_bits`, masking the one which straddles it.
+        let masked_limbs = limbs
+            .iter()
+            .take(ceil_div_usize(security_bits, 64))
+            .enumerate()
+            .map(|(i, &limb)| match security_bits - 64 * i {
+                bits if bits >= 64 => limb,
+                bits => limb & ((1 << bits) - 1),
+            })
+            .collect::<Vec<_>>();
+        let two_to_64 = SF::<E>::from_canonical_u64(1 << 32).square();
+        masked_limbs.iter().rev().fold(SF::<E>::ZERO, |acc, &limb| {
+            acc * two_to_64 + SF::<E>::from_canonical_u64(limb)
+        })
+    }
+}
+
+impl<E: PairingEngine> PolynomialCommitmentScheme<E::G1> for Kzg<E> {
+    type ProverParams = KzgSrs<E>;
+    type VerifierParams = KzgVerifierKey<E>;
+    type OpeningProof = KzgOpeningProof<E::G1>;
+
+    fn commit(
+        params: &KzgSrs<E>,
+        coeffs: &[<E::G1 as Curve>::ScalarField],
+        blinding: bool,
+    ) -> PolynomialCommitment<E::G1> {
+        assert!(!blinding, "KZG commitments are not hiding");
+        params.commit_coeffs(coeffs).into()
+    }
+
+    fn open<H: TranscriptHash<<E::G1 as Curve>::BaseField>>(
+        params: &KzgSrs<E>,
+        polynomials_coeffs: &[&[<E::G1 as Curve>::ScalarField]],
+        _commitments: &[PolynomialCommitment<E::G1>],
+        opening_points: &[<E::G1 as Curve>::ScalarField],
+        challenger: &mut Challenger<<E::G1 as Curve>::BaseField, H>,
+    ) -> Result<KzgOpeningProof<E::G1>> {
+        let zero = <E::G1 as Curve>::ScalarField::ZERO;
+
+        // Reduce all polynomials to a single one using powers of `v`.
+        let v = Self::get_challenge(challenger, "kzg_v", params.security_bits);
+        let mut reduced_coeffs = vec![zero; params.degree()];
+        for (&v_pow, coeffs) in powers(v, polynomials_coeffs.len())
+            .iter()
+            .zip(polynomials_coeffs)
+        {
+            for (j, &c) in coeffs.iter().enumerate() {
+                reduced_coeffs[j] = reduced_coeffs[j] + v_pow * c;
+            }
+        }
+
+        // For each point `z`, commit to the quotient `(f(X) - f(z)) / (X - z)`, which we compute by
+        // synthetic division. The remainder is `f(z)`, so it can simply be dropped.
+        let witnesses = opening_points
+            .iter()
+            .map(|&z| {
+                let mut quotient = vec![zero; reduced_coeffs.len() - 1];
+                let mut acc = zero;
+                for i in (1..reduced_coeffs.len()).rev() {
+                    acc = acc * z + reduced_coeffs[i];
+                    quotient[i - 1] = acc;
+                }
+                params.commit_coeffs(&quotient)
+            })
+            .collect::<Vec<_>>();
+        let witnesses = ProjectivePoint::batch_to_affine(&witnesses);
+        challenger.append_affine_points("kzg_witnesses", &witnesses);
+
+        Ok(KzgOpeningProof { witnesses })
+    }
+
+    fn verify<H: TranscriptHash<<E::G1 as Curve>::BaseField>>(
+        params: &KzgVerifierKey<E>,
+        commitments: &[AffinePoint<E::G1>],
+        opening_points: &[<E::G1 as Curve>::ScalarField],
+        openings: &[Vec<<E::G1 as Curve>::ScalarField>],
+        proof: &KzgOpeningProof<E::G1>,
+        challenger: &mut Challenger<<E::G1 as Curve>::BaseField, H>,
+    ) -> Result<bool> {
+        if openings.len() != opening_points.len()
+            || proof.witnesses.len() != opening_points.len()
+            || openings.iter().any(|o| o.len() != commitments.len())
+        {
+            return Ok(false);
+        }
+
+        let v = Self::get_challenge(challenger, "kzg_v", params.security_bits);
+        challenger.append_affine_points("kzg_witnesses", &proof.witnesses);
+        let r = Self::get_challenge(challenger, "kzg_r", params.security_bits);
+
+        let reduced_commitment = msm_parallel(
+            &powers(v, commitments.len()),
+            &AffinePoint::batch_to_projective(commitments),
+            8,
+        );
+
+        // For each point `z_j` with reduced opening `y_j` and witness `W_j`, we have
+        // `C - [y_j] G + [z_j] W_j = [tau] W_j`. We check a random combination of these using
+        // powers of `r`, i.e.
+        //     e(sum r^j (C - [y_j] G + [z_j] W_j), H) = e(sum r^j W_j, [tau] H).
+        let r_powers = powers(r, opening_points.len());
+        let mut lhs_scalars = Vec::new();
+        let mut lhs_points = Vec::new();
+        let mut combined_y = <E::G1 as Curve>::ScalarField::ZERO;
+        for (j, &r_pow) in r_powers.iter().enumerate() {
+            combined_y = combined_y + r_pow * reduce_with_powers(&openings[j], v);
+            lhs_scalars.push(r_pow * opening_points[j]);
+            lhs_points.push(proof.witnesses[j].to_projective());
+        }
+        lhs_scalars.push(
+            r_powers
+                .iter()
+                .fold(<E::G1 as Curve>::ScalarField::ZERO, |acc, &r_pow| acc + r_pow),
+        );
+        lhs_points.push(reduced_commitment);
+        lhs_scalars.push(-combined_y);
+        lhs_points.push(params.g.to_projective());
+        let lhs = msm_parallel(&lhs_scalars, &lhs_points, 8);
+        let rhs = msm_parallel(
+            &r_powers,
+            &AffinePoint::batch_to_projective(&proof.witnesses),
+            8,
+        );
+
+        Ok(E::pairing_product_is_one(&[
+            (lhs.to_affine(), params.h),
+            ((-rhs).to_affine(), params.tau_h),
+        ]))
+    }
+}
+
+/// Evaluates each polynomial at each point, in the layout expected by `Kzg::verify`.
+pub fn evaluate_openings<F: Field>(polynomials_coeffs: &[&[F]], points: &[F]) -> Vec<Vec<F>> {
+    points
+        .iter()
+        .map(|&z| polynomials_coeffs.iter().map(|p| eval_poly(p, z)).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::thread_rng;
+
+    use crate::kzg::{evaluate_openings, Kzg, KzgSrs, PairingEngine};
+    use crate::plonk_challenger::Challenger;
+    use crate::poly_commit::PolynomialCommitmentScheme;
+    use crate::{field_to_biguint, AffinePoint, Bls12377, Bls12377Scalar, Curve, Field, PolynomialCommitment, ProjectivePoint};
+
+    /// A stand-in for a real pairing, which represents each `G2` element by its discrete log with
+    /// respect to the `G2` generator. Then `e(a, [b] H) = e(a, H)^b`, so a product of pairings is
+    /// trivial iff `sum [b_i] a_i = 0`. This faithfully models a pairing, but is obviously insecure.
+    struct DiscreteLogEngine;
+
+    impl PairingEngine for DiscreteLogEngine {
+        type G1 = Bls12377;
+        type G2Affine = Bls12377Scalar;
+
+        fn g2_generator() -> Bls12377Scalar {
+            Bls12377Scalar::ONE
+        }
+
+        fn g2_mul(point: Bls12377Scalar, scalar: Bls12377Scalar) -> Bls12377Scalar {
+            point * scalar
+        }
+
+        fn pairing_product_is_one(pairs: &[(AffinePoint<Bls12377>, Bls12377Scalar)]) -> bool {
+            let sum: ProjectivePoint<Bls12377> = pairs
+                .iter()
+                .map(|&(a, b)| Bls12377::convert(b) * a.to_projective())
+                .fold(ProjectivePoint::ZERO, |acc, p| acc + p);
+            sum.zero
+        }
+    }
+
+    #[test]
+    fn test_challenge_bits() {
+        for &security_bits in &[64, 100, 128, 250] {
+            let mut challenger: Challenger<_> = Challenger::new("test_kzg", 128);
+            let challenge =
+                Kzg::<DiscreteLogEngine>::get_challenge(&mut challenger, "kzg_v", security_bits);
+            assert!(field_to_biguint(challenge).bits() <= security_bits as u64);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Challenges have fewer than 512 bits")]
+    fn test_challenge_bits_too_large() {
+        let mut challenger: Challenger<_> = Challenger::new("test_kzg", 128);
+        Kzg::<DiscreteLogEngine>::get_challenge(&mut challenger, "kzg_v", 512);
+    }
+
+    #[test]
+    fn test_kzg_batch_opening() -> Result<()> {
+        type K = Kzg<DiscreteLogEngine>;
+        let degree = 16;
+        let srs = KzgSrs::<DiscreteLogEngine>::generate_insecure(degree, 128, &mut thread_rng());
+        let vk = srs.verifier_key();
+
+        let polys: Vec<Vec<Bls12377Scalar>> = (0..3)
+            .map(|i| (0..degree - i).map(|_| Bls12377Scalar::rand()).collect())
+            .collect();
+        let poly_refs = polys.iter().map(|p| p.as_slice()).collect::<Vec<_>>();
+        let mut commitments = poly_refs
+            .iter()
+            .map(|p| K::commit(&srs, p, false))
+            .collect::<Vec<_>>();
+        PolynomialCommitment::batch_to_affine(&mut commitments);
+        let commitments_affine = PolynomialCommitment::commitments_to_affine_vec(&commitments);
+        let points = [Bls12377Scalar::rand(), Bls12377Scalar::rand()];
+        let mut openings = evaluate_openings(&poly_refs, &points);
+
+        let mut prover_challenger: Challenger<_> = Challenger::new("test_kzg", 128);
+        prover_challenger.append_affine_points("commitments", &commitments_affine);
+        let proof = K::open(&srs, &poly_refs, &commitments, &points, &mut prover_challenger)?;
+        assert_eq!(proof.witnesses.len(), points.len());
+
+        let mut verifier_challenger: Challenger<_> = Challenger::new("test_kzg", 128);
+        verifier_challenger.append_affine_points("commitments", &commitments_affine);
+        assert!(K::verify(
+            &vk,
+            &commitments_affine,
+            &points,
+            &openings,
+            &proof,
+            &mut verifier_challenger.clone(),
+        )?);
+
+        openings[0][1] = openings[0][1] + Bls12377Scalar::ONE;
+        assert!(!K::verify(
+            &vk,
+            &commitments_affine,
+            &points,
+            &openings,
+            &proof,
+            &mut verifier_challenger,
+        )?);
+        Ok(())
+    }
+}
@@ -15,45 +15,83 @@
 
 pub use bigint::*;
 pub use circuit_bigint::*;
+pub use circuit_bool::*;
 pub use circuit_builder::*;
 pub use circuit_curve::*;
+pub use circuit_elgamal::*;
+pub use circuit_foreign_curve::*;
 pub use circuit_foreign_field::*;
+pub use circuit_memory::*;
 pub use circuit_ordering::*;
+pub use circuit_schnorr::*;
+pub use circuit_uint::*;
 pub use conversions::*;
 pub use curve::*;
+pub use ecdsa::*;
+pub use elgamal::*;
 pub use fft::*;
 pub use field::*;
 pub use gates::*;
 pub use hash_to_curve::*;
+pub use keccak::*;
 pub use mds::*;
+pub use merkle::*;
 pub use partition::*;
+pub use pedersen::*;
 pub use plonk::*;
 pub use plonk_proof::*;
 pub use plonk_recursion::*;
 pub use poly_commit::*;
 pub use polynomial::*;
+pub use poseidon::*;
 pub use pseudorandom::*;
 pub use rescue::*;
+pub use rsa::*;
+pub use schnorr::*;
 pub use serialization::*;
+pub use sha256::*;
 pub use target::*;
+pub use transcript_hash::*;
 pub use verifier::*;
 pub use witness::*;
 
 mod bigint;
 mod circuit_bigint;
+mod circuit_bits;
+mod circuit_bool;
 mod circuit_builder;
 mod circuit_curve;
+mod circuit_elgamal;
+mod circuit_foreign_curve;
 mod circuit_foreign_field;
+mod circuit_keccak;
+mod circuit_memory;
+mod circuit_merkle;
+mod circuit_mux;
 mod circuit_ordering;
+mod circuit_pedersen;
+mod circuit_permutation;
+mod circuit_poseidon;
+mod circuit_rescue;
+mod circuit_rsa;
+mod circuit_schnorr;
+mod circuit_sha256;
+mod circuit_uint;
 mod conversions;
 mod curve;
+mod ecdsa;
+mod elgamal;
 mod fft;
 mod field;
 mod gates;
 pub mod halo;
 mod hash_to_curve;
+mod keccak;
+pub mod kzg;
 mod mds;
+mod merkle;
 mod partition;
+mod pedersen;
 mod plonk;
 pub mod plonk_challenger;
 mod plonk_proof;
@@ -61,10 +99,15 @@ mod plonk_recursion;
 pub mod plonk_util;
 pub mod poly_commit;
 pub mod polynomial;
+mod poseidon;
 mod pseudorandom;
 mod rescue;
+mod rsa;
+mod schnorr;
 mod serialization;
+mod sha256;
 mod target;
+mod transcript_hash;
 pub mod util;
 mod verifier;
 mod witness;
@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use crate::{Field, RescueTranscriptHash, TranscriptHash};
+
+/// Hashes two sibling nodes into their parent with a single permutation, by absorbing them into an
+/// empty sponge and squeezing one element. With Rescue, this matches
+/// `rescue_hash_n_to_1(vec![left, right], security_bits)`.
+pub fn merkle_compress<F: Field, H: TranscriptHash<F>>(hash: &H, left: F, right: F) -> F {
+    assert!(H::RATE >= 2, "The hash must absorb two nodes at once");
+    let mut state = vec![F::ZERO; H::WIDTH];
+    state[0] = left;
+    state[1] = right;
+    hash.permute(&state)[0]
+}
+
+/// A proof that a leaf is at a certain index of a Merkle tree. It consists of the sibling of each
+/// node on the path from the leaf to the root, starting with the leaf's sibling.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerkleProof<F: Field> {
+    pub siblings: Vec<F>,
+}
+
+impl<F: Field> MerkleProof<F> {
+    /// Computes the root of a tree with `leaf` at `index`. Bit `i` of `index` says whether the node
+    /// at height `i` on the path is a right child.
+    pub fn compute_root<H: TranscriptHash<F>>(&self, hash: &H, leaf: F, index: usize) -> F {
+        let mut node = leaf;
+        for (i, &sibling) in self.siblings.iter().enumerate() {
+            node = if (index >> i) & 1 == 0 {
+                merkle_compress(hash, node, sibling)
+            } else {
+                merkle_compress(hash, sibling, node)
+            };
+        }
+        node
+    }
+
+    pub fn verify<H: TranscriptHash<F>>(&self, hash: &H, leaf: F, index: usize, root: F) -> bool {
+        self.compute_root(hash, leaf, index) == root
+    }
+
+    /// Checks that replacing `old_leaf` with `new_leaf` at `index` turns `old_root` into
+    /// `new_root`. The update doesn't change any siblings on the path, so one proof covers both
+    /// roots.
+    pub fn verify_update<H: TranscriptHash<F>>(
+        &self,
+        hash: &H,
+        index: usize,
+        old_leaf: F,
+        new_leaf: F,
+        old_root: F,
+        new_root: F,
+    ) -> bool {
+        self.verify(hash, old_leaf, index, old_root) && self.verify(hash, new_leaf, index, new_root)
+    }
+}
+
+/// A Merkle tree over a power-of-two number of leaves, with every node stored.
+#[derive(Clone, Debug)]
+pub struct MerkleTree<F: Field, H: TranscriptHash<F> = RescueTranscriptHash<F>> {
+    hash: H,
+    /// The nodes at each height, starting with the leaves and ending with the root.
+    layers: Vec<Vec<F>>,
+}
+
+impl<F: Field, H: TranscriptHash<F>> MerkleTree<F, H> {
+    pub fn new(leaves: Vec<F>, security_bits: usize) -> Self {
+        assert!(
+            leaves.len().is_power_of_two(),
+            "The number of leaves must be a power of two"
+        );
+
+        let hash = H::new(security_bits);
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let parents = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| merkle_compress(&hash, pair[0], pair[1]))
+                .collect();
+            layers.push(parents);
+        }
+        MerkleTree { hash, layers }
+    }
+
+    pub fn hash(&self) -> &H {
+        &self.hash
+    }
+
+    pub fn height(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    pub fn root(&self) -> F {
+        self.layers[self.height()][0]
+    }
+
+    pub fn leaves(&self) -> &[F] {
+        &self.layers[0]
+    }
+
+    pub fn prove(&self, index: usize) -> MerkleProof<F> {
+        let siblings = (0..self.height())
+            .map(|h| self.layers[h][(index >> h) ^ 1])
+            .collect();
+        MerkleProof { siblings }
+    }
+
+    /// Replaces the leaf at `index`, and recomputes the nodes on its path.
+    pub fn update(&mut self, index: usize, leaf: F) {
+        self.layers[0][index] = leaf;
+        for h in 0..self.height() {
+            let parent_index = index >> (h + 1);
+            let left = self.layers[h][2 * parent_index];
+            let right = self.layers[h][2 * parent_index + 1];
+            self.layers[h + 1][parent_index] = merkle_compress(&self.hash, left, right);
+        }
+    }
+}
+
+/// A Merkle tree of a fixed height in which every leaf starts out as zero. Only subtrees with a
+/// nonzero leaf are stored, so the tree can be tall enough to index leaves by keys.
+///
+/// Zero marks an empty leaf, so a proof that the leaf at some index is zero is a proof of
+/// non-membership.
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree<F: Field, H: TranscriptHash<F> = RescueTranscriptHash<F>> {
+    hash: H,
+    height: usize,
+    /// The root of an empty subtree of each height.
+    empty_roots: Vec<F>,
+    /// The nodes which differ from the empty subtree root of their height, keyed by height and
+    /// index.
+    nodes: HashMap<(usize, usize), F>,
+}
+
+impl<F: Field, H: TranscriptHash<F>> SparseMerkleTree<F, H> {
+    pub fn new(height: usize, security_bits: usize) -> Self {
+        assert!(
+            height < std::mem::size_of::<usize>() * 8,
+            "Indices must fit in a usize"
+        );
+
+        let hash = H::new(security_bits);
+        let mut empty_roots = vec![F::ZERO];
+        for h in 0..height {
+            empty_roots.push(merkle_compress(&hash, empty_roots[h], empty_roots[h]));
+        }
+        SparseMerkleTree {
+            hash,
+            height,
+            empty_roots,
+            nodes: HashMap::new(),
+        }
+    }
+
+    pub fn hash(&self) -> &H {
+        &self.hash
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn root(&self) -> F {
+        self.node(self.height, 0)
+    }
+
+    /// Returns the leaf at `index`, which is zero if it was never set.
+    pub fn get(&self, index: usize) -> F {
+        self.node(0, index)
+    }
+
+    /// Returns a proof of the leaf at `index`. If the leaf is empty, this proves non-membership.
+    pub fn prove(&self, index: usize) -> MerkleProof<F> {
+        let siblings = (0..self.height)
+            .map(|h| self.node(h, (index >> h) ^ 1))
+            .collect();
+        MerkleProof { siblings }
+    }
+
+    /// Sets the leaf at `index`, and returns a proof for `MerkleProof::verify_update` from the old
+    /// root to the new one. Setting a leaf to zero removes it.
+    pub fn update(&mut self, index: usize, leaf: F) -> MerkleProof<F> {
+        assert!(index >> self.height == 0, "Index out of range");
+        let proof = self.prove(index);
+
+        let mut node = leaf;
+        for h in 0..=self.height {
+            let node_index = index >> h;
+            self.set_node(h, node_index, node);
+            if h < self.height {
+                let sibling = self.node(h, node_index ^ 1);
+                node = if node_index & 1 == 0 {
+                    merkle_compress(&self.hash, node, sibling)
+                } else {
+                    merkle_compress(&self.hash, sibling, node)
+                };
+            }
+        }
+        proof
+    }
+
+    fn node(&self, height: usize, index: usize) -> F {
+        match self.nodes.get(&(height, index)) {
+            Some(&node) => node,
+            None => self.empty_roots[height],
+        }
+    }
+
+    fn set_node(&mut self, height: usize, index: usize, node: F) {
+        if node == self.empty_roots[height] {
+            self.nodes.remove(&(height, index));
+        } else {
+            self.nodes.insert((height, index), node);
+        }
+    }
+}
+
+/// A sparse Merkle tree whose leaves are filled in order, as in a log or a commitment list. Every
+/// index at or beyond `num_leaves` is empty, and can be proven so with `prove`.
+#[derive(Clone, Debug)]
+pub struct AppendOnlyMerkleTree<F: Field, H: TranscriptHash<F> = RescueTranscriptHash<F>> {
+    tree: SparseMerkleTree<F, H>,
+    num_leaves: usize,
+}
+
+impl<F: Field, H: TranscriptHash<F>> AppendOnlyMerkleTree<F, H> {
+    pub fn new(height: usize, security_bits: usize) -> Self {
+        AppendOnlyMerkleTree {
+            tree: SparseMerkleTree::new(height, security_bits),
+            num_leaves: 0,
+        }
+    }
+
+    pub fn hash(&self) -> &H {
+        self.tree.hash()
+    }
+
+    pub fn height(&self) -> usize {
+        self.tree.height()
+    }
+
+    pub fn root(&self) -> F {
+        self.tree.root()
+    }
+
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    pub fn get(&self, index: usize) -> F {
+        self.tree.get(index)
+    }
+
+    pub fn prove(&self, index: usize) -> MerkleProof<F> {
+        self.tree.prove(index)
+    }
+
+    /// Appends a leaf, which must be nonzero since zero marks empty leaves. Returns the leaf's
+    /// index, along with a proof of the update from an empty leaf.
+    pub fn append(&mut self, leaf: F) -> (usize, MerkleProof<F>) {
+        assert!(leaf.is_nonzero(), "Zero is reserved for empty leaves");
+        let index = self.num_leaves;
+        let proof = self.tree.update(index, leaf);
+        self.num_leaves += 1;
+        (index, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{rescue_hash_n_to_1, AppendOnlyMerkleTree, Field, MerkleTree, SparseMerkleTree, TweedledeeBase};
+
+    type F = TweedledeeBase;
+
+    #[test]
+    fn test_merkle_tree() {
+        let leaves: Vec<F> = (0..8).map(|_| F::rand()).collect();
+        let mut tree = MerkleTree::<F>::new(leaves.clone(), 128);
+        assert_eq!(tree.height(), 3);
+
+        // A tree of two leaves matches the hand-rolled Rescue version.
+        let pair = MerkleTree::<F>::new(leaves[..2].to_vec(), 128);
+        assert_eq!(pair.root(), rescue_hash_n_to_1(leaves[..2].to_vec(), 128));
+
+        for (i, &leaf) in leaves.iter().enumerate() {
+            let proof = tree.prove(i);
+            assert!(proof.verify(tree.hash(), leaf, i, tree.root()));
+            assert!(!proof.verify(tree.hash(), leaf, i ^ 1, tree.root()));
+            assert!(!proof.verify(tree.hash(), leaf + F::ONE, i, tree.root()));
+        }
+
+        let old_root = tree.root();
+        let proof = tree.prove(5);
+        let new_leaf = F::rand();
+        tree.update(5, new_leaf);
+        assert_eq!(tree.leaves()[5], new_leaf);
+        assert!(proof.verify_update(tree.hash(), 5, leaves[5], new_leaf, old_root, tree.root()));
+
+        let mut new_leaves = leaves;
+        new_leaves[5] = new_leaf;
+        assert_eq!(tree.root(), MerkleTree::<F>::new(new_leaves, 128).root());
+    }
+
+    #[test]
+    fn test_sparse_merkle_tree() {
+        let mut tree = SparseMerkleTree::<F>::new(3, 128);
+        let empty_root = tree.root();
+        assert_eq!(
+            empty_root,
+            MerkleTree::<F>::new(vec![F::ZERO; 8], 128).root()
+        );
+
+        let leaf = F::rand();
+        let old_root = tree.root();
+        let proof = tree.update(6, leaf);
+        assert!(proof.verify_update(tree.hash(), 6, F::ZERO, leaf, old_root, tree.root()));
+        assert_eq!(tree.get(6), leaf);
+
+        // The other leaves are still provably empty.
+        let proof = tree.prove(2);
+        assert!(proof.verify(tree.hash(), F::ZERO, 2, tree.root()));
+        assert!(!tree.prove(6).verify(tree.hash(), F::ZERO, 6, tree.root()));
+
+        let mut leaves = vec![F::ZERO; 8];
+        leaves[6] = leaf;
+        assert_eq!(tree.root(), MerkleTree::<F>::new(leaves, 128).root());
+
+        // Removing the leaf restores the empty tree.
+        tree.update(6, F::ZERO);
+        assert_eq!(tree.root(), empty_root);
+    }
+
+    #[test]
+    fn test_tall_sparse_merkle_tree() {
+        let mut tree = SparseMerkleTree::<F>::new(32, 128);
+        let index = 0xdead_beef;
+        let leaf = F::rand();
+        tree.update(index, leaf);
+        assert!(tree
+            .prove(index)
+            .verify(tree.hash(), leaf, index, tree.root()));
+        assert!(tree
+            .prove(index + 1)
+            .verify(tree.hash(), F::ZERO, index + 1, tree.root()));
+    }
+
+    #[test]
+    fn test_append_only_merkle_tree() {
+        let mut tree = AppendOnlyMerkleTree::<F>::new(4, 128);
+        for i in 0..5 {
+            let leaf = F::rand();
+            let old_root = tree.root();
+            let (index, proof) = tree.append(leaf);
+            assert_eq!(index, i);
+            assert!(proof.verify_update(tree.hash(), i, F::ZERO, leaf, old_root, tree.root()));
+        }
+        assert_eq!(tree.num_leaves(), 5);
+        assert!(tree.prove(5).verify(tree.hash(), F::ZERO, 5, tree.root()));
+    }
+}
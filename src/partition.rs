@@ -39,15 +39,20 @@ impl<F: Field> TargetPartitions<F> {
         let a_index = self.indices[&a];
         let b_index = self.indices[&b];
         if a_index != b_index {
-            // Merge a's partition into b's partition, leaving a's partition empty.
-            // We have to clone because Rust's borrow checker doesn't know that
-            // self.partitions[b_index] and self.partitions[b_index] are disjoint.
-            let mut a_partition = self.partitions[a_index].clone();
-            let b_partition = &mut self.partitions[b_index];
-            for a_sibling in &a_partition {
-                *self.indices.get_mut(a_sibling).unwrap() = b_index;
+            // Move the smaller partition into the larger one, leaving the smaller one empty. Some
+            // targets, like constant wires, are copied very often, so always moving a's partition
+            // would make building large circuits quadratic.
+            let (from_index, to_index) =
+                if self.partitions[a_index].len() <= self.partitions[b_index].len() {
+                    (a_index, b_index)
+                } else {
+                    (b_index, a_index)
+                };
+            let mut from_partition = std::mem::take(&mut self.partitions[from_index]);
+            for sibling in &from_partition {
+                *self.indices.get_mut(sibling).unwrap() = to_index;
             }
-            b_partition.append(&mut a_partition);
+            self.partitions[to_index].append(&mut from_partition);
         }
     }
 
@@ -0,0 +1,74 @@
+use crate::plonk_util::pedersen_commit;
+use crate::{blake_hash_usize_to_curve, msm_precompute, AffinePoint, Curve, Field, MsmPrecomputation};
+
+/// Generators for Pedersen vector commitments. They are derived by hashing to the curve, so nobody
+/// knows any discrete log relations between them.
+#[derive(Clone, Debug)]
+pub struct PedersenGenerators<C: Curve> {
+    pub g: Vec<AffinePoint<C>>,
+    pub h: AffinePoint<C>,
+    g_msm_precomputation: MsmPrecomputation<C>,
+}
+
+impl<C: Curve> PedersenGenerators<C> {
+    /// Derives generators for committing to up to `n` values. We skip the seed zero, since the
+    /// in-circuit MSMs start from that point, so a generator equal to it would lead to exceptional
+    /// cases.
+    pub fn new(n: usize) -> Self {
+        let g: Vec<AffinePoint<C>> = (1..=n).map(blake_hash_usize_to_curve).collect();
+        let g_msm_precomputation = msm_precompute(&AffinePoint::batch_to_projective(&g), 8);
+        PedersenGenerators {
+            g,
+            h: blake_hash_usize_to_curve(n + 1),
+            g_msm_precomputation,
+        }
+    }
+
+    /// Computes the commitment `sum_i [v_i] g_i + [r] h`, where `r` is the blinding factor.
+    pub fn commit(&self, values: &[C::ScalarField], blinding: C::ScalarField) -> AffinePoint<C> {
+        assert!(values.len() <= self.g.len(), "Too many values");
+        // Unused generators are given zero weight.
+        let mut padded_values = values.to_vec();
+        padded_values.resize(self.g.len(), C::ScalarField::ZERO);
+        pedersen_commit(&padded_values, blinding, self.h, &self.g_msm_precomputation).to_affine()
+    }
+
+    /// Checks that `commitment` opens to the given values and blinding factor.
+    pub fn verify_opening(
+        &self,
+        commitment: AffinePoint<C>,
+        values: &[C::ScalarField],
+        blinding: C::ScalarField,
+    ) -> bool {
+        values.len() <= self.g.len() && self.commit(values, blinding) == commitment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Curve, Field, PedersenGenerators, Tweedledum};
+
+    type C = Tweedledum;
+    type SF = <C as Curve>::ScalarField;
+
+    #[test]
+    fn test_pedersen_commit() {
+        let generators = PedersenGenerators::<C>::new(3);
+        let xs = vec![SF::rand(), SF::rand(), SF::rand()];
+        let ys = vec![SF::rand(), SF::rand(), SF::rand()];
+        let (r_x, r_y) = (SF::rand(), SF::rand());
+        let commitment_x = generators.commit(&xs, r_x);
+        let commitment_y = generators.commit(&ys, r_y);
+        assert!(generators.verify_opening(commitment_x, &xs, r_x));
+        assert!(!generators.verify_opening(commitment_x, &ys, r_x));
+        assert!(!generators.verify_opening(commitment_x, &xs, r_y));
+
+        // Commitments are additively homomorphic.
+        let sums: Vec<SF> = xs.iter().zip(ys.iter()).map(|(&x, &y)| x + y).collect();
+        let commitment_sum = commitment_x.to_projective() + commitment_y.to_projective();
+        assert_eq!(
+            generators.commit(&sums, r_x + r_y),
+            commitment_sum.to_affine()
+        );
+    }
+}
@@ -15,13 +15,14 @@ use crate::poly_commit::PolynomialCommitment;
 use crate::polynomial::Polynomial;
 use crate::target::Target;
 use crate::util::{ceil_div_usize, log2_strict};
+use crate::verifier::verification_key_digest;
 use crate::witness::{PartialWitness, Witness, WitnessGenerator};
-use crate::{evaluate_all_constraints, fft_with_precomputation_power_of_2, AffinePoint, FftPrecomputation, Field, HaloCurve, MsmPrecomputation, OpeningSet, VerificationKey};
+use crate::{evaluate_all_constraints, fft_with_precomputation_power_of_2, AffinePoint, Blake3TranscriptHash, FftPrecomputation, Field, HaloCurve, MsmPrecomputation, OpeningSet, PoseidonTranscriptHash, RescueTranscriptHash, TranscriptHash, TranscriptHashType, VerificationKey};
 
 pub(crate) const NUM_WIRES: usize = 9;
 pub(crate) const NUM_ROUTED_WIRES: usize = 6;
 pub(crate) const NUM_ADVICE_WIRES: usize = NUM_WIRES - NUM_ROUTED_WIRES;
-pub(crate) const NUM_CONSTANTS: usize = 6;
+pub(crate) const NUM_CONSTANTS: usize = 7;
 pub(crate) const GRID_WIDTH: usize = 65;
 // This is currently dominated by Base4SumGate. It has degree-4n constraints, and its prefix is 4
 // bits long, so its filtered constraints are degree-8n. Dividing by Z_H makes t degree-7n.
@@ -30,6 +31,8 @@ pub(crate) const QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER: usize = 7;
 /// Contains all data needed to generate and/or verify proofs.
 pub struct Circuit<C: HaloCurve> {
     pub security_bits: usize,
+    /// The hash used to generate Fiat-Shamir challenges.
+    pub transcript_hash: TranscriptHashType,
     pub num_public_inputs: usize,
     pub num_gates_without_pis: usize,
     pub gate_constants: Vec<Vec<C::ScalarField>>,
@@ -87,7 +90,42 @@ impl<C: HaloCurve> Circuit<C> {
         old_proofs: &[OldProof<C>],
         blinding_commitments: bool,
     ) -> Result<Proof<C>> {
-        let mut challenger = Challenger::new(self.security_bits);
+        match self.transcript_hash {
+            TranscriptHashType::Rescue => self
+                .generate_proof_with_transcript_hash::<InnerC, RescueTranscriptHash<_>>(
+                    witness,
+                    old_proofs,
+                    blinding_commitments,
+                ),
+            TranscriptHashType::Poseidon => self
+                .generate_proof_with_transcript_hash::<InnerC, PoseidonTranscriptHash<_>>(
+                    witness,
+                    old_proofs,
+                    blinding_commitments,
+                ),
+            TranscriptHashType::Blake3 => self
+                .generate_proof_with_transcript_hash::<InnerC, Blake3TranscriptHash>(
+                    witness,
+                    old_proofs,
+                    blinding_commitments,
+                ),
+        }
+    }
+
+    fn generate_proof_with_transcript_hash<
+        InnerC: HaloCurve<BaseField = C::ScalarField>,
+        H: TranscriptHash<C::BaseField>,
+    >(
+        &self,
+        witness: &Witness<C::ScalarField>,
+        old_proofs: &[OldProof<C>],
+        blinding_commitments: bool,
+    ) -> Result<Proof<C>> {
+        debug_assert_eq!(H::TYPE, self.transcript_hash);
+        let mut challenger = Challenger::<C::BaseField, H>::for_verification_key(
+            self.vk_digest(),
+            self.security_bits,
+        );
 
         // Convert the witness both to coefficient form and a degree-8n LDE.
         let wire_values_by_wire_index = &witness.transpose();
@@ -118,9 +156,12 @@ impl<C: HaloCurve> Circuit<C> {
         };
 
         // Generate a random beta and gamma from the transcript.
-        challenger
-            .observe_affine_points(&PolynomialCommitment::commitments_to_affine_vec(&c_wires));
-        let (beta_bf, gamma_bf) = challenger.get_2_challenges();
+        challenger.append_affine_points(
+            "c_wires",
+            &PolynomialCommitment::commitments_to_affine_vec(&c_wires),
+        );
+        let beta_bf = challenger.challenge("beta");
+        let gamma_bf = challenger.challenge("gamma");
         let beta_sf = beta_bf.try_convert::<C::ScalarField>()?;
         let gamma_sf = gamma_bf.try_convert::<C::ScalarField>()?;
 
@@ -142,8 +183,8 @@ impl<C: HaloCurve> Circuit<C> {
         );
 
         // Generate a random alpha from the transcript.
-        challenger.observe_affine_point(c_plonk_z.to_affine());
-        let alpha_bf = challenger.get_challenge();
+        challenger.append_affine_point("c_plonk_z", c_plonk_z.to_affine());
+        let alpha_bf = challenger.challenge("alpha");
         let alpha_sf = alpha_bf.try_convert::<C::ScalarField>()?;
 
         // Generate the vanishing polynomial.
@@ -239,21 +280,28 @@ impl<C: HaloCurve> Circuit<C> {
             .collect::<Vec<_>>();
 
         // Observe the `t` polynomial commitment.
-        challenger
-            .observe_affine_points(&PolynomialCommitment::commitments_to_affine_vec(&c_plonk_t));
+        challenger.append_affine_points(
+            "c_plonk_t",
+            &PolynomialCommitment::commitments_to_affine_vec(&c_plonk_t),
+        );
         // If the proof doesn't output public inputs, observe the `pis_quotient` polynomial commitment and the public inputs.
-        challenger.observe_affine_point(c_pis_quotient.to_affine());
+        challenger.append_affine_point("c_pis_quotient", c_pis_quotient.to_affine());
         // Observe the public inputs
-        challenger.observe_elements(
+        challenger.append_message(
+            "public_inputs",
             &C::ScalarField::try_convert_all(&public_inputs)
                 .expect("Public inputs should fit in both fields"),
         );
         // Observe the old proofs' `G` points.
-        old_proofs
-            .iter()
-            .for_each(|old_proof| challenger.observe_affine_point(old_proof.halo_g));
+        challenger.append_affine_points(
+            "old_proofs",
+            &old_proofs
+                .iter()
+                .map(|old_proof| old_proof.halo_g)
+                .collect::<Vec<_>>(),
+        );
         // Generate a random `zeta` from the transcript.
-        let zeta_bf = challenger.get_challenge();
+        let zeta_bf = challenger.challenge("zeta");
         let zeta_sf =
             C::try_convert_b2s(zeta_bf).expect("should fit in both fields with high probability");
 
@@ -301,8 +349,10 @@ impl<C: HaloCurve> Circuit<C> {
             .collect();
 
         // Generate random v, u, and x from the transcript.
-        challenger.observe_elements(&all_opened_values_bf);
-        let (v_bf, u_bf, u_scaling_bf) = challenger.get_3_challenges();
+        challenger.append_message("openings", &all_opened_values_bf);
+        let v_bf = challenger.challenge("v");
+        let u_bf = challenger.challenge("u");
+        let u_scaling_bf = challenger.challenge("u_scaling");
         let v_sf = v_bf.try_convert::<C::ScalarField>()?;
         let u_sf = u_bf.try_convert::<C::ScalarField>()?;
         let u_scaling_sf = u_scaling_bf.try_convert::<C::ScalarField>()?;
@@ -487,6 +537,16 @@ impl<C: HaloCurve> Circuit<C> {
     pub fn generate_partial_witness(
         &self,
         inputs: PartialWitness<C::ScalarField>,
+    ) -> PartialWitness<C::ScalarField> {
+        self.generate_partial_witness_with_overrides(inputs, &HashMap::new())
+    }
+
+    /// Like `generate_partial_witness`, but any value a generator produces for a target in
+    /// `overrides` is replaced by the overriding value, which later generators then build on.
+    fn generate_partial_witness_with_overrides(
+        &self,
+        inputs: PartialWitness<C::ScalarField>,
+        overrides: &HashMap<Target<C::ScalarField>, C::ScalarField>,
     ) -> PartialWitness<C::ScalarField> {
         let start = Instant::now();
 
@@ -535,7 +595,18 @@ impl<C: HaloCurve> Circuit<C> {
             for &generator_idx in &pending_generator_indices {
                 let generator: &dyn WitnessGenerator<C::ScalarField> =
                     self.generators[generator_idx].borrow();
-                let result = generator.generate(&self.gate_constants, &witness);
+                let mut result = generator.generate(&self.gate_constants, &witness);
+                if !overrides.is_empty() {
+                    let mut overridden_result = PartialWitness::new();
+                    for target in result.all_populated_targets() {
+                        let value = overrides
+                            .get(&target)
+                            .copied()
+                            .unwrap_or_else(|| result.get_target(target));
+                        overridden_result.set_target(target, value);
+                    }
+                    result = overridden_result;
+                }
                 populated_targets.extend(result.all_populated_targets());
                 witness.extend(result);
                 completed_generator_indices.insert(generator_idx);
@@ -590,6 +661,47 @@ impl<C: HaloCurve> Circuit<C> {
         Witness::from_partial(&partial_witness, self.degree())
     }
 
+    /// Generates a witness and a proof from `inputs`, and returns whether the proof verifies. In
+    /// debug builds, a witness which violates a constraint makes witness or proof generation panic,
+    /// which is treated as a failure, so that tests of malicious witnesses work in any build.
+    #[cfg(test)]
+    pub(crate) fn proof_verifies<InnerC: HaloCurve<BaseField = C::ScalarField>>(
+        &self,
+        inputs: PartialWitness<C::ScalarField>,
+    ) -> bool {
+        self.proof_verifies_with_overrides::<InnerC>(inputs, &[])
+    }
+
+    /// Like `proof_verifies`, but each overridden target, along with every target copied to or
+    /// from it, takes the given value in place of the generated one, and everything generated from
+    /// it is derived from that value. This mimics a prover which tampers with a generated value,
+    /// such as a hint, rather than with the inputs, since generated values would replace any inputs
+    /// for the same targets.
+    #[cfg(test)]
+    pub(crate) fn proof_verifies_with_overrides<InnerC: HaloCurve<BaseField = C::ScalarField>>(
+        &self,
+        inputs: PartialWitness<C::ScalarField>,
+        overrides: &[(Target<C::ScalarField>, C::ScalarField)],
+    ) -> bool {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut overridden_values = HashMap::new();
+            for &(target, value) in overrides {
+                for &sibling in self.routing_target_partitions.get_partition(target) {
+                    overridden_values.insert(sibling, value);
+                }
+            }
+            let partial_witness =
+                self.generate_partial_witness_with_overrides(inputs, &overridden_values);
+
+            let witness = Witness::from_partial(&partial_witness, self.degree());
+            let public_inputs = self.get_public_inputs(&witness);
+            let proof = self.generate_proof::<InnerC>(&witness, &[], true).unwrap();
+            let vk = self.to_vk();
+            crate::verify_proof::<C, InnerC>(&public_inputs, &proof, &[], &vk, true).is_ok()
+        }))
+        .unwrap_or(false)
+    }
+
     /// For the given set of targets, find any copy constraints involving those targets and populate
     /// the witness with copies as needed.
     fn generate_copies(
@@ -631,11 +743,25 @@ impl<C: HaloCurve> Circuit<C> {
             num_public_inputs: self.num_public_inputs,
             num_gates_without_pis: self.num_gates_without_pis,
             security_bits: self.security_bits,
+            transcript_hash: self.transcript_hash,
             pedersen_g_msm_precomputation: Some(self.pedersen_g_msm_precomputation.clone()),
             fft_precomputation: Some(self.fft_precomputation_n.clone()),
         }
     }
 
+    /// The digest of this circuit's verification key; see `VerificationKey::digest`.
+    pub fn vk_digest(&self) -> C::BaseField {
+        verification_key_digest(
+            &PolynomialCommitment::commitments_to_affine_vec(&self.c_constants),
+            &PolynomialCommitment::commitments_to_affine_vec(&self.c_s_sigmas),
+            self.degree(),
+            self.num_public_inputs,
+            self.num_gates_without_pis,
+            self.security_bits,
+            self.transcript_hash,
+        )
+    }
+
     pub fn get_public_inputs(&self, witness: &Witness<C::ScalarField>) -> Vec<C::ScalarField> {
         (0..self.num_public_inputs)
             .map(|i| witness.get_indices(self.num_gates_without_pis + 2 * (i / NUM_WIRES), i % NUM_WIRES))
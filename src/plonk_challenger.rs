@@ -1,72 +1,138 @@
-use crate::{rescue_permutation, AffinePoint, AffinePointTarget, CircuitBuilder, Curve, Field, HaloCurve, ProjectivePoint, Target, RESCUE_SPONGE_RATE, RESCUE_SPONGE_WIDTH};
+use std::marker::PhantomData;
+
+use crate::{AffinePoint, AffinePointTarget, CircuitBuilder, Curve, Field, HaloCurve, ProjectivePoint, RecursiveTranscriptHash, RescueTranscriptHash, Target, TranscriptHash};
+
+/// The domain separator of Plonk proof transcripts. This should change whenever the transcript
+/// format does, so that proofs from different versions of the protocol can't be confused.
+pub const PLONK_DOMAIN_SEPARATOR: &str = "plonky/plonk/v1";
+
+/// Encodes a transcript label as a field element, by interpreting its bytes as a little-endian
+/// integer. Labels are short, so this is injective.
+pub(crate) fn label_to_field<F: Field>(label: &str) -> F {
+    assert!(
+        label.len() < F::BYTES,
+        "Transcript labels must fit in a field element"
+    );
+    let mut bytes = label.as_bytes().to_vec();
+    bytes.resize(F::BYTES, 0);
+    F::from_canonical_u8_vec(bytes).expect("Label should be a canonical field element")
+}
 
 /// Observes prover messages, and generates challenges by hashing the transcript.
+///
+/// Each message and challenge is labeled, and every transcript starts with a domain separator, so
+/// that transcripts of different protocols, or different parts of one protocol, are never confused.
 #[derive(Clone)]
-pub struct Challenger<F: Field> {
+pub struct Challenger<F: Field, H: TranscriptHash<F> = RescueTranscriptHash<F>> {
     sponge_state: Vec<F>,
     input_buffer: Vec<F>,
     output_buffer: Vec<F>,
-    security_bits: usize,
+    hash: H,
 }
 
 /// Observes prover messages, and generates verifier challenges based on the transcript.
 ///
-/// The implementation is roughly based on a duplex sponge with a Rescue permutation. Note that in
-/// each round, our sponge can absorb an arbitrary number of prover messages and generate an
-/// arbitrary number of verifier challenges. This might appear to diverge from the duplex sponge
+/// The implementation is roughly based on a duplex sponge, whose permutation is given by the
+/// `TranscriptHash` `H`. By default this is Rescue, so that proofs can be verified recursively.
+/// Note that in each round, our sponge can absorb an arbitrary number of prover messages and
+/// generate an arbitrary number of verifier challenges. This might appear to diverge from the duplex sponge
 /// design, but it can be viewed as a duplex sponge whose inputs are sometimes zero (when we perform
 /// multiple squeezes) and whose outputs are sometimes ignored (when we perform multiple
 /// absorptions). Thus the security properties of a duplex sponge still apply to our design.
-impl<F: Field> Challenger<F> {
-    pub fn new(security_bits: usize) -> Challenger<F> {
-        Challenger {
-            sponge_state: vec![F::ZERO; RESCUE_SPONGE_WIDTH],
+impl<F: Field, H: TranscriptHash<F>> Challenger<F, H> {
+    /// Creates a transcript for the protocol identified by `domain_separator`.
+    pub fn new(domain_separator: &'static str, security_bits: usize) -> Challenger<F, H> {
+        let mut challenger = Challenger {
+            sponge_state: vec![F::ZERO; H::WIDTH],
             input_buffer: Vec::new(),
             output_buffer: Vec::new(),
-            security_bits,
-        }
+            hash: H::new(security_bits),
+        };
+        challenger.append_message("domain_separator", &[label_to_field(domain_separator)]);
+        challenger
     }
 
-    pub fn observe_element(&mut self, element: F) {
-        // Any buffered outputs are now invalid, since they wouldn't reflect this input.
-        self.output_buffer.clear();
-
-        self.input_buffer.push(element);
+    /// Creates the transcript of a Plonk proof, bound to the digest of the circuit's verification
+    /// key, so that a proof for one circuit can't be verified against another.
+    pub fn for_verification_key(vk_digest: F, security_bits: usize) -> Challenger<F, H> {
+        let mut challenger = Self::new(PLONK_DOMAIN_SEPARATOR, security_bits);
+        challenger.append_message("vk_digest", &[vk_digest]);
+        challenger
     }
 
-    pub fn observe_elements(&mut self, elements: &[F]) {
+    /// Absorbs a prover message. The label and the message length are absorbed first, so that
+    /// messages can't be confused with one another.
+    pub fn append_message(&mut self, label: &'static str, elements: &[F]) {
+        self.observe_element(label_to_field(label));
+        self.observe_element(F::from_canonical_usize(elements.len()));
         for &element in elements {
             self.observe_element(element);
         }
     }
 
-    pub fn observe_affine_point<C: Curve<BaseField = F>>(&mut self, point: AffinePoint<C>) {
-        debug_assert!(!point.zero);
-        self.observe_element(point.x);
-        self.observe_element(point.y);
+    pub fn append_affine_point<C: Curve<BaseField = F>>(
+        &mut self,
+        label: &'static str,
+        point: AffinePoint<C>,
+    ) {
+        self.append_affine_points(label, &[point]);
     }
 
-    pub fn observe_affine_points<C: Curve<BaseField = F>>(&mut self, points: &[AffinePoint<C>]) {
-        for &point in points {
-            self.observe_affine_point(point);
-        }
+    pub fn append_affine_points<C: Curve<BaseField = F>>(
+        &mut self,
+        label: &'static str,
+        points: &[AffinePoint<C>],
+    ) {
+        debug_assert!(points.iter().all(|point| !point.zero));
+        let coordinates: Vec<F> = points
+            .iter()
+            .flat_map(|point| vec![point.x, point.y])
+            .collect();
+        self.append_message(label, &coordinates);
     }
 
-    pub fn observe_proj_point<C: Curve<BaseField = F>>(&mut self, point: ProjectivePoint<C>) {
-        self.observe_affine_point(point.to_affine());
+    pub fn append_proj_point<C: Curve<BaseField = F>>(
+        &mut self,
+        label: &'static str,
+        point: ProjectivePoint<C>,
+    ) {
+        self.append_affine_point(label, point.to_affine());
     }
 
-    pub fn observe_proj_points<C: Curve<BaseField = F>>(&mut self, points: &[ProjectivePoint<C>]) {
-        self.observe_affine_points(&ProjectivePoint::batch_to_affine(points));
+    pub fn append_proj_points<C: Curve<BaseField = F>>(
+        &mut self,
+        label: &'static str,
+        points: &[ProjectivePoint<C>],
+    ) {
+        self.append_affine_points(label, &ProjectivePoint::batch_to_affine(points));
+    }
+
+    /// Generates a verifier challenge, after absorbing its label.
+    pub fn challenge(&mut self, label: &'static str) -> F {
+        self.observe_element(label_to_field(label));
+        self.get_challenge()
+    }
+
+    /// Generates `n` verifier challenges under a single label.
+    pub fn challenges(&mut self, label: &'static str, n: usize) -> Vec<F> {
+        self.observe_element(label_to_field(label));
+        (0..n).map(|_| self.get_challenge()).collect()
     }
 
-    pub fn get_challenge(&mut self) -> F {
+    fn observe_element(&mut self, element: F) {
+        // Any buffered outputs are now invalid, since they wouldn't reflect this input.
+        self.output_buffer.clear();
+
+        self.input_buffer.push(element);
+    }
+
+    fn get_challenge(&mut self) -> F {
         self.absorb_buffered_inputs();
 
         if self.output_buffer.is_empty() {
             // Evaluate the permutation to produce `r` new outputs.
-            self.sponge_state = rescue_permutation(&self.sponge_state, self.security_bits);
-            self.output_buffer = self.sponge_state[0..RESCUE_SPONGE_RATE].to_vec();
+            self.sponge_state = self.hash.permute(&self.sponge_state);
+            self.output_buffer = self.sponge_state[0..H::RATE].to_vec();
         }
 
         self.output_buffer
@@ -74,99 +140,137 @@ impl<F: Field> Challenger<F> {
             .expect("Output buffer should be non-empty")
     }
 
-    pub fn get_2_challenges(&mut self) -> (F, F) {
-        (self.get_challenge(), self.get_challenge())
-    }
-
-    pub fn get_3_challenges(&mut self) -> (F, F, F) {
-        (
-            self.get_challenge(),
-            self.get_challenge(),
-            self.get_challenge(),
-        )
-    }
-
-    pub fn get_n_challenges(&mut self, n: usize) -> Vec<F> {
-        (0..n).map(|_| self.get_challenge()).collect()
-    }
-
     /// Absorb any buffered inputs. After calling this, the input buffer will be empty.
     fn absorb_buffered_inputs(&mut self) {
-        for input_chunk in self.input_buffer.chunks(RESCUE_SPONGE_RATE) {
+        // With nothing to absorb, the buffered outputs are still valid; resetting them would
+        // return the same challenge again.
+        if self.input_buffer.is_empty() {
+            return;
+        }
+
+        for input_chunk in self.input_buffer.chunks(H::RATE) {
             // Add the inputs to our sponge state.
             for (i, &input) in input_chunk.iter().enumerate() {
                 self.sponge_state[i] = self.sponge_state[i] + input;
             }
 
             // Apply the permutation.
-            self.sponge_state = rescue_permutation(&self.sponge_state, self.security_bits);
+            self.sponge_state = self.hash.permute(&self.sponge_state);
         }
 
-        self.output_buffer = self.sponge_state[0..RESCUE_SPONGE_RATE].to_vec();
+        self.output_buffer = self.sponge_state[0..H::RATE].to_vec();
 
         self.input_buffer.clear();
     }
 }
 
 /// A recursive version of `Challenger`.
-pub(crate) struct RecursiveChallenger<F: Field> {
+pub(crate) struct RecursiveChallenger<
+    F: Field,
+    H: RecursiveTranscriptHash<F> = RescueTranscriptHash<F>,
+> {
     sponge_state: Vec<Target<F>>,
     input_buffer: Vec<Target<F>>,
     output_buffer: Vec<Target<F>>,
+    _phantom: PhantomData<H>,
 }
 
-impl<F: Field> RecursiveChallenger<F> {
+impl<F: Field, H: RecursiveTranscriptHash<F>> RecursiveChallenger<F, H> {
     pub(crate) fn new<C: HaloCurve<ScalarField = F>>(
         builder: &mut CircuitBuilder<C>,
-    ) -> RecursiveChallenger<F> {
+        domain_separator: &'static str,
+    ) -> RecursiveChallenger<F, H> {
         let zero = builder.zero_wire();
-        RecursiveChallenger {
-            sponge_state: vec![zero; RESCUE_SPONGE_WIDTH],
+        let mut challenger = RecursiveChallenger {
+            sponge_state: vec![zero; H::WIDTH],
             input_buffer: Vec::new(),
             output_buffer: Vec::new(),
-        }
+            _phantom: PhantomData,
+        };
+        let domain_separator = builder.constant_wire(label_to_field(domain_separator));
+        challenger.append_message(builder, "domain_separator", &[domain_separator]);
+        challenger
     }
 
-    pub(crate) fn observe_element(&mut self, target: Target<F>) {
-        // Any buffered outputs are now invalid, since they wouldn't reflect this input.
-        self.output_buffer.clear();
-
-        self.input_buffer.push(target);
+    pub(crate) fn for_verification_key<C: HaloCurve<ScalarField = F>>(
+        builder: &mut CircuitBuilder<C>,
+        vk_digest: Target<F>,
+    ) -> RecursiveChallenger<F, H> {
+        let mut challenger = Self::new(builder, PLONK_DOMAIN_SEPARATOR);
+        challenger.append_message(builder, "vk_digest", &[vk_digest]);
+        challenger
     }
 
-    pub(crate) fn observe_elements(&mut self, targets: &[Target<F>]) {
+    pub(crate) fn append_message<C: HaloCurve<ScalarField = F>>(
+        &mut self,
+        builder: &mut CircuitBuilder<C>,
+        label: &'static str,
+        targets: &[Target<F>],
+    ) {
+        let label = builder.constant_wire(label_to_field(label));
+        let len = builder.constant_wire(F::from_canonical_usize(targets.len()));
+        self.observe_element(label);
+        self.observe_element(len);
         for &target in targets {
             self.observe_element(target);
         }
     }
 
-    pub(crate) fn observe_affine_point<C: Curve<BaseField = F>>(
+    pub(crate) fn append_affine_point<
+        C: HaloCurve<ScalarField = F>,
+        InnerC: Curve<BaseField = F>,
+    >(
         &mut self,
-        point: AffinePointTarget<C>,
+        builder: &mut CircuitBuilder<C>,
+        label: &'static str,
+        point: AffinePointTarget<InnerC>,
     ) {
-        self.observe_element(point.x);
-        self.observe_element(point.y);
+        self.append_affine_points(builder, label, &[point]);
     }
 
-    pub(crate) fn observe_affine_points<C: Curve<BaseField = F>>(
+    pub(crate) fn append_affine_points<
+        C: HaloCurve<ScalarField = F>,
+        InnerC: Curve<BaseField = F>,
+    >(
         &mut self,
-        points: &[AffinePointTarget<C>],
+        builder: &mut CircuitBuilder<C>,
+        label: &'static str,
+        points: &[AffinePointTarget<InnerC>],
     ) {
-        for &point in points {
-            self.observe_affine_point(point);
-        }
+        let coordinates: Vec<Target<F>> = points
+            .iter()
+            .flat_map(|point| vec![point.x, point.y])
+            .collect();
+        self.append_message(builder, label, &coordinates);
     }
 
-    pub(crate) fn get_challenge<C: HaloCurve<ScalarField = F>>(
+    pub(crate) fn challenge<C: HaloCurve<ScalarField = F>>(
         &mut self,
         builder: &mut CircuitBuilder<C>,
-    ) -> Target<C::ScalarField> {
+        label: &'static str,
+    ) -> Target<F> {
+        let label = builder.constant_wire(label_to_field(label));
+        self.observe_element(label);
+        self.get_challenge(builder)
+    }
+
+    fn observe_element(&mut self, target: Target<F>) {
+        // Any buffered outputs are now invalid, since they wouldn't reflect this input.
+        self.output_buffer.clear();
+
+        self.input_buffer.push(target);
+    }
+
+    fn get_challenge<C: HaloCurve<ScalarField = F>>(
+        &mut self,
+        builder: &mut CircuitBuilder<C>,
+    ) -> Target<F> {
         self.absorb_buffered_inputs(builder);
 
         if self.output_buffer.is_empty() {
             // Evaluate the permutation to produce `r` new outputs.
-            self.sponge_state = builder.rescue_permutation(&self.sponge_state);
-            self.output_buffer = self.sponge_state[0..RESCUE_SPONGE_RATE].to_vec();
+            self.sponge_state = H::permute_recursively(builder, &self.sponge_state);
+            self.output_buffer = self.sponge_state[0..H::RATE].to_vec();
         }
 
         self.output_buffer
@@ -174,54 +278,28 @@ impl<F: Field> RecursiveChallenger<F> {
             .expect("Output buffer should be non-empty")
     }
 
-    pub(crate) fn get_2_challenges<C: HaloCurve<ScalarField = F>>(
-        &mut self,
-        builder: &mut CircuitBuilder<C>,
-    ) -> (Target<C::ScalarField>, Target<C::ScalarField>) {
-        (self.get_challenge(builder), self.get_challenge(builder))
-    }
-
-    #[allow(clippy::type_complexity)]
-    pub(crate) fn get_3_challenges<C: HaloCurve<ScalarField = F>>(
-        &mut self,
-        builder: &mut CircuitBuilder<C>,
-    ) -> (
-        Target<C::ScalarField>,
-        Target<C::ScalarField>,
-        Target<C::ScalarField>,
-    ) {
-        (
-            self.get_challenge(builder),
-            self.get_challenge(builder),
-            self.get_challenge(builder),
-        )
-    }
-
-    #[allow(dead_code)]
-    pub(crate) fn get_n_challenges<C: HaloCurve<ScalarField = F>>(
-        &mut self,
-        builder: &mut CircuitBuilder<C>,
-        n: usize,
-    ) -> Vec<Target<C::ScalarField>> {
-        (0..n).map(|_| self.get_challenge(builder)).collect()
-    }
-
     /// Absorb any buffered inputs. After calling this, the input buffer will be empty.
     fn absorb_buffered_inputs<C: HaloCurve<ScalarField = F>>(
         &mut self,
         builder: &mut CircuitBuilder<C>,
     ) {
-        for input_chunk in self.input_buffer.chunks(RESCUE_SPONGE_RATE) {
+        // With nothing to absorb, the buffered outputs are still valid; resetting them would
+        // return the same challenge again.
+        if self.input_buffer.is_empty() {
+            return;
+        }
+
+        for input_chunk in self.input_buffer.chunks(H::RATE) {
             // Add the inputs to our sponge state.
             for (i, &input) in input_chunk.iter().enumerate() {
                 self.sponge_state[i] = builder.add(self.sponge_state[i], input);
             }
 
             // Apply the permutation.
-            self.sponge_state = builder.rescue_permutation(&self.sponge_state);
+            self.sponge_state = H::permute_recursively(builder, &self.sponge_state);
         }
 
-        self.output_buffer = self.sponge_state[0..RESCUE_SPONGE_RATE].to_vec();
+        self.output_buffer = self.sponge_state[0..H::RATE].to_vec();
 
         self.input_buffer.clear();
     }
@@ -229,12 +307,21 @@ impl<F: Field> RecursiveChallenger<F> {
 
 #[cfg(test)]
 mod tests {
-    use crate::plonk_challenger::{Challenger, RecursiveChallenger};
-    use crate::{CircuitBuilder, Curve, Field, PartialWitness, Target, Tweedledum};
+    use crate::plonk_challenger::{label_to_field, Challenger, RecursiveChallenger};
+    use crate::{CircuitBuilder, Curve, Field, PartialWitness, PoseidonTranscriptHash, RecursiveTranscriptHash, RescueTranscriptHash, Target, Tweedledum};
+
+    #[test]
+    fn test_consistency_rescue() {
+        test_consistency::<RescueTranscriptHash<_>>();
+    }
 
-    /// Tests for consistency between `Challenger` and `RecursiveChallenger`.
     #[test]
-    fn test_consistency() {
+    fn test_consistency_poseidon() {
+        test_consistency::<PoseidonTranscriptHash<_>>();
+    }
+
+    /// Tests for consistency between `Challenger` and `RecursiveChallenger`.
+    fn test_consistency<H: RecursiveTranscriptHash<<Tweedledum as Curve>::ScalarField>>() {
         type C = Tweedledum;
         type SF = <C as Curve>::ScalarField;
 
@@ -249,22 +336,29 @@ mod tests {
             .map(|&n| (0..n).map(|_| SF::rand()).collect::<Vec<_>>())
             .collect();
 
-        let mut challenger = Challenger::new(128);
+        let mut challenger = Challenger::<SF, H>::for_verification_key(SF::ONE, 128);
         let mut outputs_per_round: Vec<Vec<SF>> = Vec::new();
         for (r, inputs) in inputs_per_round.iter().enumerate() {
-            challenger.observe_elements(inputs);
-            outputs_per_round.push(challenger.get_n_challenges(num_outputs_per_round[r]));
+            challenger.append_message("inputs", inputs);
+            outputs_per_round.push(challenger.challenges("outputs", num_outputs_per_round[r]));
         }
 
         let mut builder = CircuitBuilder::<C>::new(128);
-        let mut recursive_challenger = RecursiveChallenger::new(&mut builder);
+        let vk_digest = builder.one_wire();
+        let mut recursive_challenger =
+            RecursiveChallenger::<SF, H>::for_verification_key(&mut builder, vk_digest);
         let mut recursive_outputs_per_round: Vec<Vec<Target<<C as Curve>::ScalarField>>> =
             Vec::new();
         for (r, inputs) in inputs_per_round.iter().enumerate() {
-            recursive_challenger.observe_elements(&builder.constant_wires(inputs));
-            recursive_outputs_per_round.push(
-                recursive_challenger.get_n_challenges(&mut builder, num_outputs_per_round[r]),
-            );
+            let input_targets = builder.constant_wires(inputs);
+            recursive_challenger.append_message(&mut builder, "inputs", &input_targets);
+            // Mirror Challenger::challenges, which has no recursive counterpart.
+            let label = builder.constant_wire(label_to_field("outputs"));
+            recursive_challenger.observe_element(label);
+            let outputs = (0..num_outputs_per_round[r])
+                .map(|_| recursive_challenger.get_challenge(&mut builder))
+                .collect();
+            recursive_outputs_per_round.push(outputs);
         }
         let circuit = builder.build();
         let witness = circuit.generate_partial_witness(PartialWitness::new());
@@ -274,5 +368,38 @@ mod tests {
             .collect();
 
         assert_eq!(outputs_per_round, recursive_output_values_per_round);
+
+        // Challenges drawn together must still be distinct.
+        for outputs in &outputs_per_round {
+            for (i, x) in outputs.iter().enumerate() {
+                assert!(outputs[i + 1..].iter().all(|y| y != x));
+            }
+        }
+    }
+
+    #[test]
+    fn test_labels_separate_messages() {
+        type F = <Tweedledum as Curve>::ScalarField;
+        let message = [F::rand(), F::rand()];
+
+        let mut challenger: Challenger<F> = Challenger::new("test", 128);
+        challenger.append_message("a", &message);
+        let challenge = challenger.challenge("c");
+
+        // The same message under a different label.
+        let mut challenger: Challenger<F> = Challenger::new("test", 128);
+        challenger.append_message("b", &message);
+        assert_ne!(challenger.challenge("c"), challenge);
+
+        // The same message split into two messages.
+        let mut challenger: Challenger<F> = Challenger::new("test", 128);
+        challenger.append_message("a", &message[..1]);
+        challenger.append_message("a", &message[1..]);
+        assert_ne!(challenger.challenge("c"), challenge);
+
+        // The same transcript in a different domain.
+        let mut challenger: Challenger<F> = Challenger::new("other test", 128);
+        challenger.append_message("a", &message);
+        assert_ne!(challenger.challenge("c"), challenge);
     }
 }
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::plonk_challenger::Challenger;
 use crate::plonk_util::{halo_g, halo_n, halo_s};
-use crate::{AffinePoint, AffinePointTarget, Curve, Field, HaloCurve, PartialWitness, Target, SECURITY_BITS};
+use crate::{AffinePoint, AffinePointTarget, Curve, Field, HaloCurve, PartialWitness, Target, TranscriptHash, VerificationKey, SECURITY_BITS};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SchnorrProof<C: HaloCurve> {
@@ -56,37 +56,49 @@ impl<C: HaloCurve> Proof<C> {
     }
 
     // Computes all challenges used in the proof verification.
-    pub fn get_challenges(
+    pub fn get_challenges<H: TranscriptHash<C::BaseField>>(
         &self,
+        vk: &VerificationKey<C>,
         public_inputs: &[C::ScalarField],
         old_proofs: &[OldProof<C>],
     ) -> Result<ProofChallenge<C>> {
-        let mut challenger = Challenger::new(SECURITY_BITS);
+        let mut challenger =
+            Challenger::<C::BaseField, H>::for_verification_key(vk.digest(), SECURITY_BITS);
         let error_msg = "Conversion from base to scalar field failed.";
-        challenger.observe_affine_points(&self.c_wires);
-        let (beta_bf, gamma_bf) = challenger.get_2_challenges();
+        challenger.append_affine_points("c_wires", &self.c_wires);
+        let beta_bf = challenger.challenge("beta");
+        let gamma_bf = challenger.challenge("gamma");
         let beta = C::try_convert_b2s(beta_bf).map_err(|_| anyhow!(error_msg))?;
         let gamma = C::try_convert_b2s(gamma_bf).map_err(|_| anyhow!(error_msg))?;
-        challenger.observe_affine_point(self.c_plonk_z);
-        let alpha_bf = challenger.get_challenge();
+        challenger.append_affine_point("c_plonk_z", self.c_plonk_z);
+        let alpha_bf = challenger.challenge("alpha");
         let alpha = C::try_convert_b2s(alpha_bf).map_err(|_| anyhow!(error_msg))?;
-        challenger.observe_affine_points(&self.c_plonk_t);
-        challenger.observe_affine_point(self.c_pis_quotient);
-        challenger.observe_elements(
+        challenger.append_affine_points("c_plonk_t", &self.c_plonk_t);
+        challenger.append_affine_point("c_pis_quotient", self.c_pis_quotient);
+        challenger.append_message(
+            "public_inputs",
             &C::ScalarField::try_convert_all(public_inputs)
                 .expect("Public inputs should fit in both fields"),
         );
-        old_proofs
-            .iter()
-            .for_each(|old_proof| challenger.observe_affine_point(old_proof.halo_g));
-        let zeta_bf = challenger.get_challenge();
+        challenger.append_affine_points(
+            "old_proofs",
+            &old_proofs
+                .iter()
+                .map(|old_proof| old_proof.halo_g)
+                .collect::<Vec<_>>(),
+        );
+        let zeta_bf = challenger.challenge("zeta");
         let zeta = C::try_convert_b2s(zeta_bf).map_err(|_| anyhow!(error_msg))?;
+        let mut all_opened_values_bf = Vec::new();
         for os in self.all_opening_sets().iter() {
             for &f in os.to_vec().iter() {
-                challenger.observe_element(C::try_convert_s2b(f).map_err(|_| anyhow!(error_msg))?);
+                all_opened_values_bf.push(C::try_convert_s2b(f).map_err(|_| anyhow!(error_msg))?);
             }
         }
-        let (v_bf, u_bf, u_scaling_bf) = challenger.get_3_challenges();
+        challenger.append_message("openings", &all_opened_values_bf);
+        let v_bf = challenger.challenge("v");
+        let u_bf = challenger.challenge("u");
+        let u_scaling_bf = challenger.challenge("u_scaling");
         let v = C::try_convert_b2s(v_bf).map_err(|_| anyhow!(error_msg))?;
         let u = C::try_convert_b2s(u_bf).map_err(|_| anyhow!(error_msg))?;
         let u_scaling = C::try_convert_b2s(u_scaling_bf).map_err(|_| anyhow!(error_msg))?;
@@ -94,8 +106,8 @@ impl<C: HaloCurve> Proof<C> {
         // Compute IPA challenges.
         let mut halo_us = Vec::new();
         for i in 0..self.halo_l.len() {
-            challenger.observe_affine_points(&[self.halo_l[i], self.halo_r[i]]);
-            let r_bf = challenger.get_challenge();
+            challenger.append_affine_points("halo_lr", &[self.halo_l[i], self.halo_r[i]]);
+            let r_bf = challenger.challenge("halo_challenge");
             let r_sf = r_bf.try_convert::<C::ScalarField>()?;
             let r_bits = &r_sf.to_canonical_bool_vec()[..SECURITY_BITS];
             let u_j_squared = halo_n::<C>(r_bits);
@@ -106,8 +118,8 @@ impl<C: HaloCurve> Proof<C> {
         }
 
         // Compute challenge for Schnorr protocol.
-        challenger.observe_affine_point(self.schnorr_proof.r);
-        let schnorr_challenge_bf = challenger.get_challenge();
+        challenger.append_affine_point("schnorr_r", self.schnorr_proof.r);
+        let schnorr_challenge_bf = challenger.challenge("schnorr");
         let schnorr_challenge =
             C::try_convert_b2s(schnorr_challenge_bf).map_err(|_| anyhow!(error_msg))?;
 
@@ -190,6 +202,10 @@ pub struct ProofTarget<C: Curve, InnerC: Curve<BaseField = C::ScalarField>> {
     pub c_plonk_z: AffinePointTarget<InnerC>,
     /// A commitment to the quotient polynomial.
     pub c_plonk_t: Vec<AffinePointTarget<InnerC>>,
+    /// A commitment to the public input quotient polynomial.
+    pub c_pis_quotient: AffinePointTarget<InnerC>,
+    /// The public inputs of the proof being verified, converted to `C::ScalarField`.
+    pub public_inputs: Vec<Target<C::ScalarField>>,
 
     /// The opening of each polynomial at each `PublicInputGate` index.
     pub o_public_inputs: Option<Vec<OpeningSetTarget<C>>>,
@@ -246,10 +262,13 @@ impl<C: HaloCurve, InnerC: HaloCurve<BaseField = C::ScalarField>> ProofTarget<C,
         &self,
         witness: &mut PartialWitness<C::ScalarField>,
         values: Proof<InnerC>,
+        public_inputs: &[InnerC::ScalarField],
     ) -> Result<()> {
         witness.set_point_targets(&self.c_wires, &values.c_wires);
         witness.set_point_target(self.c_plonk_z, values.c_plonk_z);
         witness.set_point_targets(&self.c_plonk_t, &values.c_plonk_t);
+        witness.set_point_target(self.c_pis_quotient, values.c_pis_quotient);
+        witness.set_targets(&self.public_inputs, &Field::try_convert_all(public_inputs)?);
 
         self.o_local.populate_witness(witness, &values.o_local)?;
         self.o_right.populate_witness(witness, &values.o_right)?;
@@ -326,6 +345,8 @@ pub struct OpeningSetTarget<C: Curve> {
     pub o_plonk_t: Vec<Target<C::ScalarField>>,
     /// The purported opening of some old proofs `halo_g` polynomials.
     pub o_old_proofs: Vec<Target<C::ScalarField>>,
+    /// The purported opening of the public input quotient polynomial.
+    pub o_pi_quotient: Target<C::ScalarField>,
 }
 
 impl<C: Curve> OpeningSetTarget<C> {
@@ -337,6 +358,7 @@ impl<C: Curve> OpeningSetTarget<C> {
             &[self.o_plonk_z],
             self.o_plonk_t.as_slice(),
             self.o_old_proofs.as_slice(),
+            &[self.o_pi_quotient],
         ]
         .concat()
     }
@@ -371,6 +393,10 @@ impl<C: Curve> OpeningSetTarget<C> {
             &Target::convert_slice(&self.o_old_proofs),
             &Field::try_convert_all(&values.o_old_proofs)?,
         );
+        witness.set_target(
+            self.o_pi_quotient.convert(),
+            Field::try_convert(&values.o_pi_quotient)?,
+        );
         Ok(())
     }
 }
@@ -3,10 +3,15 @@ use crate::plonk_challenger::RecursiveChallenger;
 use crate::plonk_proof::OldProofTarget;
 use crate::plonk_util::{powers_recursive, reduce_with_powers_recursive};
 use crate::util::ceil_div_usize;
-use crate::{get_subgroup_shift, hash_usize_to_curve, AffinePointTarget, Circuit, CircuitBuilder, CurveMulEndoResult, CurveMulOp, Field, HaloCurve, OpeningSetTarget, ProofTarget, SchnorrProofTarget, Target, GRID_WIDTH, NUM_CONSTANTS, NUM_ROUTED_WIRES, NUM_WIRES, QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER};
+use crate::{get_subgroup_shift, hash_usize_to_curve, AffinePointTarget, Circuit, CircuitBuilder, CurveMulEndoResult, CurveMulOp, Field, HaloCurve, OpeningSetTarget, ProofTarget, RecursiveTranscriptHash, RescueTranscriptHash, SchnorrProofTarget, Target, GRID_WIDTH, NUM_CONSTANTS, NUM_ROUTED_WIRES, NUM_WIRES, QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER};
 
 /// Wraps a `Circuit` for recursive verification with inputs for the proof data.
 /// The circuit is over the field `C::ScalarField` and verifies a proof performed over the curve `InnerC`.
+///
+/// The circuit does not fix which inner circuit it verifies. The inner verification key's digest is
+/// a free public input, the first one, which the inner transcript is bound to; whoever verifies the
+/// recursive proof must check that it matches the expected inner verification key, or else a proof
+/// of any circuit will be accepted.
 pub struct RecursiveCircuit<C: HaloCurve, InnerC: HaloCurve<BaseField = C::ScalarField>> {
     pub circuit: Circuit<C>,
     pub public_inputs: RecursionPublicInputs<C::ScalarField>,
@@ -17,6 +22,9 @@ pub struct RecursiveCircuit<C: HaloCurve, InnerC: HaloCurve<BaseField = C::Scala
 /// Public inputs of the recursive circuit. This contains data for the inner proof which is needed
 /// to complete verification of it.
 pub struct RecursionPublicInputs<F: Field> {
+    /// The digest of the inner proof's verification key, which its transcript is bound to. Nothing
+    /// in the circuit constrains it, so the outer verifier has to pin it.
+    vk_digest: Target<F>,
     beta: Target<F>,
     gamma: Target<F>,
     alpha: Target<F>,
@@ -38,6 +46,7 @@ fn num_public_input_gates(num_public_inputs: usize) -> usize {
     ceil_div_usize(num_public_inputs, NUM_WIRES)
 }
 
+/// Builds a circuit which verifies a proof generated with the default transcript hash, Rescue.
 pub fn recursive_verification_circuit<
     C: HaloCurve,
     InnerC: HaloCurve<BaseField = C::ScalarField>,
@@ -46,9 +55,30 @@ pub fn recursive_verification_circuit<
     security_bits: usize,
     num_public_inputs: usize,
     num_old_proofs: usize,
+) -> RecursiveCircuit<C, InnerC> {
+    recursive_verification_circuit_with_transcript_hash::<C, InnerC, RescueTranscriptHash<_>>(
+        degree_pow,
+        security_bits,
+        num_public_inputs,
+        num_old_proofs,
+    )
+}
+
+/// Builds a circuit which verifies a proof whose Fiat-Shamir challenges were generated with the
+/// transcript hash `H`.
+pub fn recursive_verification_circuit_with_transcript_hash<
+    C: HaloCurve,
+    InnerC: HaloCurve<BaseField = C::ScalarField>,
+    H: RecursiveTranscriptHash<C::ScalarField>,
+>(
+    degree_pow: usize,
+    security_bits: usize,
+    num_public_inputs: usize,
+    num_old_proofs: usize,
 ) -> RecursiveCircuit<C, InnerC> {
     let mut builder = CircuitBuilder::<C>::new(security_bits);
     let public_inputs = RecursionPublicInputs {
+        vk_digest: builder.add_public_input(),
         beta: builder.add_public_input(),
         gamma: builder.add_public_input(),
         alpha: builder.add_public_input(),
@@ -67,23 +97,13 @@ pub fn recursive_verification_circuit<
 
     let num_public_input_gates = num_public_input_gates(num_public_inputs);
 
-    let proof = ProofTarget::<C, InnerC> {
-        c_wires: builder.add_virtual_point_targets(NUM_WIRES),
-        c_plonk_z: builder.add_virtual_point_target(),
-        c_plonk_t: builder.add_virtual_point_targets(QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
-        o_public_inputs: Some(make_opening_sets(
-            &mut builder,
-            num_public_input_gates,
-            num_old_proofs,
-        )),
-        o_local: make_opening_set(&mut builder, num_old_proofs),
-        o_right: make_opening_set(&mut builder, num_old_proofs),
-        o_below: make_opening_set(&mut builder, num_old_proofs),
-        halo_l_i: builder.add_virtual_point_targets(degree_pow),
-        halo_r_i: builder.add_virtual_point_targets(degree_pow),
-        halo_g: builder.add_virtual_point_target(),
-        schnorr_proof: make_schnorr_proof(&mut builder),
-    };
+    let proof = make_proof::<C, InnerC>(
+        &mut builder,
+        degree_pow,
+        num_public_inputs,
+        num_public_input_gates,
+        num_old_proofs,
+    );
 
     let old_proofs = make_old_proofs(&mut builder, num_old_proofs, degree_pow);
 
@@ -107,37 +127,29 @@ pub fn recursive_verification_circuit<
     // TODO: Verify that each prover polynomial commitment is on the curve.
     // Can call curve_assert_valid.
 
-    // Compute random challenges.
-    let mut challenger = RecursiveChallenger::<C::ScalarField>::new(&mut builder);
-    challenger.observe_affine_points(&proof.c_wires);
-    let (beta, gamma) = challenger.get_2_challenges(&mut builder);
-    challenger.observe_affine_point(proof.c_plonk_z);
-    let alpha = challenger.get_challenge(&mut builder);
-    challenger.observe_affine_points(&proof.c_plonk_t);
-    let zeta = challenger.get_challenge(&mut builder);
-    challenger.observe_elements(&proof.all_opening_targets());
+    let ProofChallengeTargets {
+        beta,
+        gamma,
+        alpha,
+        zeta,
+        v,
+        u,
+        u_scaling,
+        raw_ipa_challenges,
+        schnorr_challenge,
+    } = get_challenges_recursively::<C, InnerC, H>(
+        &mut builder,
+        &proof,
+        &old_proofs,
+        public_inputs.vk_digest,
+    );
 
     verify_old_proof_evaluation::<C, InnerC>(&mut builder, &old_proofs, &proof.o_local, zeta);
 
-    // v: Challenge to combine different opening points.
-    // u: Challenge to combine different polynomials.
-    // u_scaling: Challenge for scaling u used in the IPA verification.
-    let (v, u, u_scaling) = challenger.get_3_challenges(&mut builder);
-
-    // Compute IPA challenges.
-    let mut raw_ipa_challenges = Vec::new();
-    for i in 0..degree_pow {
-        challenger.observe_affine_points(&[proof.halo_l_i[i], proof.halo_r_i[i]]);
-        let r = challenger.get_challenge(&mut builder);
-        raw_ipa_challenges.push(r);
-    }
-
-    // Compute challenge for Schnorr protocol.
-    let schnorr_challenge = challenger.get_challenge(&mut builder);
-
     let halo_us = verify_all_ipas::<C, InnerC>(
         &mut builder,
         &proof,
+        &old_proofs,
         zeta,
         u,
         v,
@@ -217,11 +229,96 @@ pub fn recursive_verification_circuit<
     }
 }
 
+/// The Fiat-Shamir challenges of a proof, as elements of the inner curve's base field.
+struct ProofChallengeTargets<F: Field> {
+    beta: Target<F>,
+    gamma: Target<F>,
+    alpha: Target<F>,
+    zeta: Target<F>,
+    v: Target<F>,
+    u: Target<F>,
+    u_scaling: Target<F>,
+    raw_ipa_challenges: Vec<Target<F>>,
+    schnorr_challenge: Target<F>,
+}
+
+/// The in-circuit equivalent of `Proof::get_challenges`, which must observe exactly the same
+/// transcript.
+fn get_challenges_recursively<
+    C: HaloCurve,
+    InnerC: HaloCurve<BaseField = C::ScalarField>,
+    H: RecursiveTranscriptHash<C::ScalarField>,
+>(
+    builder: &mut CircuitBuilder<C>,
+    proof: &ProofTarget<C, InnerC>,
+    old_proofs: &[OldProofTarget<InnerC>],
+    vk_digest: Target<C::ScalarField>,
+) -> ProofChallengeTargets<C::ScalarField> {
+    let mut challenger =
+        RecursiveChallenger::<C::ScalarField, H>::for_verification_key(builder, vk_digest);
+    challenger.append_affine_points(builder, "c_wires", &proof.c_wires);
+    let beta = challenger.challenge(builder, "beta");
+    let gamma = challenger.challenge(builder, "gamma");
+    challenger.append_affine_point(builder, "c_plonk_z", proof.c_plonk_z);
+    let alpha = challenger.challenge(builder, "alpha");
+    challenger.append_affine_points(builder, "c_plonk_t", &proof.c_plonk_t);
+    challenger.append_affine_point(builder, "c_pis_quotient", proof.c_pis_quotient);
+    challenger.append_message(builder, "public_inputs", &proof.public_inputs);
+    let old_proofs_halo_g = old_proofs
+        .iter()
+        .map(|old_proof| old_proof.halo_g)
+        .collect::<Vec<_>>();
+    challenger.append_affine_points(builder, "old_proofs", &old_proofs_halo_g);
+    let zeta = challenger.challenge(builder, "zeta");
+    // Like the native verifier, we only observe the openings at zeta, g zeta and g^65 zeta.
+    let openings = [&proof.o_local, &proof.o_right, &proof.o_below]
+        .iter()
+        .flat_map(|opening_set| opening_set.to_vec())
+        .collect::<Vec<_>>();
+    challenger.append_message(builder, "openings", &openings);
+
+    // v: Challenge to combine different opening points.
+    // u: Challenge to combine different polynomials.
+    // u_scaling: Challenge for scaling u used in the IPA verification.
+    let v = challenger.challenge(builder, "v");
+    let u = challenger.challenge(builder, "u");
+    let u_scaling = challenger.challenge(builder, "u_scaling");
+
+    // Compute IPA challenges.
+    let mut raw_ipa_challenges = Vec::new();
+    for i in 0..proof.halo_l_i.len() {
+        challenger.append_affine_points(
+            builder,
+            "halo_lr",
+            &[proof.halo_l_i[i], proof.halo_r_i[i]],
+        );
+        let r = challenger.challenge(builder, "halo_challenge");
+        raw_ipa_challenges.push(r);
+    }
+
+    // Compute challenge for Schnorr protocol.
+    challenger.append_affine_point(builder, "schnorr_r", proof.schnorr_proof.r);
+    let schnorr_challenge = challenger.challenge(builder, "schnorr");
+
+    ProofChallengeTargets {
+        beta,
+        gamma,
+        alpha,
+        zeta,
+        v,
+        u,
+        u_scaling,
+        raw_ipa_challenges,
+        schnorr_challenge,
+    }
+}
+
 /// Verify all IPAs in the given proof, and return IPA challenges.
 #[allow(clippy::too_many_arguments)]
 fn verify_all_ipas<C: HaloCurve, InnerC: HaloCurve<BaseField = C::ScalarField>>(
     builder: &mut CircuitBuilder<C>,
     proof: &ProofTarget<C, InnerC>,
+    old_proofs: &[OldProofTarget<InnerC>],
     zeta: Target<C::ScalarField>,
     u: Target<C::ScalarField>,
     v: Target<C::ScalarField>,
@@ -243,6 +340,11 @@ fn verify_all_ipas<C: HaloCurve, InnerC: HaloCurve<BaseField = C::ScalarField>>(
         proof.c_wires.clone(),
         vec![proof.c_plonk_z],
         proof.c_plonk_t.clone(),
+        old_proofs
+            .iter()
+            .map(|old_proof| old_proof.halo_g)
+            .collect(),
+        vec![proof.c_pis_quotient],
     ]
     .concat();
     let mut c_reduction_muls = Vec::new();
@@ -423,6 +525,7 @@ fn make_opening_set<C: HaloCurve>(
         o_plonk_z: builder.add_virtual_target(),
         o_plonk_t: builder.add_virtual_targets(QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
         o_old_proofs: builder.add_virtual_targets(num_old_proofs),
+        o_pi_quotient: builder.add_virtual_target(),
     }
 }
 fn make_opening_sets<C: HaloCurve>(
@@ -435,6 +538,34 @@ fn make_opening_sets<C: HaloCurve>(
         .collect()
 }
 
+fn make_proof<C: HaloCurve, InnerC: HaloCurve<BaseField = C::ScalarField>>(
+    builder: &mut CircuitBuilder<C>,
+    degree_pow: usize,
+    num_public_inputs: usize,
+    num_public_input_gates: usize,
+    num_old_proofs: usize,
+) -> ProofTarget<C, InnerC> {
+    ProofTarget {
+        c_wires: builder.add_virtual_point_targets(NUM_WIRES),
+        c_plonk_z: builder.add_virtual_point_target(),
+        c_plonk_t: builder.add_virtual_point_targets(QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
+        c_pis_quotient: builder.add_virtual_point_target(),
+        public_inputs: builder.add_virtual_targets(num_public_inputs),
+        o_public_inputs: Some(make_opening_sets(
+            builder,
+            num_public_input_gates,
+            num_old_proofs,
+        )),
+        o_local: make_opening_set(builder, num_old_proofs),
+        o_right: make_opening_set(builder, num_old_proofs),
+        o_below: make_opening_set(builder, num_old_proofs),
+        halo_l_i: builder.add_virtual_point_targets(degree_pow),
+        halo_r_i: builder.add_virtual_point_targets(degree_pow),
+        halo_g: builder.add_virtual_point_target(),
+        schnorr_proof: make_schnorr_proof(builder),
+    }
+}
+
 fn make_schnorr_proof<C: HaloCurve, InnerC: HaloCurve<BaseField = C::ScalarField>>(
     builder: &mut CircuitBuilder<C>,
 ) -> SchnorrProofTarget<InnerC> {
@@ -619,3 +750,69 @@ fn verify_old_proof_evaluation<C: HaloCurve, InnerC: HaloCurve<BaseField = C::Sc
         builder.copy(computed, o_local.o_old_proofs[i]);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::plonk_recursion::{get_challenges_recursively, make_proof, num_public_input_gates};
+    use crate::{CircuitBuilder, Curve, Field, PartialWitness, RescueTranscriptHash, Tweedledee, Tweedledum};
+
+    type C = Tweedledum;
+    type InnerC = Tweedledee;
+    type InnerSF = <InnerC as Curve>::ScalarField;
+
+    #[test]
+    fn test_challenges_match_native() {
+        let mut inner_builder = CircuitBuilder::<InnerC>::new(128);
+        let public_inputs = inner_builder.add_public_inputs(2);
+        let sum = inner_builder.add(public_inputs[0], public_inputs[1]);
+        inner_builder.assert_nonzero(sum);
+        let inner_circuit = inner_builder.build();
+        let mut inner_partial_witness = PartialWitness::new();
+        inner_partial_witness.set_targets(&public_inputs, &[InnerSF::rand(), InnerSF::rand()]);
+        let inner_witness = inner_circuit.generate_witness(inner_partial_witness);
+        let inner_proof = inner_circuit
+            .generate_proof::<C>(&inner_witness, &[], true)
+            .unwrap();
+        let inner_public_inputs = inner_circuit.get_public_inputs(&inner_witness);
+        let inner_vk = inner_circuit.to_vk();
+        let expected = inner_proof
+            .get_challenges::<RescueTranscriptHash<_>>(&inner_vk, &inner_public_inputs, &[])
+            .unwrap();
+
+        let mut builder = CircuitBuilder::<C>::new(128);
+        let vk_digest = builder.add_virtual_target();
+        let proof = make_proof::<C, InnerC>(
+            &mut builder,
+            inner_circuit.degree_pow(),
+            inner_public_inputs.len(),
+            num_public_input_gates(inner_public_inputs.len()),
+            0,
+        );
+        let challenges = get_challenges_recursively::<C, InnerC, RescueTranscriptHash<_>>(
+            &mut builder,
+            &proof,
+            &[],
+            vk_digest,
+        );
+        let circuit = builder.build();
+
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_target(vk_digest, inner_vk.digest());
+        proof
+            .populate_witness(&mut partial_witness, inner_proof, &inner_public_inputs)
+            .unwrap();
+        let witness = circuit.generate_partial_witness(partial_witness);
+        let get = |target| -> InnerSF { witness.get_target(target).try_convert().unwrap() };
+        assert_eq!(get(challenges.beta), expected.beta);
+        assert_eq!(get(challenges.gamma), expected.gamma);
+        assert_eq!(get(challenges.alpha), expected.alpha);
+        assert_eq!(get(challenges.zeta), expected.zeta);
+        assert_eq!(get(challenges.v), expected.v);
+        assert_eq!(get(challenges.u), expected.u);
+        assert_eq!(get(challenges.u_scaling), expected.u_scaling);
+        assert_eq!(
+            get(challenges.schnorr_challenge),
+            expected.schnorr_challenge
+        );
+    }
+}
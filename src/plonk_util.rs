@@ -197,8 +197,9 @@ pub fn pedersen_hash<C: Curve>(
     msm_execute_parallel(pedersen_g_msm_precomputation, xs)
 }
 
-#[allow(dead_code)]
-fn pedersen_commit<C: Curve>(
+/// Computes `sum_i [x_i] g_i + [opening] h`, where the `g_i` are the generators in the given
+/// precomputation.
+pub fn pedersen_commit<C: Curve>(
     xs: &[C::ScalarField],
     opening: C::ScalarField,
     h: AffinePoint<C>,
@@ -1,6 +1,50 @@
+use anyhow::Result;
+
+use crate::plonk_challenger::Challenger;
 use crate::plonk_util::pedersen_hash;
 use crate::MsmPrecomputation;
-use crate::{AffinePoint, Curve, Field, ProjectivePoint};
+use crate::{AffinePoint, Curve, Field, ProjectivePoint, TranscriptHash};
+
+/// A scheme for committing to polynomials and proving their evaluations at a set of points.
+///
+/// Openings are batched: every committed polynomial is opened at every opening point. Challenges
+/// are drawn from `challenger`, which is expected to have already observed the commitments and
+/// the purported evaluations, in the same order for the prover and the verifier.
+pub trait PolynomialCommitmentScheme<C: Curve> {
+    /// Parameters needed to commit to polynomials and to generate opening proofs.
+    type ProverParams;
+    /// Parameters needed to verify opening proofs.
+    type VerifierParams;
+    type OpeningProof;
+
+    /// Commits to a polynomial given by its coefficients. If `blinding` is true, the commitment
+    /// is hiding.
+    fn commit(
+        params: &Self::ProverParams,
+        coeffs: &[C::ScalarField],
+        blinding: bool,
+    ) -> PolynomialCommitment<C>;
+
+    /// Proves the evaluations of all `polynomials_coeffs` at all `opening_points`.
+    fn open<H: TranscriptHash<C::BaseField>>(
+        params: &Self::ProverParams,
+        polynomials_coeffs: &[&[C::ScalarField]],
+        commitments: &[PolynomialCommitment<C>],
+        opening_points: &[C::ScalarField],
+        challenger: &mut Challenger<C::BaseField, H>,
+    ) -> Result<Self::OpeningProof>;
+
+    /// Verifies an opening proof, where `openings[i][j]` is the purported evaluation of the `j`th
+    /// committed polynomial at the `i`th opening point.
+    fn verify<H: TranscriptHash<C::BaseField>>(
+        params: &Self::VerifierParams,
+        commitments: &[AffinePoint<C>],
+        opening_points: &[C::ScalarField],
+        openings: &[Vec<C::ScalarField>],
+        proof: &Self::OpeningProof,
+        challenger: &mut Challenger<C::BaseField, H>,
+    ) -> Result<bool>;
+}
 
 #[derive(Debug, Copy, Clone)]
 /// Represents a curve point either in affine or projective coordinates.
@@ -26,6 +70,15 @@ impl<C: Curve> From<AffinePoint<C>> for PolynomialCommitment<C> {
     }
 }
 
+impl<C: Curve> From<ProjectivePoint<C>> for PolynomialCommitment<C> {
+    fn from(p: ProjectivePoint<C>) -> Self {
+        Self {
+            commitment: CurvePoint::Projective(p),
+            randomness: C::ScalarField::ZERO,
+        }
+    }
+}
+
 impl<C: Curve> PolynomialCommitment<C> {
     /// Creates a polynomial commitment from a vector of coefficients.
     /// If `blinding` is true, a random blinding factor is used. Otherwise, it is set to zero.
@@ -0,0 +1,279 @@
+use num::ToPrimitive;
+
+use crate::rescue::shake_field_elements;
+use crate::{field_order, field_to_biguint, mds_matrix, Field, POSEIDON_SPONGE_WIDTH};
+
+pub fn poseidon_hash_1_to_1<F: Field>(input: F, security_bits: usize) -> F {
+    poseidon_hash_n_to_1(vec![input], security_bits)
+}
+
+pub fn poseidon_hash_n_to_1<F: Field>(inputs: Vec<F>, security_bits: usize) -> F {
+    let params = PoseidonParams::new(POSEIDON_SPONGE_WIDTH, security_bits);
+    poseidon_sponge(&params, &inputs, 1)[0]
+}
+
+pub fn poseidon_hash_n_to_2<F: Field>(inputs: Vec<F>, security_bits: usize) -> (F, F) {
+    let params = PoseidonParams::new(POSEIDON_SPONGE_WIDTH, security_bits);
+    let outputs = poseidon_sponge(&params, &inputs, 2);
+    (outputs[0], outputs[1])
+}
+
+/// An instance of the Poseidon permutation, with its round numbers, round constants and MDS matrix
+/// precomputed.
+#[derive(Clone, Debug)]
+pub struct PoseidonParams<F: Field> {
+    width: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+    /// The constants added at the start of each round.
+    round_constants: Vec<Vec<F>>,
+    /// The MDS matrix, indexed by row then column.
+    mds: Vec<Vec<F>>,
+}
+
+impl<F: Field> PoseidonParams<F> {
+    pub fn new(width: usize, security_bits: usize) -> Self {
+        let (full_rounds, partial_rounds) = recommended_poseidon_rounds::<F>(width, security_bits);
+        let mds_matrix = mds_matrix::<F>(width);
+        PoseidonParams {
+            width,
+            full_rounds,
+            partial_rounds,
+            round_constants: generate_poseidon_constants(width, full_rounds, partial_rounds),
+            mds: (0..width)
+                .map(|r| (0..width).map(|c| mds_matrix.get(r, c)).collect())
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn full_rounds(&self) -> usize {
+        self.full_rounds
+    }
+
+    pub fn partial_rounds(&self) -> usize {
+        self.partial_rounds
+    }
+
+    /// The constants added at the start of each round.
+    pub fn round_constants(&self) -> &[Vec<F>] {
+        &self.round_constants
+    }
+
+    /// The MDS matrix, indexed by row then column.
+    pub fn mds(&self) -> &[Vec<F>] {
+        &self.mds
+    }
+
+    /// Returns whether round `r` of the permutation is a full round. Half of the full rounds come
+    /// before the partial rounds, and half after.
+    pub(crate) fn is_full_round(&self, r: usize) -> bool {
+        r < self.full_rounds / 2 || r >= self.full_rounds / 2 + self.partial_rounds
+    }
+}
+
+/// A sponge with a capacity of one element, like `rescue_sponge`, but using the Poseidon
+/// permutation.
+pub fn poseidon_sponge<F: Field>(
+    params: &PoseidonParams<F>,
+    inputs: &[F],
+    num_outputs: usize,
+) -> Vec<F> {
+    let rate = params.width() - 1;
+    let mut state = vec![F::ZERO; params.width()];
+
+    // Absorb all input chunks.
+    for input_chunk in inputs.chunks(rate) {
+        for i in 0..input_chunk.len() {
+            state[i] = state[i] + input_chunk[i];
+        }
+        state = poseidon_permutation(params, &state);
+    }
+
+    // Squeeze until we have the desired number of outputs.
+    let mut outputs = Vec::new();
+    loop {
+        for i in 0..rate {
+            outputs.push(state[i]);
+            if outputs.len() == num_outputs {
+                return outputs;
+            }
+        }
+        state = poseidon_permutation(params, &state);
+    }
+}
+
+/// The Poseidon permutation. Each round adds the round constants, applies the `x^alpha` S-box to
+/// the whole state (in full rounds) or to the first element only (in partial rounds), and then
+/// applies the MDS matrix.
+pub fn poseidon_permutation<F: Field>(params: &PoseidonParams<F>, state: &[F]) -> Vec<F> {
+    assert_eq!(state.len(), params.width());
+    let mut state = state.to_vec();
+
+    for (r, round_constants) in params.round_constants().iter().enumerate() {
+        for (x, &constant) in state.iter_mut().zip(round_constants.iter()) {
+            *x = *x + constant;
+        }
+
+        if params.is_full_round(r) {
+            state = state.iter().map(|x| x.exp(F::ALPHA)).collect();
+        } else {
+            state[0] = state[0].exp(F::ALPHA);
+        }
+
+        state = params
+            .mds()
+            .iter()
+            .map(|row| F::inner_product(row, &state))
+            .collect();
+    }
+
+    state
+}
+
+/// Generates `width` round constants for each round of the permutation.
+///
+/// As with Rescue, the constants are read from SHAKE256, which is seeded with the ASCII string
+/// `Poseidon(p,t,f,r)`, where `p` is the field order in decimal, `t` is the width, and `f` and `r`
+/// are the numbers of full and partial rounds.
+fn generate_poseidon_constants<F: Field>(
+    width: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+) -> Vec<Vec<F>> {
+    let seed = format!(
+        "Poseidon({},{},{},{})",
+        field_order::<F>(),
+        width,
+        full_rounds,
+        partial_rounds
+    );
+    shake_field_elements::<F>(&seed, width * (full_rounds + partial_rounds))
+        .chunks(width)
+        .map(|constants| constants.to_vec())
+        .collect()
+}
+
+/// Returns the number of full and partial rounds, as chosen by `calc_round_numbers.py`, the
+/// reference parameter script accompanying the Poseidon paper.
+///
+/// Among the round numbers which satisfy the bounds of `poseidon_rounds_are_secure`, we add the
+/// recommended security margin of two full rounds and 7.5% more partial rounds, then take the
+/// choice with the fewest S-boxes, `t R_F + R_P`. With 128-bit security and a width of 4, this gives
+/// 8 full rounds and 56 partial rounds.
+pub(crate) fn recommended_poseidon_rounds<F: Field>(
+    width: usize,
+    security_bits: usize,
+) -> (usize, usize) {
+    let mut best: Option<(usize, usize)> = None;
+    let mut min_cost = usize::MAX;
+    for partial_rounds in 1..500 {
+        // The cost grows with the number of full rounds, so only the smallest secure one matters.
+        let full_rounds = (4..100).step_by(2).find(|&full_rounds| {
+            poseidon_rounds_are_secure::<F>(width, security_bits, full_rounds, partial_rounds)
+        });
+        if let Some(full_rounds) = full_rounds {
+            let full_rounds = full_rounds + 2;
+            let partial_rounds = (partial_rounds as f64 * 1.075).ceil() as usize;
+            let cost = width * full_rounds + partial_rounds;
+            let fewer_full_rounds = best.is_none_or(|(best_full, _)| full_rounds < best_full);
+            if cost < min_cost || (cost == min_cost && fewer_full_rounds) {
+                best = Some((full_rounds, partial_rounds));
+                min_cost = cost;
+            }
+        }
+    }
+    best.expect("No secure round numbers found")
+}
+
+/// Checks the bounds of section 4.1 of the Poseidon paper, as in `sat_inequiv_alpha` of the
+/// reference script: the number of full rounds needed to resist statistical attacks, and the total
+/// number of rounds needed to resist interpolation and three kinds of Gröbner basis attacks.
+fn poseidon_rounds_are_secure<F: Field>(
+    width: usize,
+    security_bits: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+) -> bool {
+    let alpha = field_to_biguint(F::ALPHA)
+        .to_f64()
+        .expect("alpha is too large");
+    let t = width as f64;
+    let m = security_bits as f64;
+    let r_p = partial_rounds as f64;
+    let log2_p = field_order::<F>().to_f64().expect("p is too large").log2();
+    let n = log2_p.ceil();
+    let log_alpha_2 = 1.0 / alpha.log2();
+
+    let statistical = if m <= (log2_p - (alpha - 1.0) / 2.0).floor() * (t + 1.0) {
+        6.0
+    } else {
+        10.0
+    };
+    let interpolation = 1.0 + (log_alpha_2 * m.min(n)).ceil() + t.log(alpha).ceil() - r_p;
+    let groebner_1 = log_alpha_2 * m.min(log2_p) - r_p;
+    let groebner_2 = t - 1.0 + log_alpha_2 * (m / (t + 1.0)).min(log2_p / 2.0) - r_p;
+    let groebner_3 = (t - 2.0 + m / (2.0 * alpha.log2()) - r_p) / (t - 1.0);
+
+    let bounds = [
+        statistical,
+        interpolation,
+        groebner_1,
+        groebner_2,
+        groebner_3,
+    ];
+    let min_full_rounds = bounds
+        .iter()
+        .map(|bound| bound.ceil())
+        .fold(f64::MIN, f64::max);
+    full_rounds as f64 >= min_full_rounds
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::poseidon::recommended_poseidon_rounds;
+    use crate::{poseidon_permutation, poseidon_sponge, Field, PoseidonParams, TweedledeeBase};
+
+    type F = TweedledeeBase;
+
+    #[test]
+    fn test_recommended_rounds() {
+        // The values given by the reference script, for the widths around ours.
+        assert_eq!(recommended_poseidon_rounds::<F>(3, 128), (8, 56));
+        assert_eq!(recommended_poseidon_rounds::<F>(4, 128), (8, 56));
+        assert_eq!(recommended_poseidon_rounds::<F>(5, 128), (8, 56));
+    }
+
+    #[test]
+    fn test_permutation_is_deterministic_and_mixes() {
+        let params = PoseidonParams::new(4, 128);
+        let state = vec![F::ZERO, F::ONE, F::TWO, F::ZERO];
+        let output = poseidon_permutation(&params, &state);
+        assert_eq!(output, poseidon_permutation(&params, &state));
+
+        // Changing any single input element should change every output element.
+        for i in 0..4 {
+            let mut tweaked_state = state.clone();
+            tweaked_state[i] = tweaked_state[i] + F::ONE;
+            let tweaked_output = poseidon_permutation(&params, &tweaked_state);
+            for j in 0..4 {
+                assert_ne!(output[j], tweaked_output[j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sponge_absorbs_every_chunk() {
+        let params = PoseidonParams::new(4, 128);
+        let inputs: Vec<F> = (0..7).map(F::from_canonical_usize).collect();
+        let hash = poseidon_sponge(&params, &inputs, 1)[0];
+        for i in 0..inputs.len() {
+            let mut tweaked_inputs = inputs.clone();
+            tweaked_inputs[i] = tweaked_inputs[i] + F::ONE;
+            assert_ne!(poseidon_sponge(&params, &tweaked_inputs, 1)[0], hash);
+        }
+    }
+}
@@ -1,21 +1,30 @@
+use num::integer::binomial;
+use num::{BigUint, Integer, One, ToPrimitive};
+
 use crate::util::ceil_div_usize;
-use crate::{apply_mds, Field, PRF};
-use rand_chacha::rand_core::SeedableRng;
-use rand_chacha::ChaCha8Rng;
+use crate::{biguint_to_field, field_to_biguint, mds_matrix, Field, Shake256, PRF, RESCUE_SPONGE_WIDTH};
 
-pub struct RescuePrf {
-    security_bits: usize,
+pub struct RescuePrf<F: Field> {
+    params: RescueParams<F>,
+}
+
+impl<F: Field> RescuePrf<F> {
+    pub fn new(security_bits: usize) -> Self {
+        RescuePrf {
+            params: RescueParams::new(RESCUE_SPONGE_WIDTH, security_bits),
+        }
+    }
 }
 
-impl Default for RescuePrf {
+impl<F: Field> Default for RescuePrf<F> {
     fn default() -> Self {
-        RescuePrf { security_bits: 128 }
+        Self::new(128)
     }
 }
 
-impl<F: Field> PRF<F> for RescuePrf {
+impl<F: Field> PRF<F> for RescuePrf<F> {
     fn rand(&self, x: F) -> F {
-        rescue_hash_1_to_1(x, self.security_bits)
+        self.params.sponge(&[x], 1)[0]
     }
 }
 
@@ -37,89 +46,501 @@ pub fn rescue_hash_n_to_3<F: Field>(inputs: Vec<F>, security_bits: usize) -> (F,
     (outputs[0], outputs[1], outputs[2])
 }
 
+/// Builds a Rescue instance of width `RESCUE_SPONGE_WIDTH` and runs its sponge. Callers which hash
+/// repeatedly should build a `RescueParams` once and call `RescueParams::sponge` instead.
 pub fn rescue_sponge<F: Field>(inputs: Vec<F>, num_outputs: usize, security_bits: usize) -> Vec<F> {
-    // This is mostly arbitrary, but we wouldn't want a huge width as the MDS layer could get
-    // expensive.
-    let rate = 3;
-    let capacity = 1;
-    let width = rate + capacity;
-
-    let mut state = vec![F::ZERO; width];
-
-    // Absorb all input chunks.
-    for input_chunk in inputs.chunks(rate) {
-        for i in 0..input_chunk.len() {
-            state[i] = state[i] + input_chunk[i];
-        }
-        state = rescue_permutation(&state, security_bits);
+    RescueParams::new(RESCUE_SPONGE_WIDTH, security_bits).sponge(&inputs, num_outputs)
+}
+
+/// Builds a Rescue instance of the state's width and applies its permutation. Callers which permute
+/// repeatedly should build a `RescueParams` once and call `RescueParams::permute` instead.
+pub fn rescue_permutation<F: Field>(state: &[F], security_bits: usize) -> Vec<F> {
+    RescueParams::new(state.len(), security_bits).permute(state)
+}
+
+/// The variants of Rescue which `RescueParams` can instantiate.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RescueVariant {
+    /// The original Rescue design, which our gates implement at width `RESCUE_SPONGE_WIDTH`. Each
+    /// round applies the `x^(1/alpha)` step (step A) before the `x^alpha` step (step B), and uses
+    /// our Cauchy MDS matrix.
+    Rescue,
+    /// Rescue-Prime (https://eprint.iacr.org/2020/1143), also known as Rescue-XLIX. Each round
+    /// applies the `x^alpha` step first, and the MDS matrix, round count and round constants follow
+    /// the specification's reference implementation. It is only supported natively.
+    RescuePrime,
+}
+
+/// An instance of the Rescue permutation, with its round constants, MDS matrix and inverse S-box
+/// exponent precomputed.
+#[derive(Clone, Debug)]
+pub struct RescueParams<F: Field> {
+    variant: RescueVariant,
+    width: usize,
+    /// The number of state elements which sponge inputs are never added to.
+    capacity: usize,
+    security_bits: usize,
+    /// The constants added at the end of the first and second step of each round.
+    round_constants: Vec<(Vec<F>, Vec<F>)>,
+    /// The MDS matrix, indexed by row then column.
+    mds: Vec<Vec<F>>,
+    /// The exponent `1 / alpha mod (p - 1)`, so that `x^alpha_inv` inverts the S-box `x^alpha`.
+    alpha_inv: F,
+}
+
+impl<F: Field> RescueParams<F> {
+    /// The original Rescue variant with a capacity of one element, as used by our gates.
+    pub fn new(width: usize, security_bits: usize) -> Self {
+        Self::with_variant(RescueVariant::Rescue, width, 1, security_bits)
     }
 
-    // Squeeze until we have the desired number of outputs.
-    let mut outputs = Vec::new();
-    loop {
-        for i in 0..rate {
-            outputs.push(state[i]);
-            if outputs.len() == num_outputs {
-                return outputs;
+    pub fn rescue_prime(width: usize, capacity: usize, security_bits: usize) -> Self {
+        Self::with_variant(RescueVariant::RescuePrime, width, capacity, security_bits)
+    }
+
+    pub fn with_variant(
+        variant: RescueVariant,
+        width: usize,
+        capacity: usize,
+        security_bits: usize,
+    ) -> Self {
+        assert!(
+            capacity > 0 && capacity < width,
+            "Capacity must be positive and less than the width"
+        );
+
+        let (round_constants, mds) = match variant {
+            RescueVariant::Rescue => {
+                let mds_matrix = mds_matrix::<F>(width);
+                let mds = (0..width)
+                    .map(|r| (0..width).map(|c| mds_matrix.get(r, c)).collect())
+                    .collect();
+                (generate_rescue_constants(width, security_bits), mds)
             }
+            RescueVariant::RescuePrime => (
+                generate_rescue_prime_constants(width, capacity, security_bits),
+                rescue_prime_mds_matrix(width),
+            ),
+        };
+
+        RescueParams {
+            variant,
+            width,
+            capacity,
+            security_bits,
+            round_constants,
+            mds,
+            alpha_inv: alpha_inverse::<F>(),
         }
-        state = rescue_permutation(&state, security_bits);
     }
-}
 
-pub fn rescue_permutation<F: Field>(state: &[F], security_bits: usize) -> Vec<F> {
-    let mut state = state.to_vec();
-    let width = state.len();
-    let constants = generate_rescue_constants(width, security_bits);
+    pub fn variant(&self) -> RescueVariant {
+        self.variant
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of state elements which sponge inputs are added to and outputs are read from.
+    pub fn rate(&self) -> usize {
+        self.width - self.capacity
+    }
+
+    pub fn security_bits(&self) -> usize {
+        self.security_bits
+    }
+
+    pub fn num_rounds(&self) -> usize {
+        self.round_constants.len()
+    }
 
-    for (step_a_constants, step_b_constants) in constants {
-        // Step A.
-        state = state.iter().map(|x| x.kth_root(F::ALPHA)).collect();
-        state = apply_mds(state);
-        state = add_vecs(state, step_a_constants);
+    /// The constants added at the end of the first and second step of each round. For the original
+    /// variant these are step A's and step B's constants, respectively.
+    pub fn round_constants(&self) -> &[(Vec<F>, Vec<F>)] {
+        &self.round_constants
+    }
+
+    /// The MDS matrix, indexed by row then column.
+    pub fn mds(&self) -> &[Vec<F>] {
+        &self.mds
+    }
+
+    pub fn permute(&self, state: &[F]) -> Vec<F> {
+        assert_eq!(state.len(), self.width);
+        let mut state = state.to_vec();
+
+        for (first_constants, second_constants) in &self.round_constants {
+            let (first_exponent, second_exponent) = match self.variant {
+                RescueVariant::Rescue => (self.alpha_inv, F::ALPHA),
+                RescueVariant::RescuePrime => (F::ALPHA, self.alpha_inv),
+            };
+
+            state = state.iter().map(|x| x.exp(first_exponent)).collect();
+            state = self.apply_mds(&state);
+            state = add_vecs(&state, first_constants);
+
+            state = state.iter().map(|x| x.exp(second_exponent)).collect();
+            state = self.apply_mds(&state);
+            state = add_vecs(&state, second_constants);
+        }
 
-        // Step B.
-        state = state.iter().map(|x| x.exp(F::ALPHA)).collect();
-        state = apply_mds(state);
-        state = add_vecs(state, step_b_constants);
+        state
     }
 
-    state
+    /// A sponge with this instance's rate and capacity. Unlike the Rescue-Prime reference hash, we
+    /// don't pad the inputs, so callers must fix the input length.
+    pub fn sponge(&self, inputs: &[F], num_outputs: usize) -> Vec<F> {
+        let rate = self.rate();
+        let mut state = vec![F::ZERO; self.width];
+
+        // Absorb all input chunks.
+        for input_chunk in inputs.chunks(rate) {
+            for i in 0..input_chunk.len() {
+                state[i] = state[i] + input_chunk[i];
+            }
+            state = self.permute(&state);
+        }
+
+        // Squeeze until we have the desired number of outputs.
+        let mut outputs = Vec::new();
+        loop {
+            for i in 0..rate {
+                outputs.push(state[i]);
+                if outputs.len() == num_outputs {
+                    return outputs;
+                }
+            }
+            state = self.permute(&state);
+        }
+    }
+
+    fn apply_mds(&self, state: &[F]) -> Vec<F> {
+        self.mds
+            .iter()
+            .map(|row| F::inner_product(row, state))
+            .collect()
+    }
 }
 
-fn add_vecs<F: Field>(a: Vec<F>, b: Vec<F>) -> Vec<F> {
+fn add_vecs<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
     a.iter()
         .zip(b.iter())
         .map(|(a_i, b_i)| *a_i + *b_i)
         .collect()
 }
 
+/// Returns `1 / alpha mod (p - 1)`, by finding an `n` such that `1 + n (p - 1)` is a multiple of
+/// `alpha`. See `Field::kth_root`.
+fn alpha_inverse<F: Field>() -> F {
+    let p_minus_1 = field_to_biguint(F::NEG_ONE);
+    let alpha = field_to_biguint(F::ALPHA);
+    let mut numerator = BigUint::one();
+    loop {
+        numerator += &p_minus_1;
+        if numerator.is_multiple_of(&alpha) {
+            return biguint_to_field(numerator.div_floor(&alpha).mod_floor(&p_minus_1));
+        }
+    }
+}
+
+/// Derives round constants with SHAKE256, like the reference instance generator of the Marvellous
+/// designs (https://github.com/KULeuven-COSIC/Marvellous).
+///
+/// SHAKE256 is seeded with the ASCII string `Rescue(p,m,s)`, where `p` is the field order in
+/// decimal, `m` is the width and `s` is the security level in bits. Each constant is read from the
+/// next `ceil(log2(p) / 8) + 1` bytes of output, as a little-endian integer reduced mod `p`. Each
+/// round takes `m` constants for step A, followed by `m` constants for step B.
 pub(crate) fn generate_rescue_constants<F: Field>(
     width: usize,
     security_bits: usize,
 ) -> Vec<(Vec<F>, Vec<F>)> {
-    // TODO: This should use deterministic randomness.
-    // FIX: Use ChaCha CSPRNG with a seed. This is somewhat similar to official implementation
-    // at https://github.com/KULeuven-COSIC/Marvellous/blob/master/instance_generator.sage where they
-    // use SHAKE256 with a seed to generate randomness.
-    let mut rng = ChaCha8Rng::seed_from_u64(1337);
-    let mut constants = Vec::new();
-    for _i in 0..recommended_rounds::<F>(width, security_bits) {
-        let mut step_a_constants = Vec::new();
-        for _k in 0..width {
-            step_a_constants.push(F::rand_from_rng(&mut rng));
-        }
+    let seed = format!("Rescue({},{},{})", field_order::<F>(), width, security_bits);
+    shake_round_constants(&seed, width, recommended_rounds::<F>(width, security_bits))
+}
 
-        let mut step_b_constants = Vec::new();
-        for _k in 0..width {
-            step_b_constants.push(F::rand_from_rng(&mut rng));
-        }
+/// Derives round constants as in the Rescue-Prime reference implementation. This is the same
+/// procedure as `generate_rescue_constants`, but SHAKE256 is seeded with `Rescue-XLIX(p,m,c,s)`,
+/// where `c` is the capacity.
+pub(crate) fn generate_rescue_prime_constants<F: Field>(
+    width: usize,
+    capacity: usize,
+    security_bits: usize,
+) -> Vec<(Vec<F>, Vec<F>)> {
+    let seed = format!(
+        "Rescue-XLIX({},{},{},{})",
+        field_order::<F>(),
+        width,
+        capacity,
+        security_bits
+    );
+    let num_rounds = rescue_prime_rounds::<F>(width, capacity, security_bits);
+    shake_round_constants(&seed, width, num_rounds)
+}
 
-        constants.push((step_a_constants, step_b_constants));
-    }
-    constants
+fn shake_round_constants<F: Field>(
+    seed: &str,
+    width: usize,
+    num_rounds: usize,
+) -> Vec<(Vec<F>, Vec<F>)> {
+    shake_field_elements::<F>(seed, 2 * width * num_rounds)
+        .chunks(2 * width)
+        .map(|constants| (constants[..width].to_vec(), constants[width..].to_vec()))
+        .collect()
+}
+
+/// Reads `count` field elements from SHAKE256 seeded with `seed`. Each element is read from the
+/// next `ceil(log2(p) / 8) + 1` bytes of output, as a little-endian integer reduced mod `p`.
+pub(crate) fn shake_field_elements<F: Field>(seed: &str, count: usize) -> Vec<F> {
+    let order = field_order::<F>();
+    let mut shake = Shake256::new(seed.as_bytes());
+    let mut bytes = vec![0; ceil_div_usize(F::BITS, 8) + 1];
+    (0..count)
+        .map(|_| {
+            shake.squeeze(&mut bytes);
+            biguint_to_field(BigUint::from_bytes_le(&bytes) % &order)
+        })
+        .collect()
+}
+
+fn field_order<F: Field>() -> BigUint {
+    field_to_biguint(F::NEG_ONE) + BigUint::one()
 }
 
 pub(crate) fn recommended_rounds<F: Field>(width: usize, security_bits: usize) -> usize {
     ceil_div_usize(security_bits, 2 * width).max(10)
 }
+
+/// Returns the Rescue-Prime round count. We find the smallest `l` for which a Gröbner basis attack
+/// on `l` rounds exceeds the security level, then take `ceil(1.5 * max(5, l))`.
+pub(crate) fn rescue_prime_rounds<F: Field>(
+    width: usize,
+    capacity: usize,
+    security_bits: usize,
+) -> usize {
+    let rate = width - capacity;
+    let alpha = field_to_biguint(F::ALPHA)
+        .to_usize()
+        .expect("alpha is too large");
+    let target = BigUint::one() << security_bits;
+
+    let mut l = 1;
+    while l < 24 {
+        let degree = (alpha - 1) * width * (l - 1) / 2 + 2;
+        let num_variables = width * (l - 1) + rate;
+        let cost = binomial(
+            BigUint::from(num_variables + degree),
+            BigUint::from(num_variables),
+        );
+        if cost.pow(2) > target {
+            break;
+        }
+        l += 1;
+    }
+    ceil_div_usize(3 * l.max(5), 2)
+}
+
+/// Returns the Rescue-Prime MDS matrix: the transpose of the right half of the systematic form of
+/// the `m x 2m` Vandermonde matrix `V[i][j] = g^(i j)`.
+///
+/// The reference implementation uses the smallest primitive element for `g`. We use
+/// `F::MULTIPLICATIVE_SUBGROUP_GENERATOR`, which is the smallest primitive element of the Tweedle
+/// fields.
+fn rescue_prime_mds_matrix<F: Field>(width: usize) -> Vec<Vec<F>> {
+    let g = F::MULTIPLICATIVE_SUBGROUP_GENERATOR;
+    let mut rows: Vec<Vec<F>> = (0..width)
+        .map(|i| (0..2 * width).map(|j| g.exp_usize(i * j)).collect())
+        .collect();
+
+    // Gauss-Jordan elimination. The left half is an invertible Vandermonde matrix, so each column
+    // has a pivot.
+    for c in 0..width {
+        let pivot = (c..width)
+            .find(|&r| rows[r][c].is_nonzero())
+            .expect("Vandermonde matrix should be invertible");
+        rows.swap(c, pivot);
+        let pivot_inv = rows[c][c].multiplicative_inverse().unwrap();
+        rows[c] = rows[c].iter().map(|&x| x * pivot_inv).collect();
+        for r in 0..width {
+            if r != c && rows[r][c].is_nonzero() {
+                let factor = rows[r][c];
+                rows[r] = rows[r]
+                    .iter()
+                    .zip(rows[c].iter())
+                    .map(|(&x, &y)| x - factor * y)
+                    .collect();
+            }
+        }
+    }
+
+    (0..width)
+        .map(|i| (0..width).map(|j| rows[j][width + i]).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use crate::rescue::{alpha_inverse, generate_rescue_constants, generate_rescue_prime_constants, rescue_prime_rounds};
+    use crate::{biguint_to_field, rescue_permutation, Field, RescueParams, TweedledeeBase};
+
+    type F = TweedledeeBase;
+
+    fn from_decimal(s: &str) -> F {
+        biguint_to_field(s.parse::<BigUint>().unwrap())
+    }
+
+    // The test vectors below come from an independent Python implementation of the instance
+    // generator, using `hashlib.shake_256`.
+
+    #[test]
+    fn test_rescue_constants() {
+        let constants = generate_rescue_constants::<F>(4, 128);
+        assert_eq!(constants.len(), 16);
+        assert_eq!(
+            constants[0].0[0],
+            from_decimal(
+                "7703250534539559469272463689878442288634049774937075853109096460881980910034"
+            )
+        );
+        assert_eq!(
+            constants[0].0[1],
+            from_decimal(
+                "6630956915076602919438330857038641316785793978415225056064953717926146708903"
+            )
+        );
+        assert_eq!(
+            constants[15].1[3],
+            from_decimal(
+                "12522464438880353614147057152172300556764606988314097912415757807193678820325"
+            )
+        );
+    }
+
+    #[test]
+    fn test_rescue_permutation() {
+        let state: Vec<F> = (0..4).map(F::from_canonical_usize).collect();
+        let expected = vec![
+            "8693554893701926287215578145808477341214093893809790549283009395302604431956",
+            "12427790736219277887441207202376552348912751454512050907427981672258101574453",
+            "10144358770737251118687113766209808243219349138408841120675755139040881584384",
+            "1750753011218870281490026951312902517240405913647694811826068728523398322704",
+        ]
+        .into_iter()
+        .map(from_decimal)
+        .collect::<Vec<_>>();
+        assert_eq!(RescueParams::new(4, 128).permute(&state), expected);
+        assert_eq!(rescue_permutation(&state, 128), expected);
+    }
+
+    #[test]
+    fn test_rescue_prime_rounds() {
+        let rounds_128: Vec<usize> = [3, 4, 8, 12]
+            .iter()
+            .map(|&width| rescue_prime_rounds::<F>(width, 1, 128))
+            .collect();
+        assert_eq!(rounds_128, vec![14, 11, 8, 8]);
+
+        let rounds_256: Vec<usize> = [3, 4, 8, 12]
+            .iter()
+            .map(|&width| rescue_prime_rounds::<F>(width, 1, 256))
+            .collect();
+        assert_eq!(rounds_256, vec![26, 20, 11, 8]);
+    }
+
+    #[test]
+    fn test_rescue_prime_constants() {
+        let constants = generate_rescue_prime_constants::<F>(4, 1, 128);
+        assert_eq!(constants.len(), 11);
+        assert_eq!(
+            constants[0].0[0],
+            from_decimal(
+                "17551239179151817920396141828082776557850792752545733993766609639586804080436"
+            )
+        );
+        assert_eq!(
+            constants[0].0[1],
+            from_decimal(
+                "21272209755743460821359537514603264510379766933800876655780161100543601119946"
+            )
+        );
+        assert_eq!(
+            constants[10].1[3],
+            from_decimal(
+                "13885387604017804018319462119658163809458735642745294883461166855135990937329"
+            )
+        );
+    }
+
+    #[test]
+    fn test_rescue_prime_mds() {
+        let params = RescueParams::<F>::rescue_prime(4, 1, 128);
+        assert_eq!(params.mds()[0][0], -F::from_canonical_usize(15625));
+        assert_eq!(params.mds()[0][1], F::from_canonical_usize(19500));
+        assert_eq!(params.mds()[3][3], F::from_canonical_usize(320327931));
+    }
+
+    #[test]
+    fn test_rescue_prime_permutation() {
+        let state: Vec<F> = (0..4).map(F::from_canonical_usize).collect();
+        let expected = vec![
+            "11881565162300596464434787606306639055298524749474153106015452426463856715566",
+            "508497623230919404071956053126058737392147178654016406676640618256383220893",
+            "7542653260059019033350664020039460568575831522222715495619655956126450797302",
+            "459427933227104440221725962231589323634137627088952791153703014156483619657",
+        ]
+        .into_iter()
+        .map(from_decimal)
+        .collect::<Vec<_>>();
+        assert_eq!(
+            RescueParams::rescue_prime(4, 1, 128).permute(&state),
+            expected
+        );
+
+        let state: Vec<F> = (0..8).map(F::from_canonical_usize).collect();
+        let expected = vec![
+            "18133750727726985555267970967061688764384906700465228368220125873243301965197",
+            "12038116741309651150148385420494361828956952015669356254509346829700547808932",
+            "4954467782895185843591460600055515804532435854626296753269950612587486190262",
+            "2980684068389346270001053022597194454704704944921760631045960424977660301096",
+            "1802975197014999012071851667585926399261608213776610725661459983256893594749",
+            "16337671069868657085797061121909490998943706678432300493012951817908479653193",
+            "26377943737479807104225609815029136344192124809441959143000138096851043205907",
+            "15670348792768540402796970101224969343430232457024165701169593889886772732686",
+        ]
+        .into_iter()
+        .map(from_decimal)
+        .collect::<Vec<_>>();
+        assert_eq!(
+            RescueParams::rescue_prime(8, 1, 128).permute(&state),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_sponge_rate() {
+        let params = RescueParams::<F>::rescue_prime(12, 2, 128);
+        assert_eq!(params.rate(), 10);
+
+        // Ten inputs should be absorbed by a single permutation.
+        let inputs: Vec<F> = (0..10).map(|_| F::rand()).collect();
+        let mut state = inputs.clone();
+        state.extend(vec![F::ZERO; 2]);
+        assert_eq!(
+            params.sponge(&inputs, 10),
+            params.permute(&state)[..10].to_vec()
+        );
+    }
+
+    #[test]
+    fn test_alpha_inverse() {
+        let x = F::rand();
+        assert_eq!(x.exp(alpha_inverse::<F>()).exp(F::ALPHA), x);
+        assert_eq!(x.exp(alpha_inverse::<F>()), x.kth_root(F::ALPHA));
+    }
+}
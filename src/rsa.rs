@@ -0,0 +1,143 @@
+use num::BigUint;
+
+use crate::util::ceil_div_usize;
+
+/// The DER encoding of the `DigestInfo` prefix for SHA-256, as given in RFC 8017, section 9.2.
+pub(crate) const SHA256_DIGEST_INFO_PREFIX: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// An RSA public key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RsaPublicKey {
+    pub modulus: BigUint,
+    pub exponent: BigUint,
+}
+
+impl RsaPublicKey {
+    /// The length of the modulus in bytes.
+    pub fn modulus_len(&self) -> usize {
+        ceil_div_usize(self.modulus.bits() as usize, 8)
+    }
+}
+
+/// Returns the PKCS#1 v1.5 encoding of a SHA-256 digest, i.e.
+/// `0x00 || 0x01 || 0xff..0xff || 0x00 || DigestInfo`, as an integer. Panics if `modulus_len` is
+/// too small to fit the encoding.
+pub fn rsa_pkcs1v15_sha256_encode(digest: &[u8; 32], modulus_len: usize) -> BigUint {
+    let digest_info_len = SHA256_DIGEST_INFO_PREFIX.len() + digest.len();
+    assert!(
+        modulus_len >= digest_info_len + 11,
+        "Modulus too short for a PKCS#1 v1.5 SHA-256 encoding"
+    );
+
+    let mut encoded = vec![0x00, 0x01];
+    encoded.resize(modulus_len - digest_info_len - 1, 0xff);
+    encoded.push(0x00);
+    encoded.extend_from_slice(&SHA256_DIGEST_INFO_PREFIX);
+    encoded.extend_from_slice(digest);
+    BigUint::from_bytes_be(&encoded)
+}
+
+/// Signs a SHA-256 digest with PKCS#1 v1.5 padding, given the private exponent.
+pub fn rsa_sign_pkcs1v15_sha256(
+    public_key: &RsaPublicKey,
+    private_exponent: &BigUint,
+    digest: &[u8; 32],
+) -> BigUint {
+    let encoded = rsa_pkcs1v15_sha256_encode(digest, public_key.modulus_len());
+    encoded.modpow(private_exponent, &public_key.modulus)
+}
+
+/// Verifies a PKCS#1 v1.5 signature of a SHA-256 digest.
+pub fn rsa_verify_pkcs1v15_sha256(
+    public_key: &RsaPublicKey,
+    digest: &[u8; 32],
+    signature: &BigUint,
+) -> bool {
+    if signature >= &public_key.modulus {
+        return false;
+    }
+    let encoded = rsa_pkcs1v15_sha256_encode(digest, public_key.modulus_len());
+    signature.modpow(&public_key.exponent, &public_key.modulus) == encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use crate::{rsa_sign_pkcs1v15_sha256, rsa_verify_pkcs1v15_sha256, RsaPublicKey};
+
+    /// A 512-bit test key with `e = 65537`. Far too small for real use, but it is the smallest
+    /// size which fits a PKCS#1 v1.5 SHA-256 encoding.
+    fn test_key() -> (RsaPublicKey, BigUint) {
+        let modulus = BigUint::parse_bytes(
+            b"bf683d29b26dc57a178da5a41f424fac9eeba5d8ec341a6a47721f8b2878f0fb\
+              271d4d0753c11b4f445e1512d520a07b852837fdef7fc44c99406d04598f679b",
+            16,
+        )
+        .unwrap();
+        let private_exponent = BigUint::parse_bytes(
+            b"ab9bc3aa8cc50a568aa37ead0531a8c975ba84f955dce909fc807f737e97e045\
+              5cde02fd66493909d38020d73e64f880cf13efafb97a48d159d394a27854a221",
+            16,
+        )
+        .unwrap();
+        let public_key = RsaPublicKey {
+            modulus,
+            exponent: BigUint::from(65537u32),
+        };
+        (public_key, private_exponent)
+    }
+
+    /// SHA-256("abc").
+    const TEST_DIGEST: [u8; 32] = [
+        0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22,
+        0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00,
+        0x15, 0xad,
+    ];
+
+    #[test]
+    fn test_rsa_known_signature() {
+        let (public_key, private_exponent) = test_key();
+        let expected_signature = BigUint::parse_bytes(
+            b"41785fe666825046b819358093c36159039e0a1b9dced75b13c94fce8d63d50e\
+              972637a3a1c4dd34a7b8c2140ec778e6c2dcde6292ddccf9209315e0de5f8a9f",
+            16,
+        )
+        .unwrap();
+
+        let signature = rsa_sign_pkcs1v15_sha256(&public_key, &private_exponent, &TEST_DIGEST);
+        assert_eq!(signature, expected_signature);
+        assert!(rsa_verify_pkcs1v15_sha256(
+            &public_key,
+            &TEST_DIGEST,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn test_rsa_rejects_bad_signatures() {
+        let (public_key, private_exponent) = test_key();
+        let signature = rsa_sign_pkcs1v15_sha256(&public_key, &private_exponent, &TEST_DIGEST);
+
+        let mut other_digest = TEST_DIGEST;
+        other_digest[0] ^= 1;
+        assert!(!rsa_verify_pkcs1v15_sha256(
+            &public_key,
+            &other_digest,
+            &signature
+        ));
+        assert!(!rsa_verify_pkcs1v15_sha256(
+            &public_key,
+            &TEST_DIGEST,
+            &(&signature + BigUint::from(1u32))
+        ));
+        assert!(!rsa_verify_pkcs1v15_sha256(
+            &public_key,
+            &TEST_DIGEST,
+            &(&signature + &public_key.modulus)
+        ));
+    }
+}
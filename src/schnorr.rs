@@ -0,0 +1,138 @@
+use crate::{rescue_hash_n_to_1, AffinePoint, Curve, Field};
+
+/// A Schnorr signature over the curve `C`. The challenge is a Rescue hash over the base field of
+/// `C`, so a signature can be verified cheaply in a circuit whose native field is that base field.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SchnorrSignature<C: Curve> {
+    pub r: AffinePoint<C>,
+    pub s: C::ScalarField,
+}
+
+/// Computes the public key `[secret_key] G` associated with a secret key.
+pub fn schnorr_public_key<C: Curve>(secret_key: C::ScalarField) -> AffinePoint<C> {
+    (C::convert(secret_key) * C::GENERATOR_PROJECTIVE).to_affine()
+}
+
+/// Computes the challenge `H(R, PK, message)`, where `H` is a Rescue hash over the base field.
+pub fn schnorr_challenge<C: Curve>(
+    r: AffinePoint<C>,
+    public_key: AffinePoint<C>,
+    message: &[C::BaseField],
+    security_bits: usize,
+) -> C::BaseField {
+    let mut inputs = vec![r.x, r.y, public_key.x, public_key.y];
+    inputs.extend_from_slice(message);
+    rescue_hash_n_to_1(inputs, security_bits)
+}
+
+/// Our in-circuit MSM assumes that the most significant bit of each scalar is unset, so we only
+/// accept challenges and responses below `2^(BITS - 1)`, where `BITS` is the size of the base
+/// field. Since the Tweedle fields are slightly larger than `2^254`, this rarely rejects anything.
+fn fits_in_msm<C: Curve, F: Field>(x: F) -> bool {
+    x.num_bits() < C::BaseField::BITS
+}
+
+/// Interprets a challenge as a scalar, or returns `None` if it is out of range.
+fn challenge_to_scalar<C: Curve>(challenge: C::BaseField) -> Option<C::ScalarField> {
+    if !fits_in_msm::<C, _>(challenge) {
+        return None;
+    }
+    C::try_convert_b2s(challenge).ok()
+}
+
+/// Signs a message using the given nonce. Returns `None` if the nonce yields a signature which our
+/// verifier would reject, in which case the caller should retry with a fresh nonce.
+pub fn schnorr_sign_with_nonce<C: Curve>(
+    message: &[C::BaseField],
+    secret_key: C::ScalarField,
+    nonce: C::ScalarField,
+    security_bits: usize,
+) -> Option<SchnorrSignature<C>> {
+    if nonce.is_zero() {
+        return None;
+    }
+    let r = schnorr_public_key::<C>(nonce);
+    let public_key = schnorr_public_key::<C>(secret_key);
+    let challenge = schnorr_challenge(r, public_key, message, security_bits);
+    let e = challenge_to_scalar::<C>(challenge)?;
+    let s = nonce + e * secret_key;
+    if !fits_in_msm::<C, _>(s) {
+        return None;
+    }
+    Some(SchnorrSignature { r, s })
+}
+
+/// Signs a message with a random nonce.
+pub fn schnorr_sign<C: Curve>(
+    message: &[C::BaseField],
+    secret_key: C::ScalarField,
+    security_bits: usize,
+) -> SchnorrSignature<C> {
+    loop {
+        let nonce = C::ScalarField::rand();
+        if let Some(sig) = schnorr_sign_with_nonce(message, secret_key, nonce, security_bits) {
+            return sig;
+        }
+    }
+}
+
+/// Verifies a Schnorr signature of a message against a public key, by checking that
+/// `[s] G = R + [e] PK`.
+pub fn schnorr_verify<C: Curve>(
+    message: &[C::BaseField],
+    sig: SchnorrSignature<C>,
+    public_key: AffinePoint<C>,
+    security_bits: usize,
+) -> bool {
+    if public_key.zero || !public_key.is_valid() || sig.r.zero || !sig.r.is_valid() {
+        return false;
+    }
+    if !fits_in_msm::<C, _>(sig.s) {
+        return false;
+    }
+    let challenge = schnorr_challenge(sig.r, public_key, message, security_bits);
+    let e = match challenge_to_scalar::<C>(challenge) {
+        Some(e) => e,
+        None => return false,
+    };
+    let lhs = C::convert(sig.s) * C::GENERATOR_PROJECTIVE;
+    let rhs = sig.r.to_projective() + C::convert(e) * public_key.to_projective();
+    lhs == rhs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{schnorr_public_key, schnorr_sign, schnorr_sign_with_nonce, schnorr_verify, Curve, Field, Tweedledum};
+
+    type C = Tweedledum;
+    type BF = <C as Curve>::BaseField;
+    type SF = <C as Curve>::ScalarField;
+
+    #[test]
+    fn test_schnorr_sign_and_verify() {
+        let secret_key = SF::rand();
+        let public_key = schnorr_public_key::<C>(secret_key);
+        let message = vec![BF::rand(), BF::rand(), BF::rand()];
+        let sig = schnorr_sign::<C>(&message, secret_key, 128);
+        assert!(schnorr_verify(&message, sig, public_key, 128));
+
+        // A different message, a tampered signature or a different key must all be rejected.
+        let mut other_message = message.clone();
+        other_message[2] = other_message[2] + BF::ONE;
+        assert!(!schnorr_verify(&other_message, sig, public_key, 128));
+        let mut bad_sig = sig;
+        bad_sig.s = bad_sig.s + SF::ONE;
+        assert!(!schnorr_verify(&message, bad_sig, public_key, 128));
+        let other_key = schnorr_public_key::<C>(SF::rand());
+        assert!(!schnorr_verify(&message, sig, other_key, 128));
+    }
+
+    #[test]
+    fn test_schnorr_zero_nonce() {
+        let secret_key = SF::rand();
+        assert_eq!(
+            schnorr_sign_with_nonce::<C>(&[BF::ONE], secret_key, SF::ZERO, 128),
+            None
+        );
+    }
+}
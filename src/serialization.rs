@@ -1,4 +1,4 @@
-use crate::{AffinePoint, Curve, Field, TweedledumBase, Bls12377Base, Bls12377Scalar, TweedledeeBase, PallasBase, VestaBase};
+use crate::{AffinePoint, Curve, Field};
 use serde::de::Error as DeError;
 use serde::de::Visitor;
 use serde::ser::Error as SerdeError;
@@ -109,36 +109,38 @@ impl<'de, C: Curve> Deserialize<'de> for AffinePoint<C> {
     }
 }
 
+/// Implements serde's `Serialize` and `Deserialize` for a field, using its canonical byte encoding.
+#[macro_export]
 macro_rules! impl_serde_field {
     ($field:ty) => {
-        impl Serialize for $field {
+        impl serde::Serialize for $field {
             fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
             where
-                S: Serializer,
+                S: serde::Serializer,
             {
                 let mut buf = vec![];
-                self.write(&mut buf)
-                    .map_err(|e| S::Error::custom(format!("{}", e)))?;
+                $crate::ToBytes::write(self, &mut buf)
+                    .map_err(|e| <S::Error as serde::ser::Error>::custom(format!("{}", e)))?;
                 serializer.serialize_bytes(&buf)
             }
         }
 
-        impl<'de> Deserialize<'de> for $field {
+        impl<'de> serde::Deserialize<'de> for $field {
             fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
             where
-                D: Deserializer<'de>,
+                D: serde::Deserializer<'de>,
             {
                 struct FieldVisitor {}
 
-                impl<'de> Visitor<'de> for FieldVisitor {
+                impl<'de> serde::de::Visitor<'de> for FieldVisitor {
                     type Value = $field;
 
-                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
                         write!(formatter, "A field element.")
                     }
 
-                    fn visit_bytes<E: DeError>(self, v: &[u8]) -> std::result::Result<Self::Value, E> {
-                        <$field>::read(v).map_err(|e| DeError::custom(format!("{}", e)))
+                    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> std::result::Result<Self::Value, E> {
+                        <$field as $crate::FromBytes>::read(v).map_err(|e| E::custom(format!("{}", e)))
                     }
                 }
                 deserializer.deserialize_bytes(FieldVisitor {})
@@ -147,18 +149,13 @@ macro_rules! impl_serde_field {
     };
 }
 
-impl_serde_field!(TweedledumBase);
-impl_serde_field!(TweedledeeBase);
-impl_serde_field!(Bls12377Base);
-impl_serde_field!(Bls12377Scalar);
-impl_serde_field!(PallasBase);
-impl_serde_field!(VestaBase);
-
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::{blake_hash_base_field_to_curve, CircuitBuilder, HaloCurve, PartialWitness, Proof, VerificationKey};
     use crate::{Bls12377, Bls12377Base, Bls12377Scalar};
+    use crate::{Bn254, Bn254Base, Bn254Scalar, Grumpkin};
+    use crate::{Secp256k1, Secp256k1Base, Secp256k1Scalar};
     use crate::{Tweedledee, TweedledeeBase, Tweedledum, TweedledumBase};
     use crate::{Pallas, PallasBase, Vesta, VestaBase};
     use anyhow::Result;
@@ -224,6 +221,10 @@ mod test {
     test_field_serialization!(TweedledumBase, test_tweedledum_base_serialization);
     test_field_serialization!(Bls12377Base, test_bls_base_serialization);
     test_field_serialization!(Bls12377Scalar, test_bls_scalar_serialization);
+    test_field_serialization!(Bn254Base, test_bn254_base_serialization);
+    test_field_serialization!(Bn254Scalar, test_bn254_scalar_serialization);
+    test_field_serialization!(Secp256k1Base, test_secp256k1_base_serialization);
+    test_field_serialization!(Secp256k1Scalar, test_secp256k1_scalar_serialization);
     test_curve_serialization!(
         Pallas,
         <Pallas as Curve>::BaseField,
@@ -249,6 +250,21 @@ mod test {
         <Bls12377 as Curve>::BaseField,
         test_bls_curve_serialization
     );
+    test_curve_serialization!(
+        Bn254,
+        <Bn254 as Curve>::BaseField,
+        test_bn254_curve_serialization
+    );
+    test_curve_serialization!(
+        Grumpkin,
+        <Grumpkin as Curve>::BaseField,
+        test_grumpkin_curve_serialization
+    );
+    test_curve_serialization!(
+        Secp256k1,
+        <Secp256k1 as Curve>::BaseField,
+        test_secp256k1_curve_serialization
+    );
 
     // Generate a proof and verification key for the factorial circuit.
     fn get_circuit_vk<C: HaloCurve, InnerC: HaloCurve<BaseField=C::ScalarField>>() -> (Proof<C>, VerificationKey<C>) {
@@ -326,4 +342,5 @@ mod test {
     test_proof_vk_serialization!(Tweedledum, Tweedledee, test_proof_vk_serialization_tweedledum);
     test_proof_vk_serialization!(Pallas, Vesta, test_proof_vk_serialization_pallas);
     test_proof_vk_serialization!(Vesta, Pallas, test_proof_vk_serialization_vesta);
+    test_proof_vk_serialization!(Bn254, Grumpkin, test_proof_vk_serialization_bn254);
 }
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{poseidon_permutation, CircuitBuilder, Field, HaloCurve, PoseidonParams, RescueParams, Target, POSEIDON_SPONGE_RATE, POSEIDON_SPONGE_WIDTH, RESCUE_SPONGE_RATE, RESCUE_SPONGE_WIDTH};
+
+/// Identifies the hash used for Fiat-Shamir challenges, so that it can be recorded in a
+/// `VerificationKey`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TranscriptHashType {
+    #[default]
+    Rescue,
+    Poseidon,
+    Blake3,
+}
+
+/// A permutation (or permutation-like function) used to build the duplex sponge behind
+/// `Challenger`. An instance holds anything which is expensive to derive, such as round constants,
+/// so that it only needs to be derived once per transcript.
+pub trait TranscriptHash<F: Field>: Clone {
+    const TYPE: TranscriptHashType;
+
+    /// The number of field elements in the sponge state.
+    const WIDTH: usize;
+
+    /// The number of state elements which inputs are added to and outputs are read from.
+    const RATE: usize;
+
+    fn new(security_bits: usize) -> Self;
+
+    fn permute(&self, state: &[F]) -> Vec<F>;
+}
+
+/// A `TranscriptHash` which can also be evaluated in a circuit, so that proofs using it can be
+/// verified recursively.
+pub trait RecursiveTranscriptHash<F: Field>: TranscriptHash<F> {
+    fn permute_recursively<C: HaloCurve<ScalarField = F>>(
+        builder: &mut CircuitBuilder<C>,
+        state: &[Target<F>],
+    ) -> Vec<Target<F>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct RescueTranscriptHash<F: Field> {
+    params: RescueParams<F>,
+}
+
+impl<F: Field> TranscriptHash<F> for RescueTranscriptHash<F> {
+    const TYPE: TranscriptHashType = TranscriptHashType::Rescue;
+    const WIDTH: usize = RESCUE_SPONGE_WIDTH;
+    const RATE: usize = RESCUE_SPONGE_RATE;
+
+    fn new(security_bits: usize) -> Self {
+        RescueTranscriptHash {
+            params: RescueParams::new(RESCUE_SPONGE_WIDTH, security_bits),
+        }
+    }
+
+    fn permute(&self, state: &[F]) -> Vec<F> {
+        self.params.permute(state)
+    }
+}
+
+impl<F: Field> RecursiveTranscriptHash<F> for RescueTranscriptHash<F> {
+    fn permute_recursively<C: HaloCurve<ScalarField = F>>(
+        builder: &mut CircuitBuilder<C>,
+        state: &[Target<F>],
+    ) -> Vec<Target<F>> {
+        builder.rescue_permutation(state)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PoseidonTranscriptHash<F: Field> {
+    params: PoseidonParams<F>,
+}
+
+impl<F: Field> TranscriptHash<F> for PoseidonTranscriptHash<F> {
+    const TYPE: TranscriptHashType = TranscriptHashType::Poseidon;
+    const WIDTH: usize = POSEIDON_SPONGE_WIDTH;
+    const RATE: usize = POSEIDON_SPONGE_RATE;
+
+    fn new(security_bits: usize) -> Self {
+        PoseidonTranscriptHash {
+            params: PoseidonParams::new(POSEIDON_SPONGE_WIDTH, security_bits),
+        }
+    }
+
+    fn permute(&self, state: &[F]) -> Vec<F> {
+        poseidon_permutation(&self.params, state)
+    }
+}
+
+impl<F: Field> RecursiveTranscriptHash<F> for PoseidonTranscriptHash<F> {
+    fn permute_recursively<C: HaloCurve<ScalarField = F>>(
+        builder: &mut CircuitBuilder<C>,
+        state: &[Target<F>],
+    ) -> Vec<Target<F>> {
+        let params = builder.poseidon_params.clone();
+        builder.poseidon_permutation(&params, state)
+    }
+}
+
+/// A transcript hash based on Blake3. It is much faster than the algebraic hashes natively, but
+/// would be very expensive to verify recursively, so it is meant for proofs which are only ever
+/// verified natively.
+///
+/// Rather than a permutation, we hash the whole state and expand the digest into a new state. Since
+/// Blake3 processes 64-byte blocks, a wider state is cheap, so we absorb 8 elements at a time.
+#[derive(Debug, Copy, Clone)]
+pub struct Blake3TranscriptHash;
+
+impl<F: Field> TranscriptHash<F> for Blake3TranscriptHash {
+    const TYPE: TranscriptHashType = TranscriptHashType::Blake3;
+    const WIDTH: usize = 9;
+    const RATE: usize = 8;
+
+    fn new(_security_bits: usize) -> Self {
+        Blake3TranscriptHash
+    }
+
+    fn permute(&self, state: &[F]) -> Vec<F> {
+        let mut hasher = blake3::Hasher::new();
+        for element in state {
+            hasher.update(&element.to_canonical_u8_vec());
+        }
+        let mut output_reader = hasher.finalize_xof();
+        (0..state.len())
+            .map(|_| sample_field_element(&mut output_reader))
+            .collect()
+    }
+}
+
+/// Samples a uniformly random field element from a Blake3 extended output, by rejection, reading
+/// more of the output as needed.
+pub(crate) fn sample_field_element<F: Field>(output_reader: &mut blake3::OutputReader) -> F {
+    let mut bytes = vec![0; F::BYTES];
+    loop {
+        output_reader.fill(&mut bytes);
+        bytes[F::BYTES - 1] >>= 8 * F::BYTES - F::BITS;
+        if let Ok(element) = F::from_canonical_u8_vec(bytes.clone()) {
+            return element;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Blake3TranscriptHash, Field, TranscriptHash, TweedledeeBase};
+
+    #[test]
+    fn test_blake3_permute() {
+        type F = TweedledeeBase;
+        let width = <Blake3TranscriptHash as TranscriptHash<F>>::WIDTH;
+        let hash = Blake3TranscriptHash;
+        let state: Vec<F> = (0..width).map(|_| F::rand()).collect();
+        let output = hash.permute(&state);
+        assert_eq!(output.len(), width);
+        assert_eq!(output, hash.permute(&state));
+
+        let mut tweaked_state = state.clone();
+        tweaked_state[width - 1] = F::ZERO;
+        assert_ne!(output, hash.permute(&tweaked_state));
+    }
+}
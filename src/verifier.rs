@@ -7,8 +7,9 @@ use crate::gates::evaluate_all_constraints;
 use crate::halo::verify_ipa;
 use crate::plonk_proof::OldProof;
 use crate::plonk_util::{halo_g, halo_n, halo_n_mul, halo_s, pedersen_hash, powers, reduce_with_powers};
+use crate::transcript_hash::sample_field_element;
 use crate::util::{ceil_div_usize, log2_strict};
-use crate::{blake_hash_usize_to_curve, fft_precompute, msm_execute_parallel, msm_precompute, AffinePoint, Circuit, FftPrecomputation, Field, HaloCurve, MsmPrecomputation, Polynomial, Proof, GRID_WIDTH, NUM_ROUTED_WIRES, NUM_WIRES};
+use crate::{blake_hash_usize_to_curve, fft_precompute, msm_execute_parallel, msm_precompute, AffinePoint, Blake3TranscriptHash, Circuit, FftPrecomputation, Field, HaloCurve, MsmPrecomputation, Polynomial, PoseidonTranscriptHash, Proof, RescueTranscriptHash, TranscriptHashType, GRID_WIDTH, NUM_ROUTED_WIRES, NUM_WIRES};
 
 pub const SECURITY_BITS: usize = 128;
 
@@ -20,6 +21,10 @@ pub struct VerificationKey<C: HaloCurve> {
     pub num_public_inputs: usize,
     pub num_gates_without_pis: usize,
     pub security_bits: usize,
+    /// The hash used to generate Fiat-Shamir challenges. Keys serialized before this was
+    /// configurable used Rescue.
+    #[serde(default)]
+    pub transcript_hash: TranscriptHashType,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pedersen_g_msm_precomputation: Option<MsmPrecomputation<C>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -43,6 +48,52 @@ impl<C: HaloCurve> VerificationKey<C> {
         self.clear_fft_precomputation();
         self.clear_msm_precomputation();
     }
+
+    /// A digest of everything which identifies the circuit. It is the first message of every proof
+    /// transcript, so a proof for one circuit can't be verified against another.
+    pub fn digest(&self) -> C::BaseField {
+        verification_key_digest(
+            &self.c_constants,
+            &self.c_s_sigmas,
+            self.degree,
+            self.num_public_inputs,
+            self.num_gates_without_pis,
+            self.security_bits,
+            self.transcript_hash,
+        )
+    }
+}
+
+/// Hashes the fields of a `VerificationKey` with Blake3, and samples a base field element from the
+/// output.
+pub(crate) fn verification_key_digest<C: HaloCurve>(
+    c_constants: &[AffinePoint<C>],
+    c_s_sigmas: &[AffinePoint<C>],
+    degree: usize,
+    num_public_inputs: usize,
+    num_gates_without_pis: usize,
+    security_bits: usize,
+    transcript_hash: TranscriptHashType,
+) -> C::BaseField {
+    let mut hasher = blake3::Hasher::new();
+    let sizes = [
+        c_constants.len(),
+        c_s_sigmas.len(),
+        degree,
+        num_public_inputs,
+        num_gates_without_pis,
+        security_bits,
+    ];
+    for &size in &sizes {
+        hasher.update(&(size as u64).to_le_bytes());
+    }
+    hasher.update(&[transcript_hash as u8]);
+    for point in c_constants.iter().chain(c_s_sigmas) {
+        hasher.update(&[point.zero as u8]);
+        hasher.update(&point.x.to_canonical_u8_vec());
+        hasher.update(&point.y.to_canonical_u8_vec());
+    }
+    sample_field_element(&mut hasher.finalize_xof())
 }
 
 /// Verifies a proof `proof` and some old proofs G points for a given verification key.
@@ -60,7 +111,17 @@ pub fn verify_proof<C: HaloCurve, InnerC: HaloCurve<BaseField = C::ScalarField>>
     check_proof_parameters(proof)?;
 
     // Observe the transcript and generate the associated challenge points using Fiat-Shamir.
-    let challs = proof.get_challenges(public_inputs, old_proofs)?;
+    let challs = match vk.transcript_hash {
+        TranscriptHashType::Rescue => {
+            proof.get_challenges::<RescueTranscriptHash<_>>(vk, public_inputs, old_proofs)?
+        }
+        TranscriptHashType::Poseidon => {
+            proof.get_challenges::<PoseidonTranscriptHash<_>>(vk, public_inputs, old_proofs)?
+        }
+        TranscriptHashType::Blake3 => {
+            proof.get_challenges::<Blake3TranscriptHash>(vk, public_inputs, old_proofs)?
+        }
+    };
 
     // Check the old proofs' openings.
     verify_old_proof_evaluation(old_proofs, &proof, challs.zeta)?;
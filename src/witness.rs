@@ -1,5 +1,5 @@
 use crate::util::transpose;
-use crate::{biguint_to_field, biguint_to_limbs, field_to_biguint, AffinePoint, AffinePointTarget, BigIntTarget, Curve, Field, ForeignFieldTarget, OrderingTarget, Target, Wire, LIMB_BITS, NUM_WIRES, NUM_ADVICE_WIRES, NUM_ROUTED_WIRES};
+use crate::{biguint_to_field, biguint_to_limbs, field_to_biguint, AffinePoint, AffinePointTarget, BigIntTarget, BoolTarget, Curve, Field, ForeignAffinePointTarget, ForeignFieldTarget, OrderingTarget, Target, UintTarget, U8Target, U32Target, Wire, NUM_WIRES, NUM_ADVICE_WIRES, NUM_ROUTED_WIRES};
 use num::{BigUint, Zero};
 use std::{cmp::Ordering, collections::HashMap};
 
@@ -58,6 +58,30 @@ impl<F: Field> PartialWitness<F> {
         AffinePoint::nonzero(x, y)
     }
 
+    pub fn get_bool_target(&self, target: BoolTarget<F>) -> bool {
+        let value = self.get_target(target.target());
+        if value == F::ZERO {
+            false
+        } else if value == F::ONE {
+            true
+        } else {
+            panic!("Boolean target has a non-binary value")
+        }
+    }
+
+    pub fn get_uint_target<const BITS: usize>(&self, target: UintTarget<F, BITS>) -> u64 {
+        debug_assert!(BITS <= 64);
+        self.get_target(target.target()).to_canonical_u64_vec()[0]
+    }
+
+    pub fn get_u8_target(&self, target: U8Target<F>) -> u8 {
+        self.get_uint_target(target) as u8
+    }
+
+    pub fn get_u32_target(&self, target: U32Target<F>) -> u32 {
+        self.get_uint_target(target) as u32
+    }
+
     pub fn get_ordering_target(&self, target: OrderingTarget<F>) -> Ordering {
         let OrderingTarget { lt, eq, gt } = target;
         let lt_eq_gt = &self.get_targets(&[lt, eq, gt]);
@@ -89,13 +113,13 @@ impl<F: Field> PartialWitness<F> {
         let mut result = BigUint::zero();
         for (i, &limb) in target.limbs.iter().enumerate() {
             let limb_value = field_to_biguint(self.get_target(limb));
-            result += limb_value << (i * LIMB_BITS);
+            result += limb_value << (i * target.limb_bits);
         }
         result
     }
 
     pub fn set_bigint_target(&mut self, target: &BigIntTarget<F>, value: &BigUint) {
-        let mut value_limbs = biguint_to_limbs(value);
+        let mut value_limbs = biguint_to_limbs(value, target.limb_bits);
 
         debug_assert!(
             value_limbs.len() <= target.limbs.len(),
@@ -121,6 +145,24 @@ impl<F: Field> PartialWitness<F> {
         self.set_bigint_target(&target.value, &field_to_biguint(value))
     }
 
+    pub fn get_foreign_point_target<FC: Curve>(
+        &self,
+        target: &ForeignAffinePointTarget<F, FC>,
+    ) -> AffinePoint<FC> {
+        let x = self.get_foreign_field_target(&target.x);
+        let y = self.get_foreign_field_target(&target.y);
+        AffinePoint::nonzero(x, y)
+    }
+
+    pub fn set_foreign_point_target<FC: Curve>(
+        &mut self,
+        target: &ForeignAffinePointTarget<F, FC>,
+        point: AffinePoint<FC>,
+    ) {
+        self.set_foreign_field_target(&target.x, point.x);
+        self.set_foreign_field_target(&target.y, point.y);
+    }
+
     pub fn get_wire(&self, wire: Wire) -> F {
         self.get_target(Target::Wire(wire))
     }
@@ -144,6 +186,23 @@ impl<F: Field> PartialWitness<F> {
             .for_each(|(&target, &value)| self.set_target(target, value))
     }
 
+    pub fn set_bool_target(&mut self, target: BoolTarget<F>, value: bool) {
+        self.set_target(target.target(), if value { F::ONE } else { F::ZERO });
+    }
+
+    pub fn set_uint_target<const BITS: usize>(&mut self, target: UintTarget<F, BITS>, value: u64) {
+        debug_assert!(BITS >= 64 || value >> BITS == 0);
+        self.set_target(target.target(), F::from_canonical_u64(value));
+    }
+
+    pub fn set_u8_target(&mut self, target: U8Target<F>, value: u8) {
+        self.set_uint_target(target, value as u64);
+    }
+
+    pub fn set_u32_target(&mut self, target: U32Target<F>, value: u32) {
+        self.set_uint_target(target, value as u64);
+    }
+
     pub fn set_point_target<InnerC: Curve<BaseField = F>>(
         &mut self,
         point_target: AffinePointTarget<InnerC>,
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use plonky::{blake_hash_base_field_to_curve, msm_parallel, rescue_hash_1_to_1, verify_proof, AffinePoint, Base4SumGate, Circuit, CircuitBuilder, Curve, CurveMulOp, Field, HaloCurve, PartialWitness, Target, Tweedledee, Tweedledum, Wire, Witness};
+use num::BigUint;
+use plonky::{blake_hash_base_field_to_curve, rsa_sign_pkcs1v15_sha256, ecdsa_public_key, ecdsa_sign, msm_parallel, poseidon_hash_1_to_1, rescue_hash_1_to_1, schnorr_public_key, schnorr_sign, verify_proof, AffinePoint, Base4SumGate, Bls12377, BoundedTarget, Bn254, Circuit, CircuitBuilder, Curve, CurveMulOp, Field, Grumpkin, HaloCurve, PartialWitness, PedersenGenerators, RsaPublicKey, Secp256k1, SparseMerkleTree, Target, TranscriptHashType, Tweedledee, Tweedledum, VerificationKey, Wire, Witness};
 use rand::{thread_rng, Rng};
 use std::time::Instant;
 
@@ -282,6 +283,84 @@ fn test_proof_public_input_copied() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_proof_transcript_hashes() -> Result<()> {
+    type F = <Tweedledee as Curve>::ScalarField;
+    let transcript_hashes = [
+        TranscriptHashType::Rescue,
+        TranscriptHashType::Poseidon,
+        TranscriptHashType::Blake3,
+    ];
+    for &transcript_hash in transcript_hashes.iter() {
+        let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+        builder.set_transcript_hash(transcript_hash);
+        let x = builder.add_virtual_target();
+        let x_squared = builder.square(x);
+        let c = builder.constant_wire(F::from_canonical_usize(49));
+        let should_be_zero = builder.sub(x_squared, c);
+        builder.assert_zero(should_be_zero);
+        let mut partial_witness = PartialWitness::new();
+        partial_witness.set_target(x, F::from_canonical_usize(7));
+        let circuit = builder.build();
+        let witness = circuit.generate_witness(partial_witness);
+        let proof = circuit
+            .generate_proof::<Tweedledum>(&witness, &[], true)
+            .unwrap();
+        let vk = circuit.to_vk();
+        assert_eq!(vk.transcript_hash, transcript_hash);
+        verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+        // The proof shouldn't verify if the verifier uses a different transcript hash.
+        let other_vk = VerificationKey {
+            transcript_hash: if transcript_hash == TranscriptHashType::Blake3 {
+                TranscriptHashType::Rescue
+            } else {
+                TranscriptHashType::Blake3
+            },
+            ..vk
+        };
+        assert!(
+            verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &other_vk, true).is_err()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_proof_bound_to_circuit() -> Result<()> {
+    type F = <Tweedledee as Curve>::ScalarField;
+    // Two circuits of the same size, which differ only in a constant.
+    let build_circuit = |square: usize| {
+        let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+        let x = builder.add_virtual_target();
+        let x_squared = builder.square(x);
+        let c = builder.constant_wire(F::from_canonical_usize(square));
+        let should_be_zero = builder.sub(x_squared, c);
+        builder.assert_zero(should_be_zero);
+        (builder.build(), x)
+    };
+    let (circuit, x) = build_circuit(49);
+    let (other_circuit, _) = build_circuit(64);
+    assert_eq!(circuit.degree(), other_circuit.degree());
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_target(x, F::from_canonical_usize(7));
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    let other_vk = other_circuit.to_vk();
+    assert_eq!(vk.digest(), circuit.vk_digest());
+    assert_ne!(vk.digest(), other_vk.digest());
+
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+    assert!(verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &other_vk, true).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_rescue_hash() -> Result<()> {
     type F = <Tweedledee as Curve>::ScalarField;
@@ -306,6 +385,74 @@ fn test_rescue_hash() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_poseidon_hash() -> Result<()> {
+    type F = <Tweedledee as Curve>::ScalarField;
+    let x = F::rand();
+    let h = poseidon_hash_1_to_1(x, 128);
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let t = builder.add_virtual_target();
+    let h_pur = builder.poseidon_hash_n_to_1(&[t]);
+    let c = builder.constant_wire(h);
+    let should_be_zero = builder.sub(h_pur, c);
+    builder.assert_zero(should_be_zero);
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_target(t, x);
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_merkle_update() -> Result<()> {
+    type F = <Tweedledee as Curve>::ScalarField;
+    let mut tree = SparseMerkleTree::<F>::new(4, 128);
+    tree.update(3, F::rand());
+    let old_root = tree.root();
+    let index = 9;
+    let new_leaf = F::rand();
+    let proof = tree.update(index, new_leaf);
+
+    // Prove that the leaf at the index was empty, and that setting it gives the new root.
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let index_bits: Vec<_> = (0..4).map(|_| builder.add_virtual_bool_target()).collect();
+    let siblings = builder.add_virtual_targets(4);
+    let new_leaf_target = builder.add_virtual_target();
+    let zero = builder.zero_wire();
+    let old_root_target = builder.constant_wire(old_root);
+    let new_root_target = builder.constant_wire(tree.root());
+    builder.verify_merkle_update(
+        &index_bits,
+        &siblings,
+        zero,
+        new_leaf_target,
+        old_root_target,
+        new_root_target,
+    );
+
+    let mut partial_witness = PartialWitness::new();
+    for (i, &bit) in index_bits.iter().enumerate() {
+        partial_witness.set_bool_target(bit, (index >> i) & 1 == 1);
+    }
+    partial_witness.set_targets(&siblings, &proof.siblings);
+    partial_witness.set_target(new_leaf_target, new_leaf);
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
 #[test]
 fn test_curve_add() -> Result<()> {
     type F = <Tweedledee as Curve>::ScalarField;
@@ -383,6 +530,186 @@ fn test_curve_msm() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_curve_mul_fixed_base() -> Result<()> {
+    type SF = <Tweedledum as Curve>::ScalarField;
+    let scalar_value = SF::rand();
+    let point = blake_hash_base_field_to_curve::<Tweedledee>(SF::rand());
+    let expected = Tweedledee::convert(scalar_value.try_convert().unwrap()) * point.to_projective();
+
+    let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+    let scalar = builder.add_virtual_target();
+    let result = builder.curve_mul_fixed_base::<Tweedledee>(point, scalar);
+    let expected_target = builder.constant_affine_point(expected.to_affine());
+    builder.copy_curve(result, expected_target);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_target(scalar, scalar_value);
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledee>(&witness, &[], true)
+        .unwrap();
+
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledum, Tweedledee>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_pedersen_commit() -> Result<()> {
+    type SF = <Tweedledum as Curve>::ScalarField;
+    let generators = PedersenGenerators::<Tweedledee>::new(2);
+    let values = vec![SF::rand(), SF::rand()];
+    let blinding_value = SF::rand();
+    let expected = generators.commit(
+        &SF::try_convert_all(&values).unwrap(),
+        blinding_value.try_convert().unwrap(),
+    );
+
+    let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+    let value_targets = builder.add_virtual_targets(values.len());
+    let blinding = builder.add_virtual_target();
+    let commitment = builder.pedersen_commit(&generators, &value_targets, blinding);
+    let expected_target = builder.constant_affine_point(expected);
+    builder.copy_curve(commitment, expected_target);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_targets(&value_targets, &values);
+    partial_witness.set_target(blinding, blinding_value);
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledee>(&witness, &[], true)
+        .unwrap();
+
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledum, Tweedledee>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_and_mux() -> Result<()> {
+    type F = <Tweedledum as Curve>::ScalarField;
+    let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+    let mut memory = builder.add_memory(8);
+    let addresses = builder.add_virtual_targets(4);
+    let values = builder.add_virtual_targets(4);
+    for (&address, &value) in addresses.iter().zip(values.iter()) {
+        builder.memory_write(&mut memory, address, value);
+    }
+    let read = builder.memory_read(&mut memory, addresses[1]);
+    let index = builder.add_virtual_target();
+    let selected = builder.mux(index, &values);
+    builder.copy(read, selected);
+    builder.assert_memory_consistent(memory);
+
+    let mut partial_witness = PartialWitness::new();
+    let address_values: Vec<F> = [7, 200, 7, 13]
+        .iter()
+        .map(|&a| F::from_canonical_usize(a))
+        .collect();
+    partial_witness.set_targets(&addresses, &address_values);
+    partial_witness.set_targets(&values, &[F::rand(), F::rand(), F::rand(), F::rand()]);
+    partial_witness.set_target(index, F::ONE);
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledee>(&witness, &[], true)
+        .unwrap();
+
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledum, Tweedledee>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_permutation_and_sorting() -> Result<()> {
+    type F = <Tweedledum as Curve>::ScalarField;
+    let keys: Vec<F> = [5, 1, 9, 1, 3]
+        .iter()
+        .map(|&k| F::from_canonical_usize(k))
+        .collect();
+    let payloads: Vec<F> = (0..keys.len()).map(|_| F::rand()).collect();
+
+    let mut builder = CircuitBuilder::<Tweedledum>::new(128);
+    let key_targets = builder.add_virtual_targets(keys.len());
+    let payload_targets = builder.add_virtual_targets(keys.len());
+    for &key in &key_targets {
+        builder.range_check(key, 4);
+    }
+    let bounded: Vec<_> = key_targets
+        .iter()
+        .map(|&target| BoundedTarget {
+            target,
+            max: BigUint::from(15u32),
+        })
+        .collect();
+    let sorted_keys = builder.sort_bounded(&bounded);
+    let rows: Vec<Vec<_>> = key_targets
+        .iter()
+        .zip(payload_targets.iter())
+        .map(|(&key, &payload)| vec![key, payload])
+        .collect();
+    let sorted_rows = builder.sort_rows_by_key(&rows, &BigUint::from(15u32));
+    let sorted_row_keys: Vec<_> = sorted_rows.iter().map(|row| row[0]).collect();
+    let sorted_key_targets: Vec<_> = sorted_keys.iter().map(|key| key.target).collect();
+    builder.assert_permutation(&sorted_row_keys, &key_targets);
+    for (&x, &y) in sorted_key_targets.iter().zip(sorted_row_keys.iter()) {
+        builder.copy(x, y);
+    }
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_targets(&key_targets, &keys);
+    partial_witness.set_targets(&payload_targets, &payloads);
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledee>(&witness, &[], true)
+        .unwrap();
+
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledum, Tweedledee>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_hint() -> Result<()> {
+    type F = <Tweedledee as Curve>::ScalarField;
+    let x_value = F::from_canonical_u32(thread_rng().gen::<u32>());
+
+    // We compute the quotient and remainder of x / 7 with a hint, and then check them.
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let x = builder.add_virtual_target();
+    let outputs = builder.add_hint(&[x], 2, |values| {
+        let x = values[0].to_canonical_u64_vec()[0];
+        vec![F::from_canonical_u64(x / 7), F::from_canonical_u64(x % 7)]
+    });
+    let (quotient, remainder) = (outputs[0], outputs[1]);
+    builder.range_check(quotient, 32);
+    builder.range_check(remainder, 3);
+    let seven = builder.constant_wire_u32(7);
+    let x_recomputed = builder.mul_add(quotient, seven, remainder);
+    builder.copy(x, x_recomputed);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_target(x, x_value);
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
 #[test]
 fn test_base_4_sum() -> Result<()> {
     type C = Tweedledee;
@@ -468,3 +795,365 @@ fn test_curve_double_gate() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_rescue_hash_bn254() -> Result<()> {
+    type F = <Bn254 as Curve>::ScalarField;
+    let x = F::rand();
+    let h = rescue_hash_1_to_1(x, 128);
+    let mut builder = CircuitBuilder::<Bn254>::new(128);
+    let t = builder.add_virtual_target();
+    let h_pur = builder.rescue_hash_n_to_1(&[t]);
+    let c = builder.constant_wire(h);
+    let should_be_zero = builder.sub(h_pur, c);
+    builder.assert_zero(should_be_zero);
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_target(t, x);
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Grumpkin>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Bn254, Grumpkin>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_curve_add_bn254() -> Result<()> {
+    type F = <Bn254 as Curve>::ScalarField;
+
+    let a = blake_hash_base_field_to_curve::<Grumpkin>(F::rand());
+    let b = blake_hash_base_field_to_curve::<Grumpkin>(F::rand());
+    let sum = (a + b).to_affine();
+
+    let mut builder = CircuitBuilder::<Bn254>::new(128);
+
+    let ta = builder.add_virtual_point_target();
+    let tb = builder.add_virtual_point_target();
+    let tsum_purported = builder.curve_add::<Grumpkin>(ta, tb);
+    let tsum_true = builder.constant_affine_point(sum);
+    builder.copy_curve(tsum_purported, tsum_true);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_point_target(ta, a);
+    partial_witness.set_point_target(tb, b);
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+
+    let proof = circuit
+        .generate_proof::<Grumpkin>(&witness, &[], true)
+        .unwrap();
+
+    let vk = circuit.to_vk();
+    verify_proof::<Bn254, Grumpkin>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_foreign_curve_add() -> Result<()> {
+    type FC = Secp256k1;
+    let a = blake_hash_base_field_to_curve::<FC>(<FC as Curve>::BaseField::rand());
+    let b = blake_hash_base_field_to_curve::<FC>(<FC as Curve>::BaseField::rand());
+    let sum = (a + b).to_affine();
+
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let ta = builder.add_virtual_foreign_point_target::<FC>();
+    let tb = builder.add_virtual_foreign_point_target::<FC>();
+    let tsum_purported = builder.foreign_curve_add(&ta, &tb);
+    let tsum_true = builder.constant_foreign_affine_point(sum);
+    builder.copy_foreign_curve(&tsum_purported, &tsum_true);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_foreign_point_target(&ta, a);
+    partial_witness.set_foreign_point_target(&tb, b);
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_range_check_and_bits() -> Result<()> {
+    type F = <Tweedledee as Curve>::ScalarField;
+    let x_value = F::from_canonical_u32(thread_rng().gen::<u32>() >> 7);
+    let y_value = F::rand();
+
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let x = builder.add_virtual_target();
+    let y = builder.add_virtual_target();
+    builder.range_check(x, 25);
+    builder.range_check(x, 26);
+    let y_bits = builder.to_bits_canonical(y);
+    let y_joined = builder.from_bits_le(&y_bits);
+    builder.copy(y, y_joined);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_target(x, x_value);
+    partial_witness.set_target(y, y_value);
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_comparisons() -> Result<()> {
+    type F = <Tweedledee as Curve>::ScalarField;
+    let mut rng = thread_rng();
+    let a_value = F::from_canonical_u32(rng.gen::<u32>() >> 1);
+    let b_value = a_value + F::ONE;
+    let (x_value, y_value) = (F::rand(), F::rand());
+    let (min_value, max_value) = if x_value < y_value {
+        (x_value, y_value)
+    } else {
+        (y_value, x_value)
+    };
+
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let a = builder.add_virtual_target();
+    let b = builder.add_virtual_target();
+    builder.range_check(a, 32);
+    builder.range_check(b, 32);
+    let bound = BigUint::from(u32::MAX);
+    let a = BoundedTarget {
+        target: a,
+        max: bound.clone(),
+    };
+    let b = BoundedTarget {
+        target: b,
+        max: bound,
+    };
+    builder.assert_lt_bounded(&a, &b);
+    let a_lt_b = builder.lt_bounded(&a, &b);
+    builder.assert_one(a_lt_b);
+
+    let x = builder.add_virtual_target();
+    let y = builder.add_virtual_target();
+    let min = builder.min(x, y);
+    let max = builder.max(x, y);
+    let min_expected = builder.constant_wire(min_value);
+    let max_expected = builder.constant_wire(max_value);
+    builder.copy(min, min_expected);
+    builder.copy(max, max_expected);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_target(a.target, a_value);
+    partial_witness.set_target(b.target, b_value);
+    partial_witness.set_target(x, x_value);
+    partial_witness.set_target(y, y_value);
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_bigint_mul_narrow_limbs() -> Result<()> {
+    let limb_bits = 32;
+    let mut rng = thread_rng();
+    let x_value = BigUint::from(rng.gen::<u64>());
+    let y_value = BigUint::from(rng.gen::<u64>());
+
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let max = BigUint::from(u64::MAX);
+    let x = builder.add_virtual_bigint_target_with_limb_bits(&max, true, limb_bits);
+    let y = builder.add_virtual_bigint_target_with_limb_bits(&max, true, limb_bits);
+    let product = builder.bigint_mul(&x, &y);
+    let expected = builder.constant_bigint_with_limb_bits(&(&x_value * &y_value), limb_bits);
+    builder.copy_bigint(&product, &expected);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_bigint_target(&x, &x_value);
+    partial_witness.set_bigint_target(&y, &y_value);
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_rsa_pkcs1v15_sha256() -> Result<()> {
+    // A 512-bit key, which is the smallest size that fits a PKCS#1 v1.5 SHA-256 encoding.
+    let modulus = BigUint::parse_bytes(
+        b"bf683d29b26dc57a178da5a41f424fac9eeba5d8ec341a6a47721f8b2878f0fb\
+          271d4d0753c11b4f445e1512d520a07b852837fdef7fc44c99406d04598f679b",
+        16,
+    )
+    .unwrap();
+    let private_exponent = BigUint::parse_bytes(
+        b"ab9bc3aa8cc50a568aa37ead0531a8c975ba84f955dce909fc807f737e97e045\
+          5cde02fd66493909d38020d73e64f880cf13efafb97a48d159d394a27854a221",
+        16,
+    )
+    .unwrap();
+    let public_key = RsaPublicKey {
+        modulus,
+        exponent: BigUint::from(65537u32),
+    };
+    let digest_value: [u8; 32] = thread_rng().gen();
+    let signature_value = rsa_sign_pkcs1v15_sha256(&public_key, &private_exponent, &digest_value);
+
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let max_digest = (BigUint::from(1u32) << 256) - 1u32;
+    let digest = builder.add_virtual_bigint_target(&max_digest, true);
+    let signature = builder.add_virtual_bigint_target(&(&public_key.modulus - 1u32), true);
+    let modulus = builder.constant_bigint(&public_key.modulus);
+    builder.verify_rsa_pkcs1v15_sha256(
+        &digest,
+        &signature,
+        &modulus,
+        public_key.modulus_len(),
+        &public_key.exponent,
+    );
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_bigint_target(&digest, &BigUint::from_bytes_be(&digest_value));
+    partial_witness.set_bigint_target(&signature, &signature_value);
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+fn test_verify_ecdsa<FC: Curve>() -> Result<()> {
+    type SF<FC> = <FC as Curve>::ScalarField;
+    let secret_key = SF::<FC>::rand();
+    let msg_hash_value = SF::<FC>::rand();
+    let sig_value = ecdsa_sign::<FC>(msg_hash_value, secret_key);
+
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let msg_hash = builder.add_virtual_foreign_field_target::<SF<FC>>();
+    let sig = builder.add_virtual_foreign_ecdsa_signature_target::<FC>();
+    let public_key = builder.add_virtual_foreign_point_target::<FC>();
+    builder.verify_ecdsa(&msg_hash, &sig, &public_key);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_foreign_field_target(&msg_hash, msg_hash_value);
+    partial_witness.set_foreign_field_target(&sig.r, sig_value.r);
+    partial_witness.set_foreign_field_target(&sig.s, sig_value.s);
+    partial_witness.set_foreign_point_target(&public_key, ecdsa_public_key::<FC>(secret_key));
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+#[ignore]
+// This circuit has several million gates, so it takes a lot of time and memory.
+fn test_verify_ecdsa_secp256k1() -> Result<()> {
+    test_verify_ecdsa::<Secp256k1>()
+}
+
+#[test]
+#[ignore]
+// This circuit has several million gates, so it takes a lot of time and memory.
+fn test_verify_ecdsa_bls12_377() -> Result<()> {
+    test_verify_ecdsa::<Bls12377>()
+}
+
+#[test]
+fn test_verify_schnorr() -> Result<()> {
+    type C = Tweedledee;
+    type InnerC = Tweedledum;
+    type SF = <C as Curve>::ScalarField;
+    let secret_key = <InnerC as Curve>::ScalarField::rand();
+    let public_key_value = schnorr_public_key::<InnerC>(secret_key);
+    let message_values = vec![SF::rand(), SF::rand()];
+    let sig_value = schnorr_sign::<InnerC>(&message_values, secret_key, 128);
+
+    let mut builder = CircuitBuilder::<C>::new(128);
+    let message = builder.add_virtual_targets(message_values.len());
+    let sig = builder.add_virtual_schnorr_signature_target::<InnerC>();
+    let public_key = builder.add_virtual_point_target::<InnerC>();
+    builder.verify_schnorr(&message, sig, public_key);
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_targets(&message, &message_values);
+    partial_witness.set_point_target(sig.r, sig_value.r);
+    partial_witness.set_target(sig.s, sig_value.s.try_convert().unwrap());
+    partial_witness.set_point_target(public_key, public_key_value);
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<InnerC>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<C, InnerC>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_u32_arithmetic() -> Result<()> {
+    let mut rng = thread_rng();
+    let x_value = rng.gen::<u32>();
+    let y_value = rng.gen::<u32>();
+    let expected_value = (x_value.wrapping_add(y_value) ^ x_value.rotate_right(7)) & !y_value;
+
+    let mut builder = CircuitBuilder::<Tweedledee>::new(128);
+    let x = builder.add_virtual_u32_target();
+    let y = builder.add_virtual_u32_target();
+    let sum = builder.uint_wrapping_add(x, y);
+    let x_rotated = builder.uint_rotate_right(x, 7);
+    let xor = builder.uint_xor(sum, x_rotated);
+    let not_y = builder.uint_not(y);
+    let result = builder.uint_and(xor, not_y);
+    let expected = builder.constant_u32(expected_value);
+    builder.copy(result.target(), expected.target());
+
+    let mut partial_witness = PartialWitness::new();
+    partial_witness.set_u32_target(x, x_value);
+    partial_witness.set_u32_target(y, y_value);
+
+    let circuit = builder.build();
+    let witness = circuit.generate_witness(partial_witness);
+    let proof = circuit
+        .generate_proof::<Tweedledum>(&witness, &[], true)
+        .unwrap();
+    let vk = circuit.to_vk();
+    verify_proof::<Tweedledee, Tweedledum>(&[], &proof, &[], &vk, true)?;
+
+    Ok(())
+}
@@ -24,9 +24,10 @@ fn test_proof_trivial_recursive() -> Result<()> {
         0,
     );
     let mut recursion_inputs = PartialWitness::new();
-    if let Err(e) = recursion_circuit
-        .proof
-        .populate_witness(&mut recursion_inputs, inner_proof)
+    if let Err(e) =
+        recursion_circuit
+            .proof
+            .populate_witness(&mut recursion_inputs, inner_proof, &[])
     {
         panic!("Failed to populate inputs: {:?}", e);
     }